@@ -2,15 +2,18 @@ use core::{
 	mem::{
 		ManuallyDrop, MaybeUninit,
 	},
-	ptr::NonNull,
+	pin::Pin,
+	ptr::{
+		addr_of_mut, NonNull,
+	},
 };
 use cppdvt::{
-	VtObject, VTablePtr, VtObjectPtr,
-	this_to_self, vtable, virtual_fn, virtual_call,
+	Init, VtImpl, VtObject, VTablePtr, VtObjectPtr,
+	init_with, pin_init, this_to_self, vtable, virtual_fn, virtual_call,
 };
 
 fn main() {
-	let mut stack = VecStack::new();
+	pin_init!(let mut stack = VecStack::new());
 	assert_eq!(stack.len(), 0);
 
 	assert_eq!(stack.pop(), None);
@@ -47,34 +50,16 @@ struct VecStack<T> {
 	stack: Vec<T>,
 }
 
-impl<T: 'static> Default for VecStack<T> {
-	fn default() -> Self {
-		Self::new()
-	}
-}
-
 impl<T: 'static> VecStack<T> {
-	pub const fn new() -> Self {
-		Self {
-			vtable: VTablePtr::from_ref(Self::VTABLE),
-			stack: Vec::new(),
+	pub fn new() -> impl Init<Self> {
+		unsafe {
+			init_with(|slot: NonNull<Self>| {
+				Self::write_vtable(slot);
+				addr_of_mut!((*slot.as_ptr()).stack).write(Vec::new());
+			})
 		}
 	}
 
-	pub const fn as_object(&self) -> &VtObject<StackVt<T>> {
-		unsafe { VtObject::from_ptr(VtObjectPtr::from_ref(&self.vtable)) }
-	}
-
-	pub const fn as_mut_object(&mut self) -> &mut VtObject<StackVt<T>> {
-		unsafe { VtObject::from_ptr_mut(VtObjectPtr::from_mut(&mut self.vtable)) }
-	}
-
-	const VTABLE: &StackVt<T> = &StackVt {
-		push: Self::push,
-		pop: Self::pop,
-		length: Self::length,
-	};
-
 	virtual_fn! {
 		fn push(this: VtObjectPtr<StackVt<T>>, value: NonNull<T>) {
 			let this = this_to_self!(mut this);
@@ -99,25 +84,31 @@ impl<T: 'static> VecStack<T> {
 	}
 }
 
-impl<T: 'static> AsRef<VtObject<StackVt<T>>> for VecStack<T> {
-	fn as_ref(&self) -> &VtObject<StackVt<T>> {
-		self.as_object()
-	}
+unsafe impl<T: 'static> VtImpl<StackVt<T>> for VecStack<T> {
+	const VTABLE: &'static StackVt<T> = &StackVt {
+		push: Self::push,
+		pop: Self::pop,
+		length: Self::length,
+	};
 }
-impl<T: 'static> AsMut<VtObject<StackVt<T>>> for VecStack<T> {
-	fn as_mut(&mut self) -> &mut VtObject<StackVt<T>> {
-		self.as_mut_object()
+
+impl<T: 'static> AsRef<VtObject<StackVt<T>>> for Pin<&mut VecStack<T>> {
+	fn as_ref(&self) -> &VtObject<StackVt<T>> {
+		Pin::as_ref(self).vt_object()
 	}
 }
+trait Stack<T: 'static>: AsRef<VtObject<StackVt<T>>> {
+	/// # Safety
+	/// See [`VtImpl::vt_object_mut`].
+	unsafe fn vt_object_mut(&mut self) -> &mut VtObject<StackVt<T>>;
 
-trait Stack<T>: AsRef<VtObject<StackVt<T>>> + AsMut<VtObject<StackVt<T>>> {
 	fn push(&mut self, value: T) {
 		let value = ManuallyDrop::new(value);
-		unsafe { virtual_call!(mut self.as_mut() => push(NonNull::from_ref(&value))) }
+		unsafe { virtual_call!(mut <VecStack<T>> self.vt_object_mut() => push(NonNull::from_ref(&value))) }
 	}
 	fn pop_into(&mut self, slot: &mut Option<T>) {
 		let mut value = MaybeUninit::uninit();
-		let is_some = unsafe { virtual_call!(mut self.as_mut() => pop(NonNull::new_unchecked(value.as_mut_ptr()))) };
+		let is_some = unsafe { virtual_call!(mut <VecStack<T>> self.vt_object_mut() => pop(NonNull::new_unchecked(value.as_mut_ptr()))) };
 		if is_some {
 			unsafe { *slot = Some(value.assume_init()) }
 		}
@@ -128,11 +119,12 @@ trait Stack<T>: AsRef<VtObject<StackVt<T>>> + AsMut<VtObject<StackVt<T>>> {
 		slot
 	}
 	fn len(&self) -> usize {
-		unsafe { virtual_call!(self.as_ref() => length()) }
+		unsafe { virtual_call!(<VecStack<T>> self.as_ref() => length()) }
 	}
 }
 
-impl<T, S: ?Sized> Stack<T> for S
-where
-	S: AsRef<VtObject<StackVt<T>>> + AsMut<VtObject<StackVt<T>>>,
-{}
+impl<T: 'static> Stack<T> for Pin<&mut VecStack<T>> {
+	unsafe fn vt_object_mut(&mut self) -> &mut VtObject<StackVt<T>> {
+		unsafe { Pin::as_mut(self).vt_object_mut() }
+	}
+}
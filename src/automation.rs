@@ -0,0 +1,1021 @@
+use ::core::{
+	alloc::Layout,
+	ffi::c_void,
+	fmt,
+	mem::{
+		size_of, ManuallyDrop,
+	},
+	ptr::{
+		self, NonNull,
+	},
+	slice,
+	sync::atomic::{
+		AtomicPtr, Ordering,
+	},
+};
+use ::alloc::vec::Vec;
+
+use super::{
+	ComClass, ComObject, ComPtr, GUID, HRESULT, IUnknownVt, Interface, VtObjectPtr,
+};
+
+/// Functions that allocate and free the memory of [`BSTR`]s.
+/// 
+/// COM hands `BSTR`s across modules, so every module must use the same allocator,
+/// which is `SysAllocStringLen` and `SysFreeString` on Windows.
+/// Elsewhere, the default allocator is the global allocator of Rust,
+/// which can be replaced with [`set_bstr_allocator`].
+#[derive(Debug, Clone, Copy)]
+pub struct BstrAllocator {
+	/// Allocate a string of `len` UTF-16 units with its length prefix and nul terminator,
+	/// returning a pointer to the units, or null on failure.
+	pub alloc: unsafe fn(len: u32) -> *mut u16,
+	/// Free a string returned by [`BstrAllocator::alloc`].
+	pub free: unsafe fn(ptr: *mut u16),
+}
+
+#[cfg(windows)]
+#[link(name = "oleaut32")]
+unsafe extern "system" {
+	fn SysAllocStringLen(chars: *const u16, len: u32) -> *mut u16;
+	fn SysFreeString(ptr: *mut u16);
+}
+
+impl BstrAllocator {
+	/// The allocator of the system on Windows, and the global allocator of Rust elsewhere.
+	#[cfg(windows)]
+	pub const DEFAULT: Self = Self {
+		alloc: |len| unsafe { SysAllocStringLen(ptr::null(), len) },
+		free: |ptr| unsafe { SysFreeString(ptr) },
+	};
+
+	/// The allocator of the system on Windows, and the global allocator of Rust elsewhere.
+	#[cfg(not(windows))]
+	pub const DEFAULT: Self = Self {
+		alloc: Self::rust_alloc,
+		free: Self::rust_free,
+	};
+
+	#[cfg(not(windows))]
+	fn rust_layout(len: u32) -> Layout {
+		let size = size_of::<u32>() + (len as usize + 1) * size_of::<u16>();
+		Layout::from_size_align(size, size_of::<u32>()).unwrap()
+	}
+
+	#[cfg(not(windows))]
+	unsafe fn rust_alloc(len: u32) -> *mut u16 {
+		let Some(bytes) = len.checked_mul(2) else {
+			return ptr::null_mut();
+		};
+		let prefix = unsafe { ::alloc::alloc::alloc(Self::rust_layout(len)) }.cast::<u32>();
+		if prefix.is_null() {
+			return ptr::null_mut();
+		}
+		unsafe {
+			prefix.write(bytes);
+			let chars = prefix.add(1).cast::<u16>();
+			chars.add(len as usize).write(0);
+			chars
+		}
+	}
+
+	#[cfg(not(windows))]
+	unsafe fn rust_free(ptr: *mut u16) {
+		if !ptr.is_null() {
+			unsafe {
+				let prefix = ptr.cast::<u32>().sub(1);
+				::alloc::alloc::dealloc(prefix.cast(), Self::rust_layout(prefix.read() / 2));
+			}
+		}
+	}
+}
+
+static BSTR_ALLOCATOR: AtomicPtr<BstrAllocator> =
+	AtomicPtr::new(&BstrAllocator::DEFAULT as *const BstrAllocator as *mut BstrAllocator);
+
+/// Replace the allocator of [`BSTR`]s, such as with the `SysAllocStringLen`
+/// and `SysFreeString` of a host that provides them.
+/// 
+/// # Safety
+/// No `BSTR` may be alive when the allocator is replaced.
+pub unsafe fn set_bstr_allocator(allocator: &'static BstrAllocator) {
+	BSTR_ALLOCATOR.store(allocator as *const BstrAllocator as *mut BstrAllocator, Ordering::Release);
+}
+
+fn bstr_allocator() -> &'static BstrAllocator {
+	// SAFETY: The pointer always comes from a `&'static BstrAllocator`.
+	unsafe { &*BSTR_ALLOCATOR.load(Ordering::Acquire) }
+}
+
+/// Owned, length-prefixed UTF-16 string of COM Automation.
+/// 
+/// A null `BSTR` is an empty string.
+/// 
+/// # Layout
+/// This type has the same layout and ABI as a pointer to the first UTF-16 unit.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::BSTR;
+/// let greeting = BSTR::from("héllo");
+/// assert_eq!(greeting.len(), 5);
+/// assert_eq!(greeting.as_wide()[1], 'é' as u16);
+/// assert_eq!(greeting.to_string(), "héllo");
+/// assert!(BSTR::new().is_empty());
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[repr(transparent)]
+pub struct BSTR(*mut u16);
+
+impl BSTR {
+	/// Return an empty, null `BSTR`.
+	pub const fn new() -> Self {
+		Self(ptr::null_mut())
+	}
+
+	/// Allocate a `BSTR` with the UTF-16 units `chars`.
+	/// 
+	/// # Panics
+	/// Panics if the allocation fails.
+	pub fn from_wide(chars: &[u16]) -> Self {
+		if chars.is_empty() {
+			return Self::new();
+		}
+		let len = u32::try_from(chars.len()).expect("string is too long for a BSTR");
+		let ptr = unsafe { (bstr_allocator().alloc)(len) };
+		assert!(!ptr.is_null(), "could not allocate a BSTR");
+		unsafe { ptr.copy_from_nonoverlapping(chars.as_ptr(), chars.len()) };
+		Self(ptr)
+	}
+
+	/// Take ownership of a `BSTR` returned by COM.
+	/// 
+	/// # Safety
+	/// `ptr` must be null, or a `BSTR` allocated by the current [`BstrAllocator`]
+	/// that is not owned by anything else.
+	pub const unsafe fn from_raw(ptr: *mut u16) -> Self {
+		Self(ptr)
+	}
+
+	/// Consume the `BSTR` without freeing it, returning the pointer.
+	pub fn into_raw(self) -> *mut u16 {
+		ManuallyDrop::new(self).0
+	}
+
+	/// Return the pointer to the first UTF-16 unit, which may be null.
+	pub const fn as_ptr(&self) -> *const u16 {
+		self.0
+	}
+
+	/// Return the number of UTF-16 units.
+	pub fn len(&self) -> usize {
+		if self.0.is_null() {
+			0
+		} else {
+			// SAFETY: Non-null `BSTR`s are preceded by their length in bytes.
+			unsafe { self.0.cast::<u32>().sub(1).read() as usize / 2 }
+		}
+	}
+
+	/// Return `true` if the string is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Return the UTF-16 units of the string.
+	pub fn as_wide(&self) -> &[u16] {
+		if self.0.is_null() {
+			&[]
+		} else {
+			// SAFETY: The string has `len` units.
+			unsafe { slice::from_raw_parts(self.0, self.len()) }
+		}
+	}
+}
+
+impl Default for BSTR {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Clone for BSTR {
+	fn clone(&self) -> Self {
+		Self::from_wide(self.as_wide())
+	}
+}
+
+impl Drop for BSTR {
+	fn drop(&mut self) {
+		if !self.0.is_null() {
+			unsafe { (bstr_allocator().free)(self.0) };
+		}
+	}
+}
+
+impl PartialEq for BSTR {
+	fn eq(&self, other: &Self) -> bool {
+		self.as_wide() == other.as_wide()
+	}
+}
+
+impl Eq for BSTR {}
+
+impl From<&str> for BSTR {
+	fn from(s: &str) -> Self {
+		Self::from_wide(&s.encode_utf16().collect::<Vec<_>>())
+	}
+}
+
+impl fmt::Display for BSTR {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		char::decode_utf16(self.as_wide().iter().copied())
+			.map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+			.try_for_each(|c| fmt::Write::write_char(f, c))
+	}
+}
+
+impl fmt::Debug for BSTR {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "\"{self}\"")
+	}
+}
+
+/// Type tag of a [`VARIANT`], `VARTYPE`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct VarType(pub u16);
+
+impl VarType {
+	/// No value.
+	pub const EMPTY: Self = Self(0);
+	/// SQL-style null.
+	pub const NULL: Self = Self(1);
+	/// `i16`.
+	pub const I2: Self = Self(2);
+	/// `i32`.
+	pub const I4: Self = Self(3);
+	/// `f32`.
+	pub const R4: Self = Self(4);
+	/// `f64`.
+	pub const R8: Self = Self(5);
+	/// [`BSTR`].
+	pub const BSTR: Self = Self(8);
+	/// `IDispatch`.
+	pub const DISPATCH: Self = Self(9);
+	/// [`HRESULT`], usually for missing optional arguments.
+	pub const ERROR: Self = Self(10);
+	/// `VARIANT_BOOL`, which is `-1` for true.
+	pub const BOOL: Self = Self(11);
+	/// `IUnknown`.
+	pub const UNKNOWN: Self = Self(13);
+	/// `i8`.
+	pub const I1: Self = Self(16);
+	/// `u8`.
+	pub const UI1: Self = Self(17);
+	/// `u16`.
+	pub const UI2: Self = Self(18);
+	/// `u32`.
+	pub const UI4: Self = Self(19);
+	/// `i64`.
+	pub const I8: Self = Self(20);
+	/// `u64`.
+	pub const UI8: Self = Self(21);
+	/// C `int`.
+	pub const INT: Self = Self(22);
+	/// C `unsigned int`.
+	pub const UINT: Self = Self(23);
+	/// Flag for a [`SAFEARRAY`] of the type.
+	pub const ARRAY: Self = Self(0x2000);
+	/// Flag for a pointer to the type.
+	pub const BYREF: Self = Self(0x4000);
+}
+
+/// Bounds of a dimension of a [`SAFEARRAY`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SAFEARRAYBOUND {
+	/// Number of elements.
+	pub elements: u32,
+	/// Index of the first element.
+	pub lower_bound: i32,
+}
+
+/// Header of a multi-dimensional array of COM Automation.
+/// 
+/// The bounds are stored from the last dimension to the first.
+/// Arrays are created and destroyed by their owners, so [`VARIANT`]s never free them.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
+#[repr(C)]
+pub struct SAFEARRAY {
+	/// Number of dimensions.
+	pub dims: u16,
+	/// `FADF_*` flags.
+	pub features: u16,
+	/// Size of an element in bytes.
+	pub element_size: u32,
+	/// Number of locks on the array.
+	pub locks: u32,
+	/// The elements.
+	pub data: *mut c_void,
+	/// Bounds of the dimensions, of which there are `dims`.
+	pub bounds: [SAFEARRAYBOUND; 1],
+}
+
+impl SAFEARRAY {
+	/// Return the bounds of the dimensions, from the last dimension to the first.
+	/// 
+	/// # Safety
+	/// The array must be followed by the bounds of all of its dimensions.
+	pub unsafe fn bounds(&self) -> &[SAFEARRAYBOUND] {
+		unsafe { slice::from_raw_parts(self.bounds.as_ptr(), self.dims as usize) }
+	}
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+union VariantData {
+	i64: i64,
+	f64: f64,
+	ptr: *mut c_void,
+	record: [*mut c_void; 2],
+}
+
+/// Tagged value of COM Automation, which owns its [`BSTR`] and interface pointers.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{VARIANT, VarType};
+/// let number = VARIANT::from(42);
+/// assert_eq!(number.vt(), VarType::I4);
+/// assert_eq!(number.as_i64(), Some(42));
+/// assert_eq!(number.as_f64(), Some(42.0));
+/// 
+/// let text = VARIANT::from("forty-two");
+/// assert_eq!(text.as_bstr().unwrap().to_string(), "forty-two");
+/// assert_eq!(text.as_i64(), None);
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[repr(C)]
+pub struct VARIANT {
+	vt: VarType,
+	reserved: [u16; 3],
+	data: VariantData,
+}
+
+impl VARIANT {
+	/// Return an empty `VARIANT`.
+	pub const fn new() -> Self {
+		Self {
+			vt: VarType::EMPTY,
+			reserved: [0; 3],
+			data: VariantData { record: [ptr::null_mut(); 2] },
+		}
+	}
+
+	/// Create a `VARIANT` from its type and the bytes of its value.
+	/// 
+	/// # Safety
+	/// `value` must be a valid value of type `vt`, whose ownership is transferred.
+	pub unsafe fn from_raw<V: Copy>(vt: VarType, value: V) -> Self {
+		const { assert!(size_of::<V>() <= size_of::<VariantData>()) };
+		let mut variant = Self::new();
+		unsafe { ptr::addr_of_mut!(variant.data).cast::<V>().write(value) };
+		variant.vt = vt;
+		variant
+	}
+
+	/// Return the type of the value.
+	pub const fn vt(&self) -> VarType {
+		self.vt
+	}
+
+	/// Return the value as a `V`.
+	/// 
+	/// # Safety
+	/// `V` must be the type of the value, as given by [`VARIANT::vt`].
+	pub unsafe fn get<V: Copy>(&self) -> V {
+		const { assert!(size_of::<V>() <= size_of::<VariantData>()) };
+		unsafe { ptr::addr_of!(self.data).cast::<V>().read() }
+	}
+
+	/// Return the value if it is an integer that fits in an `i64`.
+	pub fn as_i64(&self) -> Option<i64> {
+		unsafe {
+			Some(match self.vt {
+				VarType::I1 => self.get::<i8>().into(),
+				VarType::I2 => self.get::<i16>().into(),
+				VarType::I4 | VarType::INT => self.get::<i32>().into(),
+				VarType::I8 => self.get::<i64>(),
+				VarType::UI1 => self.get::<u8>().into(),
+				VarType::UI2 => self.get::<u16>().into(),
+				VarType::UI4 | VarType::UINT => self.get::<u32>().into(),
+				VarType::UI8 => self.get::<u64>().try_into().ok()?,
+				_ => return None,
+			})
+		}
+	}
+
+	/// Return the value if it is a number.
+	pub fn as_f64(&self) -> Option<f64> {
+		match self.vt {
+			VarType::R4 => Some(unsafe { self.get::<f32>() }.into()),
+			VarType::R8 => Some(unsafe { self.get::<f64>() }),
+			_ => self.as_i64().map(|value| value as f64),
+		}
+	}
+
+	/// Return the value if it is a `VARIANT_BOOL`.
+	pub fn as_bool(&self) -> Option<bool> {
+		(self.vt == VarType::BOOL).then(|| unsafe { self.get::<i16>() } != 0)
+	}
+
+	/// Return the value if it is a [`BSTR`].
+	pub fn as_bstr(&self) -> Option<&BSTR> {
+		// SAFETY: `BSTR` is a transparent pointer.
+		(self.vt == VarType::BSTR).then(|| unsafe { &*ptr::addr_of!(self.data).cast::<BSTR>() })
+	}
+
+	/// Return a new reference to the value if it is a non-null `IDispatch`.
+	pub fn to_dispatch(&self) -> Option<ComPtr<IDispatchVt>> {
+		if self.vt != VarType::DISPATCH {
+			return None;
+		}
+		let ptr = NonNull::new(unsafe { self.get::<*mut c_void>() })?;
+		// SAFETY: The value is an `IDispatch`.
+		Some(unsafe { ComPtr::from_raw_borrowed(ptr.cast()) })
+	}
+
+	/// Return a new reference to the value if it is a non-null `IUnknown` or `IDispatch`.
+	pub fn to_unknown(&self) -> Option<ComPtr<IUnknownVt>> {
+		if !matches!(self.vt, VarType::UNKNOWN | VarType::DISPATCH) {
+			return None;
+		}
+		let ptr = NonNull::new(unsafe { self.get::<*mut c_void>() })?;
+		// SAFETY: `IDispatch` starts with `IUnknown`.
+		Some(unsafe { ComPtr::from_raw_borrowed(ptr.cast()) })
+	}
+
+	/// Return the array if the value is a [`SAFEARRAY`], which the `VARIANT` does not own.
+	pub fn as_array(&self) -> Option<NonNull<SAFEARRAY>> {
+		if self.vt.0 & (VarType::ARRAY.0 | VarType::BYREF.0) == VarType::ARRAY.0 {
+			NonNull::new(unsafe { self.get::<*mut SAFEARRAY>() })
+		} else {
+			None
+		}
+	}
+}
+
+impl Default for VARIANT {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Clone for VARIANT {
+	fn clone(&self) -> Self {
+		if let Some(bstr) = self.as_bstr() {
+			return bstr.clone().into();
+		}
+		if let Some(unknown) = self.to_unknown() {
+			::core::mem::forget(unknown);
+		}
+		Self { vt: self.vt, reserved: self.reserved, data: self.data }
+	}
+}
+
+impl Drop for VARIANT {
+	fn drop(&mut self) {
+		match self.vt {
+			VarType::BSTR => drop(unsafe { BSTR::from_raw(self.get()) }),
+			VarType::UNKNOWN | VarType::DISPATCH => {
+				if let Some(ptr) = NonNull::new(unsafe { self.get::<*mut c_void>() }) {
+					drop(unsafe { ComPtr::<IUnknownVt>::from_raw(ptr.cast()) });
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+impl fmt::Debug for VARIANT {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut f = f.debug_tuple("VARIANT");
+		f.field(&self.vt);
+		if let Some(value) = self.as_i64() {
+			f.field(&value);
+		} else if let Some(value) = self.as_f64() {
+			f.field(&value);
+		} else if let Some(value) = self.as_bool() {
+			f.field(&value);
+		} else if let Some(value) = self.as_bstr() {
+			f.field(value);
+		}
+		f.finish()
+	}
+}
+
+macro_rules! variant_from {
+	($($T:ty => $vt:ident),* $(,)?) => {
+		$(
+			impl From<$T> for VARIANT {
+				fn from(value: $T) -> Self {
+					// SAFETY: The value has the type of the tag.
+					unsafe { Self::from_raw(VarType::$vt, value) }
+				}
+			}
+		)*
+	};
+}
+
+variant_from! {
+	i8 => I1,
+	i16 => I2,
+	i32 => I4,
+	i64 => I8,
+	u8 => UI1,
+	u16 => UI2,
+	u32 => UI4,
+	u64 => UI8,
+	f32 => R4,
+	f64 => R8,
+}
+
+impl From<bool> for VARIANT {
+	fn from(value: bool) -> Self {
+		// SAFETY: `VARIANT_BOOL` is an `i16`.
+		unsafe { Self::from_raw(VarType::BOOL, if value { -1i16 } else { 0 }) }
+	}
+}
+
+impl From<BSTR> for VARIANT {
+	fn from(value: BSTR) -> Self {
+		// SAFETY: The ownership of the `BSTR` is transferred.
+		unsafe { Self::from_raw(VarType::BSTR, value.into_raw()) }
+	}
+}
+
+impl From<&str> for VARIANT {
+	fn from(value: &str) -> Self {
+		BSTR::from(value).into()
+	}
+}
+
+impl From<ComPtr<IUnknownVt>> for VARIANT {
+	fn from(value: ComPtr<IUnknownVt>) -> Self {
+		// SAFETY: The ownership of the reference is transferred.
+		unsafe { Self::from_raw(VarType::UNKNOWN, ComPtr::into_raw(value)) }
+	}
+}
+
+impl From<ComPtr<IDispatchVt>> for VARIANT {
+	fn from(value: ComPtr<IDispatchVt>) -> Self {
+		// SAFETY: The ownership of the reference is transferred.
+		unsafe { Self::from_raw(VarType::DISPATCH, ComPtr::into_raw(value)) }
+	}
+}
+
+/// Arguments of `IDispatch::Invoke`, in reverse order.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
+#[repr(C)]
+pub struct DISPPARAMS {
+	/// The arguments, from the last to the first.
+	pub args: *mut VARIANT,
+	/// DISPIDs of the named arguments, which are the first of `args`.
+	pub named_args: *mut i32,
+	/// Number of arguments.
+	pub arg_count: u32,
+	/// Number of named arguments.
+	pub named_arg_count: u32,
+}
+
+/// Description of an exception raised by `IDispatch::Invoke`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
+#[repr(C)]
+pub struct EXCEPINFO {
+	/// Error code, or 0 if `scode` is used.
+	pub code: u16,
+	/// Reserved.
+	pub reserved: u16,
+	/// Name of the source of the exception.
+	pub source: BSTR,
+	/// Description of the exception.
+	pub description: BSTR,
+	/// Path of the help file.
+	pub help_file: BSTR,
+	/// Help context ID.
+	pub help_context: u32,
+	/// Reserved.
+	pub reserved_ptr: *mut c_void,
+	/// Function that fills in the rest of the structure.
+	pub deferred_fill_in: Option<unsafe extern "system" fn(info: *mut EXCEPINFO) -> HRESULT>,
+	/// Error code, or 0 if `code` is used.
+	pub scode: HRESULT,
+}
+
+/// Flags of `IDispatch::Invoke` that say how a member is used.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct DispatchFlags(pub u16);
+
+impl DispatchFlags {
+	/// The member is called as a method.
+	pub const METHOD: Self = Self(1);
+	/// The member is read as a property.
+	pub const PROPERTY_GET: Self = Self(2);
+	/// The member is assigned as a property.
+	pub const PROPERTY_PUT: Self = Self(4);
+	/// The member is assigned a reference as a property.
+	pub const PROPERTY_PUT_REF: Self = Self(8);
+
+	/// Return `true` if any of the flags in `other` are set.
+	pub const fn intersects(self, other: Self) -> bool {
+		self.0 & other.0 != 0
+	}
+}
+
+/// VTable of `IDispatch`, the interface of COM Automation for late binding.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct IDispatchVt {
+	/// Methods of `IUnknown`.
+	pub base: IUnknownVt,
+	/// Write the number of type information interfaces, 0 or 1, to `count`.
+	pub get_type_info_count: unsafe extern "system" fn(this: VtObjectPtr<IDispatchVt>, count: *mut u32) -> HRESULT,
+	/// Write the `ITypeInfo` at `index` to `info`.
+	pub get_type_info: unsafe extern "system" fn(
+		this: VtObjectPtr<IDispatchVt>,
+		index: u32,
+		lcid: u32,
+		info: *mut *mut c_void,
+	) -> HRESULT,
+	/// Map a member name followed by the names of its parameters to DISPIDs.
+	pub get_ids_of_names: unsafe extern "system" fn(
+		this: VtObjectPtr<IDispatchVt>,
+		iid: *const GUID,
+		names: *const *const u16,
+		count: u32,
+		lcid: u32,
+		ids: *mut i32,
+	) -> HRESULT,
+	/// Call the member `id`.
+	pub invoke: unsafe extern "system" fn(
+		this: VtObjectPtr<IDispatchVt>,
+		id: i32,
+		iid: *const GUID,
+		lcid: u32,
+		flags: DispatchFlags,
+		params: *mut DISPPARAMS,
+		result: *mut VARIANT,
+		exception: *mut EXCEPINFO,
+		arg_error: *mut u32,
+	) -> HRESULT,
+}
+
+impl IDispatchVt {
+	/// DISPID of unknown names.
+	pub const DISPID_UNKNOWN: i32 = -1;
+	/// DISPID of the named argument that is the value of a property assignment.
+	pub const DISPID_PROPERTYPUT: i32 = -3;
+}
+
+// SAFETY: `IDispatchVt` is the VTable of `IDispatch`.
+unsafe impl Interface for IDispatchVt {
+	const IID: GUID = GUID::from_u128(0x00020400_0000_0000_C000_000000000046);
+}
+
+impl HRESULT {
+	/// The member does not exist.
+	pub const DISP_E_MEMBERNOTFOUND: Self = Self(0x80020003_u32 as i32);
+	/// An argument has the wrong type.
+	pub const DISP_E_TYPEMISMATCH: Self = Self(0x80020005_u32 as i32);
+	/// A name is not known.
+	pub const DISP_E_UNKNOWNNAME: Self = Self(0x80020006_u32 as i32);
+	/// The member does not take named arguments.
+	pub const DISP_E_NONAMEDARGS: Self = Self(0x80020007_u32 as i32);
+	/// The member raised an exception.
+	pub const DISP_E_EXCEPTION: Self = Self(0x80020009_u32 as i32);
+	/// The member was given the wrong number of arguments.
+	pub const DISP_E_BADPARAMCOUNT: Self = Self(0x8002000E_u32 as i32);
+}
+
+impl ComPtr<IDispatchVt> {
+	/// Return the DISPID of the member `name`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `GetIDsOfNames`,
+	/// such as [`HRESULT::DISP_E_UNKNOWNNAME`] if there is no such member.
+	pub fn get_id(&self, name: &str) -> Result<i32, HRESULT> {
+		let name = name.encode_utf16().chain([0]).collect::<Vec<_>>();
+		let mut id = IDispatchVt::DISPID_UNKNOWN;
+		let this = ComPtr::as_raw(self);
+		unsafe { (self.vtable().get_ids_of_names)(this, &GUID::ZERO, &name.as_ptr(), 1, 0, &mut id) }.ok()?;
+		Ok(id)
+	}
+
+	/// Call the member `id` with the arguments `args` in their natural order.
+	/// 
+	/// For property assignments, the last argument is the value.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `Invoke`.
+	pub fn invoke(&self, id: i32, flags: DispatchFlags, args: &[VARIANT]) -> Result<VARIANT, HRESULT> {
+		// `Invoke` does not take ownership of the arguments, so they are copied bitwise.
+		let mut reversed = args.iter()
+			.rev()
+			.map(|arg| ManuallyDrop::new(unsafe { ptr::read(arg) }))
+			.collect::<Vec<_>>();
+		let mut put_id = IDispatchVt::DISPID_PROPERTYPUT;
+		let is_put = flags.intersects(DispatchFlags(DispatchFlags::PROPERTY_PUT.0 | DispatchFlags::PROPERTY_PUT_REF.0));
+		let mut params = DISPPARAMS {
+			args: reversed.as_mut_ptr().cast(),
+			named_args: if is_put { &mut put_id } else { ptr::null_mut() },
+			arg_count: reversed.len() as u32,
+			named_arg_count: is_put as u32,
+		};
+		let mut result = VARIANT::new();
+		let this = ComPtr::as_raw(self);
+		unsafe {
+			(self.vtable().invoke)(
+				this, id, &GUID::ZERO, 0, flags, &mut params, &mut result, ptr::null_mut(), ptr::null_mut(),
+			)
+		}.ok()?;
+		Ok(result)
+	}
+
+	/// Call the method `name` with the arguments `args` in their natural order.
+	/// 
+	/// # Errors
+	/// Returns the errors of [`get_id`](Self::get_id) and [`invoke`](Self::invoke).
+	pub fn call(&self, name: &str, args: &[VARIANT]) -> Result<VARIANT, HRESULT> {
+		self.invoke(self.get_id(name)?, DispatchFlags::METHOD, args)
+	}
+
+	/// Read the property `name`.
+	/// 
+	/// # Errors
+	/// Returns the errors of [`get_id`](Self::get_id) and [`invoke`](Self::invoke).
+	pub fn get(&self, name: &str) -> Result<VARIANT, HRESULT> {
+		self.invoke(self.get_id(name)?, DispatchFlags::PROPERTY_GET, &[])
+	}
+
+	/// Assign `value` to the property `name`.
+	/// 
+	/// # Errors
+	/// Returns the errors of [`get_id`](Self::get_id) and [`invoke`](Self::invoke).
+	pub fn put(&self, name: &str, value: VARIANT) -> Result<(), HRESULT> {
+		self.invoke(self.get_id(name)?, DispatchFlags::PROPERTY_PUT, &[value]).map(drop)
+	}
+}
+
+/// Arguments of a call to a [`DispatchMethod`], in their natural order.
+#[derive(Debug)]
+pub struct DispatchArgs<'a> {
+	flags: DispatchFlags,
+	reversed: &'a [VARIANT],
+}
+
+impl<'a> DispatchArgs<'a> {
+	/// Return how the member is used.
+	pub fn flags(&self) -> DispatchFlags {
+		self.flags
+	}
+
+	/// Return the number of arguments.
+	pub fn len(&self) -> usize {
+		self.reversed.len()
+	}
+
+	/// Return `true` if there are no arguments.
+	pub fn is_empty(&self) -> bool {
+		self.reversed.is_empty()
+	}
+
+	/// Return the argument at `index`.
+	pub fn get(&self, index: usize) -> Option<&'a VARIANT> {
+		self.reversed.len().checked_sub(index + 1).map(|index| &self.reversed[index])
+	}
+
+	/// Return the argument at `index`, or [`HRESULT::DISP_E_BADPARAMCOUNT`] if it is missing.
+	/// 
+	/// # Errors
+	/// Returns [`HRESULT::DISP_E_BADPARAMCOUNT`] if there are not enough arguments.
+	pub fn arg(&self, index: usize) -> Result<&'a VARIANT, HRESULT> {
+		self.get(index).ok_or(HRESULT::DISP_E_BADPARAMCOUNT)
+	}
+
+	/// Iterate over the arguments.
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a VARIANT> + 'a {
+		self.reversed.iter().rev()
+	}
+}
+
+/// Member of a [`Dispatch`] type, which is called by name through `IDispatch`.
+pub struct DispatchMethod<T> {
+	/// Name of the member, which is matched without regard to ASCII case.
+	pub name: &'static str,
+	/// Call the member.
+	pub invoke: fn(this: &T, args: &DispatchArgs<'_>) -> Result<VARIANT, HRESULT>,
+}
+
+impl<T> fmt::Debug for DispatchMethod<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DispatchMethod")
+			.field("name", &self.name)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Trait for [`ComClass`]es that implement `IDispatch` with a table of named members,
+/// with [`ComObject::DISPATCH`] as the VTable.
+/// 
+/// The DISPID of a member is its index in the table plus one.
+/// Members are called for methods and properties alike,
+/// and can tell them apart with [`DispatchArgs::flags`].
+/// 
+/// # Examples
+/// ```
+/// # use core::cell::Cell;
+/// # use cppdvt::{ComObject, Dispatch, DispatchArgs, DispatchFlags, DispatchMethod, HRESULT, IDispatchVt, VARIANT, com_object};
+/// struct Calculator {
+/// 	memory: Cell<f64>,
+/// }
+/// 
+/// com_object! {
+/// 	unsafe Calculator {
+/// 		IDispatchVt => ComObject::<Calculator>::DISPATCH,
+/// 	}
+/// }
+/// 
+/// impl Dispatch for Calculator {
+/// 	const METHODS: &'static [DispatchMethod<Self>] = &[
+/// 		DispatchMethod {
+/// 			name: "Add",
+/// 			invoke: |_, args| {
+/// 				let a = args.arg(0)?.as_f64().ok_or(HRESULT::DISP_E_TYPEMISMATCH)?;
+/// 				let b = args.arg(1)?.as_f64().ok_or(HRESULT::DISP_E_TYPEMISMATCH)?;
+/// 				Ok((a + b).into())
+/// 			},
+/// 		},
+/// 		DispatchMethod {
+/// 			name: "Memory",
+/// 			invoke: |this, args| {
+/// 				if args.flags().intersects(DispatchFlags::PROPERTY_PUT) {
+/// 					let value = args.arg(0)?.as_f64().ok_or(HRESULT::DISP_E_TYPEMISMATCH)?;
+/// 					this.memory.set(value);
+/// 					Ok(VARIANT::new())
+/// 				} else {
+/// 					Ok(this.memory.get().into())
+/// 				}
+/// 			},
+/// 		},
+/// 	];
+/// }
+/// 
+/// // An in-process client.
+/// let calculator = ComObject::create(Calculator { memory: Cell::new(0.0) })
+/// 	.query_interface::<IDispatchVt>()
+/// 	.unwrap();
+/// let sum = calculator.call("add", &[1.5.into(), 2.into()]).unwrap();
+/// assert_eq!(sum.as_f64(), Some(3.5));
+/// 
+/// calculator.put("Memory", sum).unwrap();
+/// assert_eq!(calculator.get("MEMORY").unwrap().as_f64(), Some(3.5));
+/// assert_eq!(calculator.call("Add", &[1.into()]).unwrap_err(), HRESULT::DISP_E_BADPARAMCOUNT);
+/// assert_eq!(calculator.get_id("Subtract").unwrap_err(), HRESULT::DISP_E_UNKNOWNNAME);
+/// ```
+pub trait Dispatch: ComClass {
+	/// The members, in the order of their DISPIDs.
+	const METHODS: &'static [DispatchMethod<Self>];
+}
+
+impl<T: Dispatch> ComObject<T> {
+	/// VTable of `IDispatch` for `T`, which calls the members in [`Dispatch::METHODS`].
+	pub const DISPATCH: IDispatchVt = IDispatchVt {
+		base: Self::UNKNOWN,
+		get_type_info_count: Self::dispatch_get_type_info_count,
+		get_type_info: Self::dispatch_get_type_info,
+		get_ids_of_names: Self::dispatch_get_ids_of_names,
+		invoke: Self::dispatch_invoke,
+	};
+
+	unsafe extern "system" fn dispatch_get_type_info_count(
+		_this: VtObjectPtr<IDispatchVt>,
+		count: *mut u32,
+	) -> HRESULT {
+		if count.is_null() {
+			return HRESULT::E_POINTER;
+		}
+		unsafe { count.write(0) };
+		HRESULT::S_OK
+	}
+
+	unsafe extern "system" fn dispatch_get_type_info(
+		_this: VtObjectPtr<IDispatchVt>,
+		_index: u32,
+		_lcid: u32,
+		info: *mut *mut c_void,
+	) -> HRESULT {
+		if !info.is_null() {
+			unsafe { info.write(ptr::null_mut()) };
+		}
+		HRESULT::E_NOTIMPL
+	}
+
+	unsafe extern "system" fn dispatch_get_ids_of_names(
+		_this: VtObjectPtr<IDispatchVt>,
+		_iid: *const GUID,
+		names: *const *const u16,
+		count: u32,
+		_lcid: u32,
+		ids: *mut i32,
+	) -> HRESULT {
+		if names.is_null() || ids.is_null() {
+			return HRESULT::E_POINTER;
+		}
+		let ids = unsafe { slice::from_raw_parts_mut(ids, count as usize) };
+		ids.fill(IDispatchVt::DISPID_UNKNOWN);
+		let Some(id) = ids.first_mut() else {
+			return HRESULT::S_OK;
+		};
+
+		let name = unsafe { names.read() };
+		let index = T::METHODS.iter().position(|method| unsafe { wide_eq_ignore_ascii_case(name, method.name) });
+		match index {
+			Some(index) => *id = index as i32 + 1,
+			None => return HRESULT::DISP_E_UNKNOWNNAME,
+		}
+		// Parameters are not named.
+		if count > 1 { HRESULT::DISP_E_UNKNOWNNAME } else { HRESULT::S_OK }
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	unsafe extern "system" fn dispatch_invoke(
+		this: VtObjectPtr<IDispatchVt>,
+		id: i32,
+		_iid: *const GUID,
+		_lcid: u32,
+		flags: DispatchFlags,
+		params: *mut DISPPARAMS,
+		result: *mut VARIANT,
+		_exception: *mut EXCEPINFO,
+		_arg_error: *mut u32,
+	) -> HRESULT {
+		let method = usize::try_from(id).ok()
+			.and_then(|id| id.checked_sub(1))
+			.and_then(|index| T::METHODS.get(index));
+		let Some(method) = method else {
+			return HRESULT::DISP_E_MEMBERNOTFOUND;
+		};
+		if params.is_null() {
+			return HRESULT::E_POINTER;
+		}
+
+		let params = unsafe { &*params };
+		let named_args = if params.named_arg_count == 0 {
+			&[][..]
+		} else {
+			unsafe { slice::from_raw_parts(params.named_args, params.named_arg_count as usize) }
+		};
+		let is_put = flags.intersects(DispatchFlags(DispatchFlags::PROPERTY_PUT.0 | DispatchFlags::PROPERTY_PUT_REF.0));
+		match named_args {
+			[] => {}
+			[IDispatchVt::DISPID_PROPERTYPUT] if is_put => {}
+			_ => return HRESULT::DISP_E_NONAMEDARGS,
+		}
+
+		let reversed = if params.arg_count == 0 {
+			&[][..]
+		} else {
+			unsafe { slice::from_raw_parts(params.args, params.arg_count as usize) }
+		};
+		let this = unsafe { Self::from_this(this) };
+		match (method.invoke)(this, &DispatchArgs { flags, reversed }) {
+			Ok(value) => {
+				if !result.is_null() {
+					unsafe { result.write(value) };
+				}
+				HRESULT::S_OK
+			}
+			Err(error) => error,
+		}
+	}
+}
+
+/// Return `true` if the nul-terminated UTF-16 string `wide` is `name`, ignoring ASCII case.
+unsafe fn wide_eq_ignore_ascii_case(wide: *const u16, name: &str) -> bool {
+	if wide.is_null() {
+		return false;
+	}
+	let mut len = 0;
+	while unsafe { wide.add(len).read() } != 0 {
+		len += 1;
+	}
+	let wide = unsafe { slice::from_raw_parts(wide, len) };
+	char::decode_utf16(wide.iter().copied())
+		.map(|c| c.map(|c| c.to_ascii_lowercase()).ok())
+		.eq(name.chars().map(|c| Some(c.to_ascii_lowercase())))
+}
@@ -0,0 +1,126 @@
+use ::core::{
+	cell::UnsafeCell,
+	fmt,
+	marker::PhantomPinned,
+	pin::Pin,
+};
+#[cfg(feature = "alloc")]
+use ::alloc::boxed::Box;
+
+use super::{
+	VTablePtr, VtObject, VtObjectPtr,
+};
+
+/// Trait for VTables that can be implemented by a set of closures `F`,
+/// one for each slot.
+/// 
+/// This trait is usually implemented with [`closure_vtable!`](crate::closure_vtable!).
+/// 
+/// # Safety
+/// Every slot of [`ClosureVTable::VTABLE`] must only ever treat `this`
+/// as a pointer to a [`ClosureObject<Self, F>`].
+pub unsafe trait ClosureVTable<F>: Sized + 'static {
+	/// The `VTable` shared by every [`ClosureObject<Self, F>`].
+	const VTABLE: &'static Self;
+}
+
+/// C++ object with a `VTable` whose slots are implemented by the closures `F`.
+/// 
+/// Since C++ refers to objects by address,
+/// pointers to the object can only be obtained once it is pinned,
+/// for example with [`ClosureObject::boxed`].
+/// 
+/// # Examples
+/// ```
+/// # use core::pin::pin;
+/// # use cppdvt::{ClosureObject, vtable, closure_vtable, virtual_call};
+/// vtable! {
+/// 	ListenerVt {
+/// 		pub fn on_event(code: i32) -> bool;
+/// 		pub fn on_close();
+/// 	}
+/// }
+/// 
+/// closure_vtable! {
+/// 	ListenerVt {
+/// 		fn on_event(code: i32) -> bool;
+/// 		fn on_close();
+/// 	}
+/// }
+/// 
+/// let mut events = 0;
+/// {
+/// 	let listener = pin!(ClosureObject::<ListenerVt, _>::new((
+/// 		|code: i32| {
+/// 			events += 1;
+/// 			code == 0
+/// 		},
+/// 		|| {},
+/// 	)));
+/// 
+/// 	let object = listener.as_ref().as_object();
+/// 	assert!(unsafe { virtual_call!(object => on_event(0)) });
+/// 	assert!(!unsafe { virtual_call!(object => on_event(1)) });
+/// 	unsafe { virtual_call!(object => on_close()) };
+/// }
+/// assert_eq!(events, 2);
+/// ```
+#[repr(C)]
+pub struct ClosureObject<VTable, F> {
+	vtable: VTablePtr<VTable>,
+	closures: UnsafeCell<F>,
+	_pinned: PhantomPinned,
+}
+
+impl<VTable, F> fmt::Debug for ClosureObject<VTable, F> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ClosureObject")
+			.field("vtable", &self.vtable)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<VTable: ClosureVTable<F>, F> ClosureObject<VTable, F> {
+	/// Create a new object from `closures`.
+	/// 
+	/// The object must be pinned before it can be used with C++.
+	pub const fn new(closures: F) -> Self {
+		Self {
+			vtable: VTablePtr::from_ref(VTable::VTABLE),
+			closures: UnsafeCell::new(closures),
+			_pinned: PhantomPinned,
+		}
+	}
+
+	/// Create a new object from `closures` on the heap.
+	#[cfg(feature = "alloc")]
+	pub fn boxed(closures: F) -> Pin<Box<Self>> {
+		Box::pin(Self::new(closures))
+	}
+}
+
+impl<VTable, F> ClosureObject<VTable, F> {
+	/// Return a reference that represents the C++ object.
+	pub fn as_object(self: Pin<&Self>) -> &VtObject<VTable> {
+		// SAFETY: `vtable` is the first field of a `repr(C)` struct.
+		unsafe { VtObject::from_ptr(VtObjectPtr::from_ref(&self.get_ref().vtable)) }
+	}
+
+	/// Return a pointer that can be used with C++.
+	/// 
+	/// The pointer stays valid until the object is dropped.
+	pub fn as_ptr(self: Pin<&Self>) -> VtObjectPtr<VTable> {
+		VtObjectPtr::from_ref(&self.get_ref().vtable)
+	}
+
+	/// Return the closures of the object that `this` points to.
+	/// 
+	/// This is used by the slots of [`ClosureVTable::VTABLE`].
+	/// 
+	/// # Safety
+	/// `this` must point to a live `ClosureObject<VTable, F>`,
+	/// and the returned reference must not overlap with any other reference to its closures.
+	pub unsafe fn closures_from_ptr<'a>(this: VtObjectPtr<VTable>) -> &'a mut F {
+		unsafe { &mut *this.cast::<Self>().as_ref().closures.get() }
+	}
+}
@@ -0,0 +1,562 @@
+use ::core::{
+	ffi::c_void,
+	fmt,
+	ops::Deref,
+	ptr::{
+		self, NonNull,
+	},
+};
+#[cfg(feature = "alloc")]
+use ::core::{
+	mem::size_of,
+	sync::atomic::{
+		fence, AtomicU32, AtomicUsize, Ordering,
+	},
+};
+#[cfg(feature = "alloc")]
+use ::alloc::boxed::Box;
+
+use super::{
+	VTablePtr, VtObject, VtObjectPtr,
+};
+
+/// Globally unique identifier, such as the IID of a COM interface.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::GUID;
+/// const IID_IUNKNOWN: GUID = GUID::parse("00000000-0000-0000-C000-000000000046").unwrap();
+/// assert_eq!(IID_IUNKNOWN, GUID::from_u128(0x00000000_0000_0000_C000_000000000046));
+/// assert_eq!(IID_IUNKNOWN.data4, [0xC0, 0, 0, 0, 0, 0, 0, 0x46]);
+/// assert_eq!(IID_IUNKNOWN.to_string(), "{00000000-0000-0000-C000-000000000046}");
+/// assert_eq!(GUID::parse("{00000000-0000-0000-c000-000000000046}"), Some(IID_IUNKNOWN));
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct GUID {
+	/// First 8 hexadecimal digits.
+	pub data1: u32,
+	/// Second group of 4 hexadecimal digits.
+	pub data2: u16,
+	/// Third group of 4 hexadecimal digits.
+	pub data3: u16,
+	/// Last 16 hexadecimal digits, in the order they are written.
+	pub data4: [u8; 8],
+}
+
+impl GUID {
+	/// The GUID whose digits are all zero, `GUID_NULL`.
+	pub const ZERO: Self = Self::from_u128(0);
+
+	/// Create a GUID from its digits, as written.
+	pub const fn from_u128(value: u128) -> Self {
+		Self {
+			data1: (value >> 96) as u32,
+			data2: (value >> 80) as u16,
+			data3: (value >> 64) as u16,
+			data4: (value as u64).to_be_bytes(),
+		}
+	}
+
+	/// Return the digits of the GUID, as written.
+	pub const fn to_u128(&self) -> u128 {
+		(self.data1 as u128) << 96
+			| (self.data2 as u128) << 80
+			| (self.data3 as u128) << 64
+			| u64::from_be_bytes(self.data4) as u128
+	}
+
+	/// Create a GUID from its bytes in memory, such as a VST3 `TUID`.
+	pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+		let [a, b, c, d, e, f, g, h, data4 @ ..] = bytes;
+		Self {
+			data1: u32::from_ne_bytes([a, b, c, d]),
+			data2: u16::from_ne_bytes([e, f]),
+			data3: u16::from_ne_bytes([g, h]),
+			data4,
+		}
+	}
+
+	/// Return the bytes of the GUID in memory.
+	pub const fn to_bytes(&self) -> [u8; 16] {
+		let [a, b, c, d] = self.data1.to_ne_bytes();
+		let [e, f] = self.data2.to_ne_bytes();
+		let [g, h] = self.data3.to_ne_bytes();
+		let [i, j, k, l, m, n, o, p] = self.data4;
+		[a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p]
+	}
+
+	/// Parse a GUID written as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`,
+	/// optionally in braces.
+	/// 
+	/// Returns `None` if `s` is not a GUID.
+	pub const fn parse(s: &str) -> Option<Self> {
+		let mut bytes = s.as_bytes();
+		if let [b'{', inner @ .., b'}'] = bytes {
+			bytes = inner;
+		}
+		if bytes.len() != 36 {
+			return None;
+		}
+
+		let mut value = 0u128;
+		let mut i = 0;
+		while i < bytes.len() {
+			let byte = bytes[i];
+			if matches!(i, 8 | 13 | 18 | 23) {
+				if byte != b'-' {
+					return None;
+				}
+			} else {
+				let digit = match byte {
+					b'0'..=b'9' => byte - b'0',
+					b'a'..=b'f' => byte - b'a' + 10,
+					b'A'..=b'F' => byte - b'A' + 10,
+					_ => return None,
+				};
+				value = value << 4 | digit as u128;
+			}
+			i += 1;
+		}
+		Some(Self::from_u128(value))
+	}
+}
+
+impl fmt::Display for GUID {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let [a, b, c, d, e, g, h, i] = self.data4;
+		write!(
+			f,
+			"{{{:08X}-{:04X}-{:04X}-{a:02X}{b:02X}-{c:02X}{d:02X}{e:02X}{g:02X}{h:02X}{i:02X}}}",
+			self.data1, self.data2, self.data3,
+		)
+	}
+}
+
+impl fmt::Debug for GUID {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+/// Result code returned by COM methods.
+/// 
+/// Negative codes are failures.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct HRESULT(pub i32);
+
+impl HRESULT {
+	/// Success.
+	pub const S_OK: Self = Self(0);
+	/// Success, with a result of "false".
+	pub const S_FALSE: Self = Self(1);
+	/// The method is not implemented.
+	pub const E_NOTIMPL: Self = Self(0x80004001_u32 as i32);
+	/// The object does not support the requested interface.
+	pub const E_NOINTERFACE: Self = Self(0x80004002_u32 as i32);
+	/// A pointer argument is null.
+	pub const E_POINTER: Self = Self(0x80004003_u32 as i32);
+	/// Unspecified failure.
+	pub const E_FAIL: Self = Self(0x80004005_u32 as i32);
+	/// Unexpected failure.
+	pub const E_UNEXPECTED: Self = Self(0x8000FFFF_u32 as i32);
+	/// Memory could not be allocated.
+	pub const E_OUTOFMEMORY: Self = Self(0x8007000E_u32 as i32);
+	/// An argument is invalid.
+	pub const E_INVALIDARG: Self = Self(0x80070057_u32 as i32);
+	/// The class does not support aggregation.
+	pub const CLASS_E_NOAGGREGATION: Self = Self(0x80040110_u32 as i32);
+	/// The class is not available from the server.
+	pub const CLASS_E_CLASSNOTAVAILABLE: Self = Self(0x80040111_u32 as i32);
+
+	/// Return `true` if the code is a success code.
+	pub const fn is_ok(self) -> bool {
+		self.0 >= 0
+	}
+
+	/// Return `true` if the code is a failure code.
+	pub const fn is_err(self) -> bool {
+		self.0 < 0
+	}
+
+	/// Convert the code to a [`Result`].
+	/// 
+	/// # Errors
+	/// Returns `Err(self)` if the code is a failure code.
+	pub const fn ok(self) -> Result<(), Self> {
+		if self.is_ok() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+}
+
+impl fmt::Display for HRESULT {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:#010X}", self.0 as u32)
+	}
+}
+
+impl fmt::Debug for HRESULT {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "HRESULT({self})")
+	}
+}
+
+/// VTable of `IUnknown`, which every COM interface starts with.
+/// 
+/// The methods use the calling convention of COM,
+/// which is `stdcall` on `cfg(all(windows, target_arch = "x86"))`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct IUnknownVt {
+	/// Write a new reference to the interface with the IID `iid` to `object`,
+	/// or null if the object does not support it.
+	/// 
+	/// Implementations must not assume that `iid` is aligned,
+	/// since VST3 passes `TUID`s, which are byte arrays.
+	pub query_interface: unsafe extern "system" fn(
+		this: VtObjectPtr<IUnknownVt>,
+		iid: *const GUID,
+		object: *mut *mut c_void,
+	) -> HRESULT,
+	/// Increment the reference count, returning the new count.
+	pub add_ref: unsafe extern "system" fn(this: VtObjectPtr<IUnknownVt>) -> u32,
+	/// Decrement the reference count, returning the new count.
+	/// The object is destroyed when the count reaches 0.
+	pub release: unsafe extern "system" fn(this: VtObjectPtr<IUnknownVt>) -> u32,
+}
+
+/// Trait for VTables of COM interfaces, which ties them to their IID.
+/// 
+/// # Safety
+/// `Self` must start with the layout of [`IUnknownVt`],
+/// and objects that return it from `QueryInterface` for [`Interface::IID`]
+/// must have a VTable of type `Self`.
+pub unsafe trait Interface: 'static {
+	/// The IID of the interface.
+	const IID: GUID;
+
+	/// IIDs of the interfaces that the interface derives from, other than `IUnknown`.
+	const BASE_IIDS: &'static [GUID] = &[];
+
+	/// Return `true` if `iid` is the IID of the interface or of one of its bases.
+	fn matches(iid: &GUID) -> bool {
+		*iid == Self::IID || *iid == IUnknownVt::IID || Self::BASE_IIDS.contains(iid)
+	}
+}
+
+// SAFETY: `IUnknownVt` is the VTable of `IUnknown`.
+unsafe impl Interface for IUnknownVt {
+	const IID: GUID = GUID::from_u128(0x00000000_0000_0000_C000_000000000046);
+}
+
+/// VTable of `IClassFactory`, which creates objects of a COM class.
+/// 
+/// Factories are obtained by CLSID, such as from the `DllGetClassObject` of a server.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct IClassFactoryVt {
+	/// Methods of `IUnknown`.
+	pub base: IUnknownVt,
+	/// Create an object and write a reference to its interface with the IID `iid` to `object`.
+	/// `outer` is the controlling `IUnknown` for aggregation, which is usually null.
+	pub create_instance: unsafe extern "system" fn(
+		this: VtObjectPtr<IClassFactoryVt>,
+		outer: Option<VtObjectPtr<IUnknownVt>>,
+		iid: *const GUID,
+		object: *mut *mut c_void,
+	) -> HRESULT,
+	/// Keep the server loaded while `lock` is non-zero, counting calls.
+	pub lock_server: unsafe extern "system" fn(this: VtObjectPtr<IClassFactoryVt>, lock: i32) -> HRESULT,
+}
+
+// SAFETY: `IClassFactoryVt` is the VTable of `IClassFactory`.
+unsafe impl Interface for IClassFactoryVt {
+	const IID: GUID = GUID::from_u128(0x00000001_0000_0000_C000_000000000046);
+}
+
+/// Owning reference to a COM object with the interface `VTable`,
+/// which calls `AddRef` when cloned and `Release` when dropped.
+/// 
+/// Methods of the interface are called through [`VtObject`], such as with
+/// [`virtual_call!`](crate::virtual_call!).
+pub struct ComPtr<VTable: Interface> {
+	ptr: VtObjectPtr<VTable>,
+}
+
+impl<VTable: Interface> fmt::Debug for ComPtr<VTable> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ComPtr")
+			.field("ptr", &self.ptr)
+			.finish()
+	}
+}
+
+impl<VTable: Interface> ComPtr<VTable> {
+	/// Take ownership of a reference to a COM object,
+	/// such as one returned by `QueryInterface`.
+	/// 
+	/// # Safety
+	/// `ptr` must point to a COM object with the interface `VTable`,
+	/// and the caller must own a reference to it.
+	pub unsafe fn from_raw(ptr: VtObjectPtr<VTable>) -> Self {
+		Self { ptr }
+	}
+
+	/// Create a new reference to a COM object by calling `AddRef`.
+	/// 
+	/// # Safety
+	/// `ptr` must point to a live COM object with the interface `VTable`.
+	pub unsafe fn from_raw_borrowed(ptr: VtObjectPtr<VTable>) -> Self {
+		let this = Self { ptr };
+		::core::mem::forget(this.clone());
+		this
+	}
+
+	/// Consume the reference without calling `Release`, returning the pointer.
+	pub fn into_raw(this: Self) -> VtObjectPtr<VTable> {
+		::core::mem::ManuallyDrop::new(this).ptr
+	}
+
+	/// Return the pointer to the object without affecting the reference count.
+	pub fn as_raw(this: &Self) -> VtObjectPtr<VTable> {
+		this.ptr
+	}
+
+	/// Return a reference to the interface `I` of the object, by calling `QueryInterface`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `QueryInterface`,
+	/// such as [`HRESULT::E_NOINTERFACE`] if the object does not support `I`.
+	pub fn query_interface<I: Interface>(&self) -> Result<ComPtr<I>, HRESULT> {
+		let (this, vtable) = self.unknown();
+		let mut object = ptr::null_mut();
+		// SAFETY: `Interface` guarantees that the object has the layout of `IUnknown`.
+		unsafe { (vtable.query_interface)(this, &I::IID, &mut object) }.ok()?;
+		match NonNull::new(object) {
+			// SAFETY: `QueryInterface` returned a reference to `I`.
+			Some(object) => Ok(unsafe { ComPtr::from_raw(object.cast()) }),
+			None => Err(HRESULT::E_POINTER),
+		}
+	}
+
+	fn unknown(&self) -> (VtObjectPtr<IUnknownVt>, &IUnknownVt) {
+		let this = self.ptr.cast::<VTablePtr<IUnknownVt>>();
+		// SAFETY: `Interface` guarantees that the VTable starts with `IUnknownVt`.
+		(this, unsafe { VtObject::from_ptr(this) }.vtable())
+	}
+}
+
+impl ComPtr<IClassFactoryVt> {
+	/// Create an object with the factory, returning its interface `I`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `CreateInstance`,
+	/// such as [`HRESULT::E_NOINTERFACE`] if the object does not support `I`.
+	pub fn create_instance<I: Interface>(&self) -> Result<ComPtr<I>, HRESULT> {
+		let mut object = ptr::null_mut();
+		unsafe { (self.vtable().create_instance)(self.ptr, None, &I::IID, &mut object) }.ok()?;
+		match NonNull::new(object) {
+			// SAFETY: `CreateInstance` returned a reference to `I`.
+			Some(object) => Ok(unsafe { ComPtr::from_raw(object.cast()) }),
+			None => Err(HRESULT::E_POINTER),
+		}
+	}
+
+	/// Lock the server in memory, or undo a previous lock.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `LockServer`.
+	pub fn lock_server(&self, lock: bool) -> Result<(), HRESULT> {
+		unsafe { (self.vtable().lock_server)(self.ptr, lock as i32) }.ok()
+	}
+}
+
+impl<VTable: Interface> Clone for ComPtr<VTable> {
+	fn clone(&self) -> Self {
+		let (this, vtable) = self.unknown();
+		unsafe { (vtable.add_ref)(this) };
+		Self { ptr: self.ptr }
+	}
+}
+
+impl<VTable: Interface> Deref for ComPtr<VTable> {
+	type Target = VtObject<VTable>;
+
+	fn deref(&self) -> &Self::Target {
+		unsafe { VtObject::from_ptr(self.ptr) }
+	}
+}
+
+impl<VTable: Interface> Drop for ComPtr<VTable> {
+	fn drop(&mut self) {
+		let (this, vtable) = self.unknown();
+		unsafe { (vtable.release)(this) };
+	}
+}
+
+/// Number of live [`ComObject`]s, for `DllCanUnloadNow`.
+#[cfg(feature = "alloc")]
+pub(crate) static LIVE_OBJECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Trait for Rust types that implement COM interfaces as a [`ComObject`].
+/// 
+/// This is usually implemented with [`com_object!`](crate::com_object!).
+/// 
+/// # Safety
+/// - [`ComClass::VTables`] must be an array of [`VTablePtr`]s,
+///   each of which points to a static VTable that starts with [`ComObject::UNKNOWN`],
+///   and no other type may use these VTables.
+/// - [`ComClass::interface_index`] must return the index of a VTable
+///   of the interface with the IID `iid`.
+#[cfg(feature = "alloc")]
+pub unsafe trait ComClass: Sized + 'static {
+	/// VTable pointers of the object, one for each interface.
+	type VTables: AsRef<[VTablePtr<()>]> + 'static;
+
+	/// Return the VTable pointers of a new object.
+	fn vtables() -> Self::VTables;
+
+	/// Return the index of the VTable pointer of the interface with the IID `iid`,
+	/// or `None` if the object does not support it.
+	/// 
+	/// This is not called for `IUnknown`, which is at index 0.
+	fn interface_index(iid: &GUID) -> Option<usize>;
+
+	/// Code returned by `QueryInterface` for interfaces that the object does not support,
+	/// which differs for COM-like ABIs such as VST3.
+	const NO_INTERFACE: HRESULT = HRESULT::E_NOINTERFACE;
+}
+
+/// Reference-counted COM object on the heap that implements
+/// the interfaces of a [`ComClass`] with a value of type `T`.
+/// 
+/// The object starts with one VTable pointer for each interface,
+/// and the interface pointers given to COM point to them.
+/// `this` is adjusted back to the start of the object with [`ComObject::from_this`].
+#[cfg(feature = "alloc")]
+#[repr(C)]
+pub struct ComObject<T: ComClass> {
+	vtables: T::VTables,
+	refs: AtomicU32,
+	value: T,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ComClass> ComObject<T> {
+	/// VTable of `IUnknown` for `T`, which every VTable of `T` starts with.
+	pub const UNKNOWN: IUnknownVt = IUnknownVt {
+		query_interface: Self::unknown_query_interface,
+		add_ref: Self::unknown_add_ref,
+		release: Self::unknown_release,
+	};
+
+	/// Move `value` to a new object on the heap, returning its `IUnknown`.
+	pub fn create(value: T) -> ComPtr<IUnknownVt> {
+		LIVE_OBJECTS.fetch_add(1, Ordering::Relaxed);
+		let object = Box::into_raw(Box::new(Self {
+			vtables: T::vtables(),
+			refs: AtomicU32::new(1),
+			value,
+		}));
+		// SAFETY: The first VTable pointer starts with `IUnknownVt`, and the reference is owned.
+		unsafe { ComPtr::from_raw(NonNull::new_unchecked(object).cast()) }
+	}
+
+	/// Return the object from the interface pointer `this`,
+	/// which is given to the methods of its interfaces.
+	/// 
+	/// # Safety
+	/// `this` must be an interface pointer of a live `ComObject<T>`.
+	pub unsafe fn from_this<'a, VTable>(this: VtObjectPtr<VTable>) -> &'a Self {
+		unsafe { Self::object_ptr(this.cast()).as_ref() }
+	}
+
+	/// Return a new reference to the interface `I` of the object.
+	/// 
+	/// # Errors
+	/// Returns [`ComClass::NO_INTERFACE`] if `T` does not implement `I`.
+	pub fn query_interface<I: Interface>(&self) -> Result<ComPtr<I>, HRESULT> {
+		let Some(ptr) = self.interface_ptr(&I::IID) else {
+			return Err(T::NO_INTERFACE);
+		};
+		// SAFETY: `ComClass` guarantees that the VTable at the index is of `I`.
+		Ok(unsafe { ComPtr::from_raw_borrowed(ptr.cast()) })
+	}
+
+	fn interface_ptr(&self, iid: &GUID) -> Option<NonNull<VTablePtr<()>>> {
+		let index = if *iid == IUnknownVt::IID { 0 } else { T::interface_index(iid)? };
+		let vtables = NonNull::from(&self.vtables).cast::<VTablePtr<()>>();
+		// SAFETY: `ComClass` guarantees that `index` is in bounds.
+		Some(unsafe { vtables.add(index) })
+	}
+
+	/// Return the object that the interface pointer `this` belongs to,
+	/// by finding its VTable among the VTables of `T`.
+	unsafe fn object_ptr(this: NonNull<VTablePtr<()>>) -> NonNull<Self> {
+		let vtable = unsafe { this.as_ref() }.as_ptr();
+		let index = T::vtables().as_ref().iter().position(|other| other.as_ptr() == vtable);
+		// A VTable that does not start with `ComObject::<T>::UNKNOWN` can end up here,
+		// which would otherwise adjust `this` to a wrong object.
+		let Some(index) = index else {
+			panic!("interface pointer does not belong to this COM class");
+		};
+		unsafe { this.byte_sub(index * size_of::<VTablePtr<()>>()).cast() }
+	}
+
+	unsafe extern "system" fn unknown_query_interface(
+		this: VtObjectPtr<IUnknownVt>,
+		iid: *const GUID,
+		object: *mut *mut c_void,
+	) -> HRESULT {
+		if object.is_null() {
+			return HRESULT::E_POINTER;
+		}
+		unsafe { object.write(ptr::null_mut()) };
+		if iid.is_null() {
+			return HRESULT::E_POINTER;
+		}
+
+		// VST3 passes `TUID`s, which are byte arrays that may be unaligned.
+		let iid = unsafe { iid.read_unaligned() };
+		let this = unsafe { Self::from_this(this) };
+		match this.interface_ptr(&iid) {
+			Some(ptr) => {
+				this.refs.fetch_add(1, Ordering::Relaxed);
+				unsafe { object.write(ptr.as_ptr().cast()) };
+				HRESULT::S_OK
+			}
+			None => T::NO_INTERFACE,
+		}
+	}
+
+	unsafe extern "system" fn unknown_add_ref(this: VtObjectPtr<IUnknownVt>) -> u32 {
+		unsafe { Self::from_this(this) }.refs.fetch_add(1, Ordering::Relaxed) + 1
+	}
+
+	unsafe extern "system" fn unknown_release(this: VtObjectPtr<IUnknownVt>) -> u32 {
+		let object = unsafe { Self::object_ptr(this.cast()) };
+		let refs = unsafe { object.as_ref() }.refs.fetch_sub(1, Ordering::Release) - 1;
+		if refs == 0 {
+			fence(Ordering::Acquire);
+			// SAFETY: The last reference is gone, and the object was allocated by `ComObject::create`.
+			drop(unsafe { Box::from_raw(object.as_ptr()) });
+			LIVE_OBJECTS.fetch_sub(1, Ordering::Release);
+		}
+		refs
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ComClass> Deref for ComObject<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.value
+	}
+}
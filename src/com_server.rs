@@ -0,0 +1,279 @@
+use ::core::{
+	ffi::c_void,
+	ptr::NonNull,
+	sync::atomic::{
+		AtomicUsize, Ordering,
+	},
+};
+use ::alloc::vec::Vec;
+
+use super::{
+	com::LIVE_OBJECTS,
+	ComClass, ComObject, ComPtr, GUID, HRESULT, IClassFactoryVt, IUnknownVt, Interface, VTablePtr, VtObjectPtr,
+};
+
+/// Type of `DllGetClassObject`, the entry point of in-process COM servers,
+/// which writes a reference to the interface `iid` of the class object of `clsid` to `object`.
+pub type GetClassObjectFn = unsafe extern "system" fn(
+	clsid: *const GUID,
+	iid: *const GUID,
+	object: *mut *mut c_void,
+) -> HRESULT;
+
+/// Type of functions that create a new object of a COM class.
+pub type CreateObjectFn = fn() -> ComPtr<IUnknownVt>;
+
+/// Number of outstanding calls to `IClassFactory::LockServer` in this image.
+static SERVER_LOCKS: AtomicUsize = AtomicUsize::new(0);
+
+/// COM class that is created by its CLSID, for [`get_class_object`].
+/// 
+/// These are usually declared with [`com_server!`](crate::com_server!).
+#[derive(Debug, Clone, Copy)]
+pub struct ClassEntry {
+	/// CLSID of the class.
+	pub clsid: GUID,
+	/// Create a new object of the class.
+	pub create: CreateObjectFn,
+}
+
+struct ClassFactory {
+	create: CreateObjectFn,
+}
+
+impl ClassFactory {
+	unsafe extern "system" fn create_instance(
+		this: VtObjectPtr<IClassFactoryVt>,
+		outer: Option<VtObjectPtr<IUnknownVt>>,
+		iid: *const GUID,
+		object: *mut *mut c_void,
+	) -> HRESULT {
+		if object.is_null() {
+			return HRESULT::E_POINTER;
+		}
+		unsafe { object.write(::core::ptr::null_mut()) };
+		if outer.is_some() {
+			return HRESULT::CLASS_E_NOAGGREGATION;
+		}
+
+		let this = unsafe { ComObject::<Self>::from_this(this) };
+		let unknown = (this.create)();
+		// The new reference is written to `object`, and `unknown` is released.
+		unsafe { (unknown.vtable().query_interface)(ComPtr::as_raw(&unknown), iid, object) }
+	}
+
+	unsafe extern "system" fn lock_server(_this: VtObjectPtr<IClassFactoryVt>, lock: i32) -> HRESULT {
+		if lock != 0 {
+			SERVER_LOCKS.fetch_add(1, Ordering::Relaxed);
+		} else if SERVER_LOCKS.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |locks| locks.checked_sub(1)).is_err() {
+			// Unbalanced unlocks must not wrap around and keep the server loaded forever.
+			return HRESULT::E_UNEXPECTED;
+		}
+		HRESULT::S_OK
+	}
+}
+
+// SAFETY: The only VTable starts with `ComObject::UNKNOWN`.
+unsafe impl ComClass for ClassFactory {
+	type VTables = [VTablePtr<()>; 1];
+
+	fn vtables() -> Self::VTables {
+		static VTABLE: IClassFactoryVt = IClassFactoryVt {
+			base: ComObject::<ClassFactory>::UNKNOWN,
+			create_instance: ClassFactory::create_instance,
+			lock_server: ClassFactory::lock_server,
+		};
+		// SAFETY: `VTABLE` is a valid VTable.
+		[unsafe { VTablePtr::new(NonNull::from(&VTABLE).cast()) }]
+	}
+
+	fn interface_index(iid: &GUID) -> Option<usize> {
+		IClassFactoryVt::matches(iid).then_some(0)
+	}
+}
+
+/// Return a new class factory that creates objects with `create`.
+/// 
+/// The factory does not support aggregation.
+pub fn class_factory(create: CreateObjectFn) -> ComPtr<IClassFactoryVt> {
+	let unknown = ComObject::create(ClassFactory { create });
+	// SAFETY: The first interface of `ClassFactory` is `IClassFactory`.
+	unsafe { ComPtr::from_raw(ComPtr::into_raw(unknown).cast()) }
+}
+
+/// Implementation of `DllGetClassObject` for the classes in `classes`,
+/// which writes a reference to the interface `iid` of a new class factory to `object`.
+/// 
+/// # Errors
+/// Returns [`HRESULT::CLASS_E_CLASSNOTAVAILABLE`] if `clsid` is not in `classes`,
+/// and other failure codes for invalid arguments.
+/// 
+/// # Safety
+/// The arguments must be valid for `DllGetClassObject`.
+pub unsafe fn get_class_object(
+	classes: &[ClassEntry],
+	clsid: *const GUID,
+	iid: *const GUID,
+	object: *mut *mut c_void,
+) -> HRESULT {
+	if object.is_null() {
+		return HRESULT::E_POINTER;
+	}
+	unsafe { object.write(::core::ptr::null_mut()) };
+	if clsid.is_null() {
+		return HRESULT::E_POINTER;
+	}
+
+	let clsid = unsafe { clsid.read_unaligned() };
+	let Some(class) = classes.iter().find(|class| class.clsid == clsid) else {
+		return HRESULT::CLASS_E_CLASSNOTAVAILABLE;
+	};
+	let factory = class_factory(class.create);
+	unsafe { (factory.vtable().base.query_interface)(ComPtr::as_raw(&factory).cast(), iid, object) }
+}
+
+/// Implementation of `DllCanUnloadNow`, which returns [`HRESULT::S_OK`]
+/// if no [`ComObject`]s of this image are alive and the server is not locked,
+/// and [`HRESULT::S_FALSE`] otherwise.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{ComObject, ComPtr, HRESULT, IUnknownVt, class_factory, com_object, dll_can_unload_now};
+/// struct Plugin;
+/// com_object! {
+/// 	unsafe Plugin {
+/// 		IUnknownVt => UNKNOWN,
+/// 	}
+/// }
+/// 
+/// let factory = class_factory(|| ComObject::create(Plugin));
+/// let lock_server = factory.vtable().lock_server;
+/// unsafe {
+/// 	assert_eq!(lock_server(ComPtr::as_raw(&factory), 1), HRESULT::S_OK);
+/// 	assert_eq!(lock_server(ComPtr::as_raw(&factory), 0), HRESULT::S_OK);
+/// 	// Unbalanced unlocks fail instead of locking the server.
+/// 	assert_eq!(lock_server(ComPtr::as_raw(&factory), 0), HRESULT::E_UNEXPECTED);
+/// }
+/// drop(factory);
+/// assert_eq!(dll_can_unload_now(), HRESULT::S_OK);
+/// ```
+pub fn dll_can_unload_now() -> HRESULT {
+	if LIVE_OBJECTS.load(Ordering::Acquire) == 0 && SERVER_LOCKS.load(Ordering::Relaxed) == 0 {
+		HRESULT::S_OK
+	} else {
+		HRESULT::S_FALSE
+	}
+}
+
+#[derive(Debug)]
+enum ClassSource {
+	Factory(ComPtr<IClassFactoryVt>),
+	Server(GetClassObjectFn),
+}
+
+/// Registry of COM classes by CLSID, for activating them in-process
+/// without the registry of the system.
+/// 
+/// Classes are registered with a function that creates objects,
+/// a class factory, or the `DllGetClassObject` of a server such as a plugin library.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{ClassRegistry, ComObject, GUID, HRESULT, IUnknownVt};
+/// struct Nothing;
+/// cppdvt::com_object! {
+/// 	unsafe Nothing {
+/// 		IUnknownVt => UNKNOWN,
+/// 	}
+/// }
+/// 
+/// const CLSID_NOTHING: GUID = GUID::parse("0B5A8F1C-7E2D-4C3A-9B6E-5D4F3A2B1C01").unwrap();
+/// 
+/// let mut registry = ClassRegistry::new();
+/// registry.register(CLSID_NOTHING, || ComObject::create(Nothing));
+/// 
+/// let nothing = registry.create_instance::<IUnknownVt>(&CLSID_NOTHING).unwrap();
+/// let missing = registry.create_instance::<IUnknownVt>(&GUID::ZERO).unwrap_err();
+/// assert_eq!(missing, HRESULT::CLASS_E_CLASSNOTAVAILABLE);
+/// ```
+#[derive(Debug, Default)]
+pub struct ClassRegistry {
+	classes: Vec<(GUID, ClassSource)>,
+}
+
+impl ClassRegistry {
+	/// Create an empty registry.
+	pub const fn new() -> Self {
+		Self { classes: Vec::new() }
+	}
+
+	/// Register the class `clsid` with a function that creates its objects,
+	/// replacing any previous registration.
+	pub fn register(&mut self, clsid: GUID, create: CreateObjectFn) {
+		self.register_factory(clsid, class_factory(create));
+	}
+
+	/// Register the class `clsid` with its class factory,
+	/// replacing any previous registration.
+	pub fn register_factory(&mut self, clsid: GUID, factory: ComPtr<IClassFactoryVt>) {
+		self.insert(clsid, ClassSource::Factory(factory));
+	}
+
+	/// Register the class `clsid` as served by `get_class_object`,
+	/// which is usually the `DllGetClassObject` of a library,
+	/// replacing any previous registration.
+	/// 
+	/// # Safety
+	/// `get_class_object` must stay valid while it is registered,
+	/// so a library that defines it must stay loaded.
+	pub unsafe fn register_server(&mut self, clsid: GUID, get_class_object: GetClassObjectFn) {
+		self.insert(clsid, ClassSource::Server(get_class_object));
+	}
+
+	/// Remove the registration of the class `clsid`,
+	/// returning `true` if it was registered.
+	pub fn unregister(&mut self, clsid: &GUID) -> bool {
+		let len = self.classes.len();
+		self.classes.retain(|(registered, _)| registered != clsid);
+		self.classes.len() != len
+	}
+
+	/// Return the class factory of the class `clsid`.
+	/// 
+	/// # Errors
+	/// Returns [`HRESULT::CLASS_E_CLASSNOTAVAILABLE`] if the class is not registered,
+	/// and the failure code of the server if it could not return the factory.
+	pub fn get_class_object(&self, clsid: &GUID) -> Result<ComPtr<IClassFactoryVt>, HRESULT> {
+		let Some((_, source)) = self.classes.iter().find(|(registered, _)| registered == clsid) else {
+			return Err(HRESULT::CLASS_E_CLASSNOTAVAILABLE);
+		};
+		match source {
+			ClassSource::Factory(factory) => Ok(factory.clone()),
+			ClassSource::Server(get_class_object) => {
+				let mut object = ::core::ptr::null_mut();
+				// SAFETY: `register_server` requires the function to be valid.
+				unsafe { get_class_object(clsid, &IClassFactoryVt::IID, &mut object) }.ok()?;
+				match NonNull::new(object) {
+					// SAFETY: The server returned a reference to `IClassFactory`.
+					Some(object) => Ok(unsafe { ComPtr::from_raw(object.cast()) }),
+					None => Err(HRESULT::E_POINTER),
+				}
+			}
+		}
+	}
+
+	/// Create an object of the class `clsid`, returning its interface `I`.
+	/// 
+	/// # Errors
+	/// Returns the errors of [`ClassRegistry::get_class_object`],
+	/// and the failure code of `CreateInstance`,
+	/// such as [`HRESULT::E_NOINTERFACE`] if the object does not support `I`.
+	pub fn create_instance<I: Interface>(&self, clsid: &GUID) -> Result<ComPtr<I>, HRESULT> {
+		self.get_class_object(clsid)?.create_instance()
+	}
+
+	fn insert(&mut self, clsid: GUID, source: ClassSource) {
+		self.unregister(&clsid);
+		self.classes.push((clsid, source));
+	}
+}
@@ -0,0 +1,252 @@
+use ::core::{
+	alloc::Layout,
+	ffi::{
+		c_void, CStr,
+	},
+	fmt,
+	mem::{
+		align_of, size_of,
+	},
+	ops::{
+		Deref,
+	},
+	ptr::NonNull,
+};
+#[cfg(windows)]
+use ::core::ffi::c_uint;
+use ::alloc::alloc::{
+	alloc, dealloc, handle_alloc_error,
+};
+
+use super::{
+	Library, VtObject, VtObjectPtr,
+};
+
+#[cfg(not(all(windows, target_arch = "x86")))]
+macro_rules! method_fn {
+	(fn($($tt:tt)*) $(-> $Ret:ty)?) => {
+		unsafe extern "C" fn($($tt)*) $(-> $Ret)?
+	};
+}
+#[cfg(all(windows, target_arch = "x86"))]
+macro_rules! method_fn {
+	(fn($($tt:tt)*) $(-> $Ret:ty)?) => {
+		unsafe extern "thiscall" fn($($tt)*) $(-> $Ret)?
+	};
+}
+
+/// VTable for the virtual destructor of a C++ class,
+/// which is usually at the start of the VTable of a class that has one.
+/// 
+/// On `cfg(not(windows))`, there are two destructors, as specified by the Itanium ABI.
+/// On `cfg(windows)`, there is a single "scalar deleting destructor".
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DestructorVt {
+	/// Destroys the object without freeing its memory (`D1`).
+	#[cfg(not(windows))]
+	pub complete_destructor: method_fn!(fn(VtObjectPtr<DestructorVt>)),
+	/// Destroys the object and frees its memory with `operator delete` (`D0`).
+	#[cfg(not(windows))]
+	pub deleting_destructor: method_fn!(fn(VtObjectPtr<DestructorVt>)),
+	/// Destroys the object, and frees its memory with `operator delete`
+	/// if bit 0 of `flags` is set.
+	#[cfg(windows)]
+	pub scalar_deleting_destructor: method_fn!(fn(VtObjectPtr<DestructorVt>, c_uint) -> *mut c_void),
+}
+
+/// Trait for VTables of C++ classes with a virtual destructor.
+/// 
+/// # Safety
+/// [`VirtualDestructor::destruct`] must destroy the object
+/// without freeing its memory.
+pub unsafe trait VirtualDestructor: Sized {
+	/// Destroy the object that `this` points to without freeing its memory.
+	/// 
+	/// # Safety
+	/// `this` must point to a live C++ object, which must not be used afterwards.
+	unsafe fn destruct(this: VtObjectPtr<Self>);
+}
+
+// SAFETY: Neither call frees the memory of the object.
+unsafe impl VirtualDestructor for DestructorVt {
+	unsafe fn destruct(this: VtObjectPtr<Self>) {
+		let vtable = unsafe { VtObject::from_ptr(this) }.vtable();
+		#[cfg(not(windows))]
+		unsafe { (vtable.complete_destructor)(this) };
+		#[cfg(windows)]
+		unsafe { (vtable.scalar_deleting_destructor)(this, 0) };
+	}
+}
+
+/// Type of C++ constructors that take no arguments other than `this`.
+/// 
+/// MSVC constructors return `this`, which may be safely ignored.
+pub type DefaultConstructor = method_fn!(fn(NonNull<c_void>));
+
+/// Owning handle to a C++ object that lives in memory allocated by Rust,
+/// and is destroyed through its virtual destructor.
+/// 
+/// This allows creating objects of C++ classes whose only exports are their
+/// constructors and VTables, without a C shim.
+/// 
+/// # Examples
+/// ```no_run
+/// # use core::{alloc::Layout, ffi::c_void};
+/// # use cppdvt::{CppBox, DestructorVt, Library};
+/// // `std::filebuf` from libstdc++ on x86_64 Linux, whose VTable starts with its destructor.
+/// let library = unsafe { Library::open(c"libstdc++.so.6") }.unwrap();
+/// let filebuf = unsafe {
+/// 	CppBox::<DestructorVt>::from_symbol(
+/// 		&library,
+/// 		c"_ZNSt13basic_filebufIcSt11char_traitsIcEEC1Ev",
+/// 		Layout::from_size_align(240, 8).unwrap(),
+/// 	)
+/// }.unwrap();
+/// drop(filebuf);
+/// ```
+pub struct CppBox<VTable: VirtualDestructor> {
+	ptr: VtObjectPtr<VTable>,
+	layout: Layout,
+}
+
+// SAFETY: `CppBox<VTable>` owns the object, like `Box<VtObject<VTable>>`.
+unsafe impl<VTable: VirtualDestructor + Send> Send for CppBox<VTable> {}
+// SAFETY: `CppBox<VTable>` owns the object, like `Box<VtObject<VTable>>`.
+unsafe impl<VTable: VirtualDestructor + Sync> Sync for CppBox<VTable> {}
+
+impl<VTable: VirtualDestructor> fmt::Debug for CppBox<VTable> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("CppBox")
+			.field("ptr", &self.ptr)
+			.field("layout", &self.layout)
+			.finish()
+	}
+}
+
+impl<VTable: VirtualDestructor> CppBox<VTable> {
+	/// Allocate memory for an object with `layout`,
+	/// and construct the object in it with `construct`.
+	/// 
+	/// `layout` is usually `sizeof` and `alignof` of the class in C++.
+	/// 
+	/// # Safety
+	/// `construct` must construct a C++ object with a compatible `VTable`
+	/// in the memory it is given,
+	/// and `layout` must be large enough and aligned enough for the object.
+	/// 
+	/// # Panics
+	/// Panics if `layout` is too small or not aligned enough to hold a VTable pointer.
+	pub unsafe fn new_with(layout: Layout, construct: impl FnOnce(NonNull<c_void>)) -> Self {
+		assert!(
+			layout.size() >= size_of::<usize>() && layout.align() >= align_of::<usize>(),
+			"C++ objects with a VTable are at least as large and aligned as a pointer",
+		);
+
+		/// Frees the memory if the constructor panics.
+		struct Guard(NonNull<u8>, Layout);
+		impl Drop for Guard {
+			fn drop(&mut self) {
+				unsafe { dealloc(self.0.as_ptr(), self.1) }
+			}
+		}
+
+		// SAFETY: `layout` has a non-zero size.
+		let memory = unsafe { alloc(layout) };
+		let Some(memory) = NonNull::new(memory) else {
+			handle_alloc_error(layout)
+		};
+		let guard = Guard(memory, layout);
+		construct(memory.cast());
+		::core::mem::forget(guard);
+		Self { ptr: memory.cast(), layout }
+	}
+
+	/// Allocate memory for an object with `layout`,
+	/// and construct the object in it with the constructor `constructor`.
+	/// 
+	/// # Safety
+	/// See [`CppBox::new_with`].
+	pub unsafe fn new(constructor: DefaultConstructor, layout: Layout) -> Self {
+		unsafe { Self::new_with(layout, |this| constructor(this)) }
+	}
+
+	/// Look up the constructor with the mangled name `symbol` in `library`,
+	/// allocate memory for an object with `layout`,
+	/// and construct the object in it.
+	/// 
+	/// The constructor is usually the complete object constructor,
+	/// such as `_ZN3FooC1Ev` on Itanium, or `??0Foo@@QEAA@XZ` with MSVC on x64.
+	/// Returns [`None`] if `library` does not define `symbol`.
+	/// 
+	/// # Safety
+	/// `symbol` must be a constructor that only takes `this`,
+	/// and see [`CppBox::new_with`].
+	/// `library` must stay loaded for as long as the object exists.
+	pub unsafe fn from_symbol(library: &Library, symbol: &CStr, layout: Layout) -> Option<Self> {
+		let constructor = library.symbol(symbol)?;
+		// SAFETY: The caller guarantees that `symbol` is a constructor.
+		let constructor = unsafe {
+			::core::mem::transmute::<*mut c_void, DefaultConstructor>(constructor.as_ptr())
+		};
+		Some(unsafe { Self::new(constructor, layout) })
+	}
+
+	/// Return the layout of the memory of the object.
+	pub fn layout(this: &Self) -> Layout {
+		this.layout
+	}
+
+	/// Consume the handle without destroying the object,
+	/// returning a pointer to it and the layout of its memory.
+	/// 
+	/// The object can be given back to a handle with [`CppBox::from_raw`].
+	pub fn into_raw(this: Self) -> (VtObjectPtr<VTable>, Layout) {
+		let this = ::core::mem::ManuallyDrop::new(this);
+		(this.ptr, this.layout)
+	}
+
+	/// Create a handle from a pointer and layout returned by [`CppBox::into_raw`].
+	/// 
+	/// # Safety
+	/// `ptr` and `layout` must have been returned by [`CppBox::into_raw`],
+	/// and the object must not be owned by anything else.
+	pub unsafe fn from_raw(ptr: VtObjectPtr<VTable>, layout: Layout) -> Self {
+		Self { ptr, layout }
+	}
+
+	/// Return a pointer to the object that can be used with C++,
+	/// such as for calling methods that mutate it.
+	pub fn as_mut_ptr(this: &mut Self) -> VtObjectPtr<VTable> {
+		this.ptr
+	}
+
+	/// Return a mutable reference to the object.
+	/// 
+	/// `CppBox` does not implement `DerefMut`, since swapping two objects
+	/// through mutable references would swap their VTable pointers,
+	/// and [`Drop`] would then run the destructor of the wrong class.
+	/// 
+	/// # Safety
+	/// The VTable pointer of the object must not be replaced through the reference.
+	pub unsafe fn as_mut(this: &mut Self) -> &mut VtObject<VTable> {
+		unsafe { VtObject::from_ptr_mut(this.ptr) }
+	}
+}
+
+impl<VTable: VirtualDestructor> Deref for CppBox<VTable> {
+	type Target = VtObject<VTable>;
+
+	fn deref(&self) -> &Self::Target {
+		unsafe { VtObject::from_ptr(self.ptr) }
+	}
+}
+
+impl<VTable: VirtualDestructor> Drop for CppBox<VTable> {
+	fn drop(&mut self) {
+		unsafe {
+			VTable::destruct(self.ptr);
+			dealloc(self.ptr.as_ptr().cast(), self.layout);
+		}
+	}
+}
@@ -0,0 +1,339 @@
+use ::core::{
+	ffi::{
+		c_char, c_int, c_void, CStr,
+	},
+	fmt,
+	iter::FusedIterator,
+	ptr::{
+		self, NonNull,
+	},
+};
+
+#[cfg(any(unix, windows))]
+use super::Library;
+use super::VtObject;
+
+/// Type of `CreateInterface`, the factory exported by modules of the Source engine,
+/// which returns the interface `name` and writes [`IFACE_OK`] or [`IFACE_FAILED`] to `return_code`.
+pub type CreateInterfaceFn = unsafe extern "C" fn(name: *const c_char, return_code: *mut c_int) -> *mut c_void;
+
+/// Type of functions that return an exposed interface, `InstantiateInterfaceFn`.
+pub type InstantiateInterfaceFn = unsafe extern "C" fn() -> *mut c_void;
+
+/// Return code of [`CreateInterfaceFn`] on success.
+pub const IFACE_OK: c_int = 0;
+/// Return code of [`CreateInterfaceFn`] on failure.
+pub const IFACE_FAILED: c_int = 1;
+
+/// Name of the head of the list of [`InterfaceReg`]s,
+/// `InterfaceReg::s_pInterfaceRegs`, as mangled for the Itanium ABI.
+pub const INTERFACE_REGS_SYMBOL: &CStr = c"_ZN12InterfaceReg15s_pInterfaceRegsE";
+
+/// Node of the list of interfaces that a module exposes through `CreateInterface`,
+/// `InterfaceReg`.
+/// 
+/// Lists of Rust interfaces are usually declared with
+/// [`expose_interfaces!`](crate::expose_interfaces!).
+/// 
+/// The fields are private so that nodes created in Rust always point to
+/// a C string and a valid next node, which [`name`](Self::name) and [`iter`](Self::iter) rely on.
+#[repr(C)]
+pub struct InterfaceReg {
+	create: InstantiateInterfaceFn,
+	name: *const c_char,
+	next: *const InterfaceReg,
+}
+
+// SAFETY: The nodes are never modified after they are linked.
+unsafe impl Sync for InterfaceReg {}
+
+impl fmt::Debug for InterfaceReg {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("InterfaceReg")
+			.field("create", &self.create)
+			.field("name", &self.name())
+			.field("next", &self.next)
+			.finish()
+	}
+}
+
+impl InterfaceReg {
+	/// Create a node for the interface `name`, followed by `next`.
+	pub const fn new(name: &'static CStr, create: InstantiateInterfaceFn, next: Option<&'static InterfaceReg>) -> Self {
+		Self {
+			create,
+			name: name.as_ptr(),
+			next: match next {
+				Some(next) => next,
+				None => ptr::null(),
+			},
+		}
+	}
+
+	/// Return the function that returns the interface.
+	pub const fn create(&self) -> InstantiateInterfaceFn {
+		self.create
+	}
+
+	/// Return the versioned name of the interface, such as `VEngineServer023`.
+	pub fn name(&self) -> &CStr {
+		// SAFETY: `name` is a C string.
+		unsafe { CStr::from_ptr(self.name) }
+	}
+
+	/// Return the next node, or [`None`] if this is the last one.
+	pub fn next(&self) -> Option<&InterfaceReg> {
+		// SAFETY: `next` is null or points to a valid node.
+		unsafe { self.next.as_ref() }
+	}
+
+	/// Return an iterator over this node and the ones that follow it.
+	pub fn iter(&self) -> InterfaceRegs<'_> {
+		InterfaceRegs { next: Some(self) }
+	}
+}
+
+/// Iterator over a list of [`InterfaceReg`]s.
+#[derive(Debug, Clone)]
+pub struct InterfaceRegs<'a> {
+	next: Option<&'a InterfaceReg>,
+}
+
+impl<'a> InterfaceRegs<'a> {
+	/// Return an iterator over the list that starts at `head`, which may be null.
+	/// 
+	/// # Safety
+	/// `head` must be null or point to a valid list that is not modified while it is iterated.
+	pub const unsafe fn new(head: *const InterfaceReg) -> Self {
+		Self { next: unsafe { head.as_ref() } }
+	}
+
+	/// Return an iterator over the interfaces registered in `library`,
+	/// which is only possible if it exports [`INTERFACE_REGS_SYMBOL`],
+	/// as the Source engine does on Linux and macOS.
+	/// 
+	/// # Safety
+	/// The symbol must be the head of the list of `InterfaceReg`s, and the library
+	/// must stay loaded and not register interfaces while the list is iterated.
+	#[cfg(any(unix, windows))]
+	pub unsafe fn from_library(library: &Library) -> Option<Self> {
+		let head = library.symbol(INTERFACE_REGS_SYMBOL)?;
+		Some(unsafe { Self::new(head.cast::<*const InterfaceReg>().read()) })
+	}
+
+	/// Return the interface `name`, or [`None`] if it is not in the list.
+	/// 
+	/// # Safety
+	/// The function that returns the interface must be safe to call,
+	/// which the list cannot guarantee, since [`InterfaceReg::new`] accepts any function.
+	pub unsafe fn create(self, name: &CStr) -> Option<NonNull<c_void>> {
+		let reg = self.into_iter().find(|reg| reg.name() == name)?;
+		NonNull::new(unsafe { (reg.create)() })
+	}
+
+	/// Return the name of the highest version of the interface `prefix` in the list,
+	/// and the version, such as `23` for `VEngineServer023` and the prefix `VEngineServer`.
+	pub fn latest(self, prefix: &str) -> Option<(&'a CStr, u32)> {
+		self.into_iter()
+			.filter_map(|reg| Some((reg.name(), interface_version(reg.name(), prefix)?)))
+			.max_by_key(|&(_, version)| version)
+	}
+}
+
+impl<'a> Iterator for InterfaceRegs<'a> {
+	type Item = &'a InterfaceReg;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let reg = self.next?;
+		self.next = reg.next();
+		Some(reg)
+	}
+}
+
+impl FusedIterator for InterfaceRegs<'_> {}
+
+/// Return the version of the interface `name` if it is `prefix` followed by decimal digits.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::interface_version;
+/// assert_eq!(interface_version(c"VEngineServer023", "VEngineServer"), Some(23));
+/// assert_eq!(interface_version(c"VEngineServer", "VEngineServer"), None);
+/// assert_eq!(interface_version(c"VEngineClient015", "VEngineServer"), None);
+/// ```
+pub fn interface_version(name: &CStr, prefix: &str) -> Option<u32> {
+	let digits = name.to_bytes().strip_prefix(prefix.as_bytes())?;
+	if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+		return None;
+	}
+	::core::str::from_utf8(digits).ok()?.parse().ok()
+}
+
+/// Implementation of `CreateInterface` for the list that starts at `head`.
+/// 
+/// # Safety
+/// The arguments must be valid for `CreateInterface`,
+/// and the functions that return the interfaces must be safe to call.
+pub unsafe fn create_interface(head: &InterfaceReg, name: *const c_char, return_code: *mut c_int) -> *mut c_void {
+	let object = if name.is_null() {
+		None
+	} else {
+		unsafe { head.iter().create(CStr::from_ptr(name)) }
+	};
+	if !return_code.is_null() {
+		unsafe { return_code.write(if object.is_some() { IFACE_OK } else { IFACE_FAILED }) };
+	}
+	object.map_or(ptr::null_mut(), NonNull::as_ptr)
+}
+
+/// The `CreateInterface` factory of a module, which returns its interfaces by versioned name.
+/// 
+/// # Examples
+/// ```
+/// # use core::ffi::c_void;
+/// # use cppdvt::InterfaceFactory;
+/// # unsafe extern "C" fn create_interface(name: *const core::ffi::c_char, _: *mut i32) -> *mut c_void {
+/// # 	let name = unsafe { core::ffi::CStr::from_ptr(name) };
+/// # 	static ENGINE_SERVER: u8 = 0;
+/// # 	let exposed = name == c"VEngineServer021" || name == c"VEngineServer023";
+/// # 	if exposed { core::ptr::from_ref(&ENGINE_SERVER).cast_mut().cast() } else { core::ptr::null_mut() }
+/// # }
+/// // `create_interface` is the `CreateInterface` of a module
+/// // that exposes `VEngineServer021` and `VEngineServer023`.
+/// let factory = unsafe { InterfaceFactory::new(create_interface) };
+/// assert!(factory.create(c"VEngineServer023").is_some());
+/// assert!(factory.create(c"VEngineServer022").is_none());
+/// 
+/// let (name, version, _) = factory.create_latest("VEngineServer").unwrap();
+/// assert_eq!((name.as_c_str(), version), (c"VEngineServer023", 23));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct InterfaceFactory {
+	create_interface: CreateInterfaceFn,
+}
+
+impl InterfaceFactory {
+	/// Highest version that [`InterfaceFactory::create_latest`] tries.
+	pub const MAX_VERSION: u32 = 999;
+
+	/// Wrap the `CreateInterface` function `create_interface`.
+	/// 
+	/// # Safety
+	/// `create_interface` must be valid as long as the factory is used.
+	pub const unsafe fn new(create_interface: CreateInterfaceFn) -> Self {
+		Self { create_interface }
+	}
+
+	/// Return the `CreateInterface` factory of `library`.
+	/// 
+	/// # Safety
+	/// The symbol must have the type of [`CreateInterfaceFn`],
+	/// and the library must stay loaded as long as the factory is used.
+	#[cfg(any(unix, windows))]
+	pub unsafe fn from_library(library: &Library) -> Option<Self> {
+		let symbol = library.symbol(c"CreateInterface")?;
+		// SAFETY: Guaranteed by the caller.
+		Some(unsafe { Self::new(::core::mem::transmute::<*mut c_void, CreateInterfaceFn>(symbol.as_ptr())) })
+	}
+
+	/// Return the raw `CreateInterface` function.
+	pub const fn as_raw(&self) -> CreateInterfaceFn {
+		self.create_interface
+	}
+
+	/// Return the interface `name`, or [`None`] if the module does not expose it.
+	pub fn create(&self, name: &CStr) -> Option<NonNull<c_void>> {
+		// Some factories only write the return code on failure.
+		let mut return_code = IFACE_OK;
+		let object = unsafe { (self.create_interface)(name.as_ptr(), &mut return_code) };
+		NonNull::new(object).filter(|_| return_code == IFACE_OK)
+	}
+
+	/// Return the interface `name` as a C++ object with the VTable `VTable`.
+	/// 
+	/// # Safety
+	/// The interface must have the VTable `VTable`, and stay valid for `'a`.
+	pub unsafe fn get<'a, VTable>(&self, name: &CStr) -> Option<&'a VtObject<VTable>> {
+		Some(unsafe { VtObject::from_ptr(self.create(name)?.cast()) })
+	}
+
+	/// Return the highest version of the interface `prefix` that the module exposes,
+	/// trying names that end in three digits from [`InterfaceFactory::MAX_VERSION`] down,
+	/// along with its name and version.
+	/// 
+	/// Use [`InterfaceRegs::latest`] instead if the list of the module is available,
+	/// since it does not need to guess the names.
+	pub fn create_latest(&self, prefix: &str) -> Option<(VersionedName, u32, NonNull<c_void>)> {
+		(0..=Self::MAX_VERSION).rev().find_map(|version| {
+			let name = VersionedName::new(prefix, version)?;
+			Some((name, version, self.create(name.as_c_str())?))
+		})
+	}
+}
+
+/// Versioned name of an interface, such as `VEngineServer023`,
+/// stored inline as a C string.
+#[derive(Clone, Copy)]
+pub struct VersionedName {
+	bytes: [u8; 64],
+}
+
+impl VersionedName {
+	/// Format `prefix` followed by `version` with at least three digits,
+	/// or return [`None`] if it does not fit or `prefix` contains a nul byte.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::VersionedName;
+	/// assert_eq!(VersionedName::new("VEngineServer", 23).unwrap().as_c_str(), c"VEngineServer023");
+	/// assert_eq!(VersionedName::new("ISteamClient", 1020).unwrap().as_c_str(), c"ISteamClient1020");
+	/// ```
+	pub fn new(prefix: &str, version: u32) -> Option<Self> {
+		use ::core::fmt::Write;
+
+		let mut writer = NameWriter { bytes: [0; 64], len: 0 };
+		write!(writer, "{prefix}{version:03}").ok()?;
+		if writer.bytes[..writer.len].contains(&0) {
+			return None;
+		}
+		Some(Self { bytes: writer.bytes })
+	}
+
+	/// Return the name as a C string.
+	pub fn as_c_str(&self) -> &CStr {
+		// SAFETY: `new` leaves at least one nul byte, and no nul byte before the end of the name.
+		unsafe { CStr::from_bytes_until_nul(&self.bytes).unwrap_unchecked() }
+	}
+}
+
+impl fmt::Debug for VersionedName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(self.as_c_str(), f)
+	}
+}
+
+impl ::core::ops::Deref for VersionedName {
+	type Target = CStr;
+
+	fn deref(&self) -> &CStr {
+		self.as_c_str()
+	}
+}
+
+struct NameWriter {
+	bytes: [u8; 64],
+	len: usize,
+}
+
+impl fmt::Write for NameWriter {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		// Keep the last byte for the nul terminator.
+		let end = self.len + s.len();
+		if end >= self.bytes.len() {
+			return Err(fmt::Error);
+		}
+		self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+		self.len = end;
+		Ok(())
+	}
+}
@@ -0,0 +1,1585 @@
+use ::core::{
+	fmt::{self, Write},
+	mem,
+	str,
+};
+use ::alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+const CONST: u8 = 1;
+const VOLATILE: u8 = 2;
+const RESTRICT: u8 = 4;
+
+/// Maximum nesting of names and types, to bound recursion on malformed symbols.
+const MAX_DEPTH: usize = 256;
+/// Maximum length of a demangled name, since substitutions can make it grow exponentially.
+const MAX_LEN: usize = 1 << 16;
+
+const OPERATORS: &[(&str, &str)] = &[
+	("nw", "operator new"), ("na", "operator new[]"), ("dl", "operator delete"), ("da", "operator delete[]"),
+	("aw", "operator co_await"),
+	("ps", "operator+"), ("ng", "operator-"), ("ad", "operator&"), ("de", "operator*"), ("co", "operator~"),
+	("pl", "operator+"), ("mi", "operator-"), ("ml", "operator*"), ("dv", "operator/"), ("rm", "operator%"),
+	("an", "operator&"), ("or", "operator|"), ("eo", "operator^"), ("aS", "operator="),
+	("pL", "operator+="), ("mI", "operator-="), ("mL", "operator*="), ("dV", "operator/="), ("rM", "operator%="),
+	("aN", "operator&="), ("oR", "operator|="), ("eO", "operator^="),
+	("ls", "operator<<"), ("rs", "operator>>"), ("lS", "operator<<="), ("rS", "operator>>="),
+	("eq", "operator=="), ("ne", "operator!="), ("lt", "operator<"), ("gt", "operator>"),
+	("le", "operator<="), ("ge", "operator>="), ("ss", "operator<=>"),
+	("nt", "operator!"), ("aa", "operator&&"), ("oo", "operator||"), ("pp", "operator++"), ("mm", "operator--"),
+	("cm", "operator,"), ("pm", "operator->*"), ("pt", "operator->"), ("cl", "operator()"), ("ix", "operator[]"),
+	("qu", "operator?"),
+];
+
+/// Return the name of an operator, such as `operator+` for `pl`.
+fn operator(code: &[u8]) -> Option<&'static str> {
+	OPERATORS.iter().find(|(op, _)| op.as_bytes() == code).map(|&(_, name)| name)
+}
+
+/// A demangled entity, referring to other nodes by their index.
+#[derive(Debug, Clone)]
+enum Node {
+	/// An identifier, builtin type or literal.
+	Name(String),
+	/// A name or function parameter in an expression,
+	/// which is not parenthesized when it is an operand.
+	Id(String),
+	/// `scope::name`.
+	Nested { scope: usize, name: usize },
+	/// `name<args...>`.
+	Template { name: usize, args: Vec<usize> },
+	/// A template argument pack.
+	Pack(Vec<usize>),
+	/// A pack expansion in a parameter list.
+	Expansion(usize),
+	/// A type with `const`, `volatile` or `restrict` qualifiers.
+	Qualified { inner: usize, quals: u8 },
+	/// A pointer or reference.
+	Pointer { inner: usize, sigil: &'static str },
+	Function { ret: usize, params: Vec<usize>, ref_qual: &'static str },
+	Array { inner: usize, dim: String },
+	/// A pointer to member.
+	Member { class: usize, member: usize },
+	/// A function with its parameters.
+	Encoding { name: usize, ret: Option<usize>, params: Vec<usize>, quals: u8, ref_qual: &'static str },
+	/// An entity declared within a function.
+	Local { function: usize, entity: usize },
+	Concat(Vec<usize>),
+	/// A template parameter in the substitution table, which refers to
+	/// the template arguments in scope where it is substituted,
+	/// except in references after `arg` is set by the first one.
+	Param { index: usize, arg: Option<usize> },
+}
+
+struct NameInfo {
+	node: usize,
+	quals: u8,
+	ref_qual: &'static str,
+	/// The last template arguments in the name, which template parameters refer to.
+	args: Option<Vec<usize>>,
+	/// Whether the name ends with template arguments.
+	template: bool,
+	/// Whether the name is a constructor, destructor or conversion operator,
+	/// which have no return type even when they are templates.
+	no_return: bool,
+}
+
+impl NameInfo {
+	fn new(node: usize) -> Self {
+		Self { node, quals: 0, ref_qual: "", args: None, template: false, no_return: false }
+	}
+}
+
+struct Parser<'a> {
+	input: &'a [u8],
+	pos: usize,
+	depth: usize,
+	nodes: Vec<Node>,
+	subs: Vec<usize>,
+	template_args: Vec<usize>,
+	no_return: bool,
+}
+
+impl<'a> Parser<'a> {
+	fn new(input: &'a str) -> Self {
+		Self {
+			input: input.as_bytes(),
+			pos: 0,
+			depth: 0,
+			nodes: Vec::new(),
+			subs: Vec::new(),
+			template_args: Vec::new(),
+			no_return: false,
+		}
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.input.get(self.pos).copied()
+	}
+
+	fn peek_at(&self, offset: usize) -> Option<u8> {
+		self.input.get(self.pos + offset).copied()
+	}
+
+	fn next(&mut self) -> Option<u8> {
+		let byte = self.peek()?;
+		self.pos += 1;
+		Some(byte)
+	}
+
+	fn eat(&mut self, byte: u8) -> bool {
+		let eaten = self.peek() == Some(byte);
+		if eaten {
+			self.pos += 1;
+		}
+		eaten
+	}
+
+	fn eat_str(&mut self, s: &str) -> bool {
+		let eaten = self.input[self.pos..].starts_with(s.as_bytes());
+		if eaten {
+			self.pos += s.len();
+		}
+		eaten
+	}
+
+	fn expect(&mut self, byte: u8) -> Option<()> {
+		self.eat(byte).then_some(())
+	}
+
+	fn rest(&self) -> &'a [u8] {
+		&self.input[self.pos..]
+	}
+
+	fn push(&mut self, node: Node) -> usize {
+		self.nodes.push(node);
+		self.nodes.len() - 1
+	}
+
+	fn name_node(&mut self, name: impl Into<String>) -> usize {
+		self.push(Node::Name(name.into()))
+	}
+
+	fn print(&self, node: usize) -> Option<String> {
+		let mut printer = Printer::new(&self.nodes);
+		printer.print(node)?;
+		Some(printer.out)
+	}
+
+	fn enter(&mut self) -> Option<()> {
+		self.depth += 1;
+		(self.depth <= MAX_DEPTH).then_some(())
+	}
+
+	/// `<number> ::= [n] <digits>`
+	fn number(&mut self) -> Option<i64> {
+		let negative = self.eat(b'n');
+		let start = self.pos;
+		while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+			self.pos += 1;
+		}
+		let value: i64 = str::from_utf8(&self.input[start..self.pos]).ok()?.parse().ok()?;
+		Some(if negative { -value } else { value })
+	}
+
+	/// `<seq-id> _`, returning 0 for a bare `_` and the base 36 value plus one otherwise.
+	fn seq_id(&mut self) -> Option<usize> {
+		let mut value = 0usize;
+		let mut any = false;
+		loop {
+			let digit = match self.next()? {
+				b'_' => return if any { value.checked_add(1) } else { Some(0) },
+				byte @ b'0'..=b'9' => byte - b'0',
+				byte @ b'A'..=b'Z' => byte - b'A' + 10,
+				_ => return None,
+			};
+			value = value.checked_mul(36)?.checked_add(digit as usize)?;
+			any = true;
+		}
+	}
+
+	/// `<source-name> ::= <length> <identifier>`
+	fn source_name(&mut self) -> Option<String> {
+		if !self.peek()?.is_ascii_digit() {
+			return None;
+		}
+		let len = usize::try_from(self.number()?).ok()?;
+		let name = self.input.get(self.pos..self.pos.checked_add(len)?)?;
+		let name = str::from_utf8(name).ok()?;
+		self.pos += len;
+		Some(if name.starts_with("_GLOBAL__N") { "(anonymous namespace)".into() } else { name.into() })
+	}
+
+	fn cv_qualifiers(&mut self) -> u8 {
+		let mut quals = 0;
+		if self.eat(b'r') {
+			quals |= RESTRICT;
+		}
+		if self.eat(b'V') {
+			quals |= VOLATILE;
+		}
+		if self.eat(b'K') {
+			quals |= CONST;
+		}
+		quals
+	}
+
+	fn encoding(&mut self) -> Option<usize> {
+		self.enter()?;
+		let saved = mem::take(&mut self.template_args);
+		let encoding = self.encoding_inner();
+		self.template_args = saved;
+		self.depth -= 1;
+		encoding
+	}
+
+	fn encoding_inner(&mut self) -> Option<usize> {
+		if matches!(self.peek()?, b'T' | b'G') {
+			return self.special_name();
+		}
+		let info = self.name()?;
+		if let Some(args) = info.args {
+			self.template_args = args;
+		}
+		let at_end = |parser: &Self| matches!(parser.peek(), None | Some(b'E' | b'.'));
+		if at_end(self) {
+			return Some(info.node);
+		}
+		let ret = match info.template && !info.no_return {
+			true => Some(self.type_()?),
+			false => None,
+		};
+		let mut params = Vec::new();
+		while !at_end(self) {
+			params.push(self.type_()?);
+		}
+		Some(self.push(Node::Encoding { name: info.node, ret, params, quals: info.quals, ref_qual: info.ref_qual }))
+	}
+
+	fn call_offset(&mut self) -> Option<()> {
+		let is_virtual = match self.next()? {
+			b'h' => false,
+			b'v' => true,
+			_ => return None,
+		};
+		self.number()?;
+		self.expect(b'_')?;
+		if is_virtual {
+			self.number()?;
+			self.expect(b'_')?;
+		}
+		Some(())
+	}
+
+	fn special_name(&mut self) -> Option<usize> {
+		let (prefix, inner) = match self.input.get(self.pos..self.pos + 2)? {
+			b"TV" => ("vtable for ", None),
+			b"TT" => ("VTT for ", None),
+			b"TI" => ("typeinfo for ", None),
+			b"TS" => ("typeinfo name for ", None),
+			b"Th" | b"Tv" => {
+				let prefix = if self.peek_at(1)? == b'h' { "non-virtual thunk to " } else { "virtual thunk to " };
+				self.pos += 1;
+				self.call_offset()?;
+				(prefix, Some(self.encoding()?))
+			}
+			b"Tc" => {
+				self.pos += 2;
+				self.call_offset()?;
+				self.call_offset()?;
+				("covariant return thunk to ", Some(self.encoding()?))
+			}
+			b"TC" => {
+				self.pos += 2;
+				let derived = self.type_()?;
+				self.number()?;
+				self.expect(b'_')?;
+				let base = self.type_()?;
+				let prefix = self.name_node("construction vtable for ");
+				let infix = self.name_node("-in-");
+				return Some(self.push(Node::Concat([prefix, base, infix, derived].to_vec())));
+			}
+			b"TW" | b"TH" | b"GV" => {
+				let prefix = match self.peek_at(1)? {
+					b'W' => "TLS wrapper function for ",
+					b'H' => "TLS init function for ",
+					_ => "guard variable for ",
+				};
+				self.pos += 2;
+				(prefix, Some(self.name()?.node))
+			}
+			b"GT" => {
+				self.pos += 2;
+				if !matches!(self.next()?, b't' | b'n') {
+					return None;
+				}
+				("transaction clone for ", Some(self.encoding()?))
+			}
+			b"GR" => {
+				self.pos += 2;
+				let name = self.name()?.node;
+				let index = self.seq_id()?;
+				let prefix = self.name_node(format!("reference temporary #{index} for "));
+				return Some(self.push(Node::Concat([prefix, name].to_vec())));
+			}
+			_ => return None,
+		};
+		let inner = match inner {
+			Some(inner) => inner,
+			None => {
+				self.pos += 2;
+				self.type_()?
+			}
+		};
+		let prefix = self.name_node(prefix);
+		Some(self.push(Node::Concat([prefix, inner].to_vec())))
+	}
+
+	fn name(&mut self) -> Option<NameInfo> {
+		self.enter()?;
+		let name = self.name_inner();
+		self.depth -= 1;
+		name
+	}
+
+	fn name_inner(&mut self) -> Option<NameInfo> {
+		match self.peek()? {
+			b'N' => return self.nested_name(),
+			b'Z' => return self.local_name(),
+			_ => {}
+		}
+		let mut info;
+		let mut is_sub = false;
+		if self.eat_str("St") {
+			let scope = self.name_node("std");
+			let name = self.unqualified_name(Some(scope))?;
+			info = NameInfo::new(self.push(Node::Nested { scope, name }));
+			info.no_return = self.no_return;
+		} else if self.peek() == Some(b'S') {
+			// A substitution is only a name when it is a template.
+			info = NameInfo::new(self.substitution()?);
+			is_sub = true;
+			if self.peek() != Some(b'I') {
+				return None;
+			}
+		} else {
+			info = NameInfo::new(self.unqualified_name(None)?);
+			info.no_return = self.no_return;
+		}
+		if self.peek() == Some(b'I') {
+			// An unscoped template name is a substitution candidate.
+			if !is_sub {
+				self.subs.push(info.node);
+			}
+			let args = self.template_args()?;
+			info.node = self.push(Node::Template { name: info.node, args: args.clone() });
+			info.args = Some(args);
+			info.template = true;
+		}
+		Some(info)
+	}
+
+	fn nested_name(&mut self) -> Option<NameInfo> {
+		self.expect(b'N')?;
+		let quals = self.cv_qualifiers();
+		let ref_qual = if self.eat(b'R') {
+			" &"
+		} else if self.eat(b'O') {
+			" &&"
+		} else {
+			""
+		};
+		let mut info = NameInfo { quals, ref_qual, ..NameInfo::new(0) };
+		let mut current = None;
+		loop {
+			let next = match self.peek()? {
+				b'E' => break,
+				b'S' if self.peek_at(1) == Some(b't') => {
+					self.pos += 2;
+					current = Some(self.name_node("std"));
+					continue;
+				}
+				b'S' => {
+					current = Some(self.substitution()?);
+					continue;
+				}
+				b'I' => {
+					let name = current?;
+					let args = self.template_args()?;
+					info.template = true;
+					info.args = Some(args.clone());
+					self.push(Node::Template { name, args })
+				}
+				b'T' => {
+					info.template = false;
+					self.template_param()?
+				}
+				_ => {
+					let name = self.unqualified_name(current)?;
+					info.template = false;
+					info.no_return = self.no_return;
+					match current {
+						Some(scope) => self.push(Node::Nested { scope, name }),
+						None => name,
+					}
+				}
+			};
+			current = Some(next);
+			// Every prefix is a substitution candidate, but the complete name is not.
+			if self.peek() != Some(b'E') {
+				self.subs.push(next);
+			}
+		}
+		self.pos += 1;
+		info.node = current?;
+		Some(info)
+	}
+
+	fn local_name(&mut self) -> Option<NameInfo> {
+		self.expect(b'Z')?;
+		let function = self.encoding()?;
+		self.expect(b'E')?;
+		if self.eat(b's') {
+			self.discriminator();
+			let entity = self.name_node("string literal");
+			return Some(NameInfo::new(self.push(Node::Local { function, entity })));
+		}
+		if self.eat(b'd') {
+			// A default argument, numbered from the last parameter.
+			let _ = self.number();
+			self.expect(b'_')?;
+		}
+		let mut info = self.name()?;
+		self.discriminator();
+		info.node = self.push(Node::Local { function, entity: info.node });
+		Some(info)
+	}
+
+	fn discriminator(&mut self) {
+		if self.eat_str("__") {
+			let _ = self.number();
+			self.eat(b'_');
+		} else if self.peek() == Some(b'_') && self.peek_at(1).is_some_and(|byte| byte.is_ascii_digit()) {
+			self.pos += 2;
+		}
+	}
+
+	fn unqualified_name(&mut self, scope: Option<usize>) -> Option<usize> {
+		self.no_return = false;
+		// Internal linkage, as emitted by GCC.
+		self.eat(b'L');
+		let mut name = match self.peek()? {
+			b'0'..=b'9' => self.source_name()?,
+			b'C' => {
+				self.pos += 1;
+				let inheriting = self.eat(b'I');
+				if !matches!(self.next()?, b'1'..=b'5') {
+					return None;
+				}
+				if inheriting {
+					self.type_()?;
+				}
+				self.no_return = true;
+				self.base_name(scope?)?
+			}
+			b'D' if matches!(self.peek_at(1)?, b'0'..=b'5') => {
+				self.pos += 2;
+				self.no_return = true;
+				format!("~{}", self.base_name(scope?)?)
+			}
+			b'U' => self.unnamed_type()?,
+			b'a'..=b'z' => {
+				let name = self.operator_name()?;
+				self.no_return = name.starts_with("operator ") && !name.starts_with("operator new")
+					&& !name.starts_with("operator delete") && !name.starts_with("operator co_await");
+				name
+			}
+			_ => return None,
+		};
+		while self.eat(b'B') {
+			let tag = self.source_name()?;
+			write!(name, "[abi:{tag}]").ok()?;
+		}
+		Some(self.name_node(name))
+	}
+
+	/// Return the unqualified name of a class, for its constructors and destructors.
+	fn base_name(&self, node: usize) -> Option<String> {
+		match &self.nodes[node] {
+			Node::Name(name) => Some(name.split('[').next().unwrap_or(name).to_string()),
+			// Unnamed types are named after the enclosing class.
+			Node::Nested { scope, name } if matches!(&self.nodes[*name], Node::Name(name) if name.starts_with("{unnamed type#")) => {
+				self.base_name(*scope)
+			}
+			Node::Nested { name, .. } | Node::Template { name, .. } => self.base_name(*name),
+			_ => None,
+		}
+	}
+
+	fn operator_name(&mut self) -> Option<String> {
+		let code = self.input.get(self.pos..self.pos + 2)?;
+		self.pos += 2;
+		match code {
+			b"cv" => {
+				let ty = self.type_()?;
+				Some(format!("operator {}", self.print(ty)?))
+			}
+			b"li" => Some(format!("operator\"\" {}", self.source_name()?)),
+			[b'v', digit] if digit.is_ascii_digit() => Some(format!("operator {}", self.source_name()?)),
+			_ => operator(code).map(str::to_string),
+		}
+	}
+
+	fn unnamed_type(&mut self) -> Option<String> {
+		self.expect(b'U')?;
+		match self.next()? {
+			b't' => Some(format!("{{unnamed type#{}}}", self.seq_id()? + 1)),
+			b'l' => {
+				let mut params = Vec::new();
+				while !self.eat(b'E') {
+					params.push(self.type_()?);
+				}
+				let index = self.seq_id()? + 1;
+				let mut printer = Printer::new(&self.nodes);
+				printer.params(&params)?;
+				Some(format!("{{lambda({})#{index}}}", printer.out))
+			}
+			_ => None,
+		}
+	}
+
+	fn substitution(&mut self) -> Option<usize> {
+		self.expect(b'S')?;
+		let (name, args): (&str, &[&str]) = match self.peek()? {
+			b'a' => ("allocator", &[]),
+			b'b' => ("basic_string", &[]),
+			b's' => ("basic_string", &["char", "std::char_traits<char>", "std::allocator<char>"]),
+			b'i' => ("basic_istream", &["char", "std::char_traits<char>"]),
+			b'o' => ("basic_ostream", &["char", "std::char_traits<char>"]),
+			b'd' => ("basic_iostream", &["char", "std::char_traits<char>"]),
+			_ => {
+				let id = self.seq_id()?;
+				let sub = *self.subs.get(id)?;
+				return match self.nodes[sub] {
+					Node::Param { index, .. } => self.template_args.get(index).copied(),
+					_ => Some(sub),
+				};
+			}
+		};
+		self.pos += 1;
+		let scope = self.name_node("std");
+		let mut name = self.name_node(name);
+		if !args.is_empty() {
+			let args = args.iter().map(|&arg| self.name_node(arg)).collect();
+			name = self.push(Node::Template { name, args });
+		}
+		Some(self.push(Node::Nested { scope, name }))
+	}
+
+	fn template_param(&mut self) -> Option<usize> {
+		let index = self.template_param_index()?;
+		self.template_args.get(index).copied()
+	}
+
+	fn template_param_index(&mut self) -> Option<usize> {
+		self.expect(b'T')?;
+		self.seq_id()
+	}
+
+	fn template_args(&mut self) -> Option<Vec<usize>> {
+		self.expect(b'I')?;
+		let mut args = Vec::new();
+		while !self.eat(b'E') {
+			args.push(self.template_arg()?);
+		}
+		Some(args)
+	}
+
+	fn template_arg(&mut self) -> Option<usize> {
+		match self.peek()? {
+			b'L' => self.expr_primary(),
+			b'X' => {
+				self.pos += 1;
+				let expr = self.expression()?;
+				self.expect(b'E')?;
+				Some(expr)
+			}
+			b'J' => {
+				self.pos += 1;
+				let mut args = Vec::new();
+				while !self.eat(b'E') {
+					args.push(self.template_arg()?);
+				}
+				Some(self.push(Node::Pack(args)))
+			}
+			_ => self.type_(),
+		}
+	}
+
+	/// Common expressions are supported: literals, operators, calls, casts, `sizeof`,
+	/// member access, and names of parameters, template parameters and other entities.
+	fn expression(&mut self) -> Option<usize> {
+		self.enter()?;
+		let expr = self.expression_inner();
+		self.depth -= 1;
+		expr
+	}
+
+	fn expression_inner(&mut self) -> Option<usize> {
+		match self.peek()? {
+			b'T' => return self.template_param(),
+			b'L' => return self.expr_primary(),
+			b'0'..=b'9' => {
+				let name = self.source_name()?;
+				if self.peek() != Some(b'I') {
+					return Some(self.push(Node::Id(name)));
+				}
+				let name = self.name_node(name);
+				let args = self.template_args()?;
+				return Some(self.push(Node::Template { name, args }));
+			}
+			_ => {}
+		}
+		let code = self.input.get(self.pos..self.pos + 2)?;
+		self.pos += 2;
+		let text = match code {
+			b"fp" => {
+				self.cv_qualifiers();
+				let param = format!("{{parm#{}}}", self.seq_id()? + 1);
+				return Some(self.push(Node::Id(param)));
+			}
+			b"sr" => {
+				// Only template parameters, decltypes and substitutions are substitution candidates here,
+				// not the names the scope is made of.
+				let mut scope = Vec::new();
+				let nested = self.eat(b'N');
+				if nested || matches!(self.peek()?, b'T' | b'D' | b'S') {
+					let ty = self.type_()?;
+					scope.push(self.print(ty)?);
+				}
+				if nested || scope.is_empty() {
+					while !self.eat(b'E') {
+						scope.push(self.simple_id()?);
+					}
+				}
+				let name = match self.peek()? {
+					b'0'..=b'9' => self.simple_id()?,
+					_ => {
+						let name = self.expression()?;
+						self.print(name)?
+					}
+				};
+				let name = format!("{}::{name}", scope.join("::"));
+				if !name.ends_with('>') {
+					return Some(self.push(Node::Id(name)));
+				}
+				name
+			}
+			b"on" => {
+				let mut name = self.operator_name()?;
+				if self.peek() == Some(b'I') {
+					let args = self.template_args()?;
+					let mut printer = Printer::new(&self.nodes);
+					printer.list(&args)?;
+					write!(name, "<{}>", printer.out).ok()?;
+				}
+				name
+			}
+			b"cl" => {
+				let function = self.expression()?;
+				format!("{}({})", self.operand(function)?, self.expression_list()?)
+			}
+			b"tl" => {
+				let ty = self.type_()?;
+				format!("{}{{{}}}", self.print(ty)?, self.expression_list()?)
+			}
+			b"cv" => {
+				let ty = self.type_()?;
+				let value = match self.eat(b'_') {
+					true => format!("({})", self.expression_list()?),
+					false => {
+						let expr = self.expression()?;
+						self.operand(expr)?
+					}
+				};
+				format!("({}){value}", self.print(ty)?)
+			}
+			b"st" | b"at" => {
+				let ty = self.type_()?;
+				format!("{} ({})", if code == b"st" { "sizeof" } else { "alignof" }, self.print(ty)?)
+			}
+			b"sz" | b"az" => {
+				let expr = self.expression()?;
+				format!("{} {}", if code == b"sz" { "sizeof" } else { "alignof" }, self.operand(expr)?)
+			}
+			b"sZ" => {
+				let pack = self.template_param()?;
+				match &self.nodes[pack] {
+					Node::Pack(items) => items.len().to_string(),
+					_ => format!("sizeof...({})", self.print(pack)?),
+				}
+			}
+			b"dt" | b"pt" => {
+				let object = self.expression()?;
+				let member = self.expression()?;
+				let op = if code == b"dt" { "." } else { "->" };
+				format!("{}{op}{}", self.operand(object)?, self.print(member)?)
+			}
+			b"qu" => {
+				let condition = self.expression()?;
+				let then = self.expression()?;
+				let otherwise = self.expression()?;
+				format!("{}?{} : {}", self.operand(condition)?, self.operand(then)?, self.operand(otherwise)?)
+			}
+			b"ps" | b"ng" | b"ad" | b"de" | b"co" | b"nt" => {
+				let operand = self.expression()?;
+				format!("{}{}", &operator(code)?["operator".len()..], self.operand(operand)?)
+			}
+			_ => {
+				let op = operator(code).filter(|op| !op.starts_with("operator ") && !op.ends_with("()"))?;
+				let left = self.expression()?;
+				let right = self.expression()?;
+				match code {
+					b"ix" => format!("{}[{}]", self.operand(left)?, self.print(right)?),
+					// Parenthesized so as not to be read as the end of template arguments.
+					b"gt" => format!("({}>{})", self.operand(left)?, self.operand(right)?),
+					_ => format!("{}{}{}", self.operand(left)?, &op["operator".len()..], self.operand(right)?),
+				}
+			}
+		};
+		Some(self.name_node(text))
+	}
+
+	/// Print an operand of an expression, in parentheses unless it is a name.
+	fn operand(&mut self, expr: usize) -> Option<String> {
+		let text = self.print(expr)?;
+		match self.nodes[expr] {
+			Node::Id(_) => Some(text),
+			_ => Some(format!("({text})")),
+		}
+	}
+
+	/// `<simple-id> ::= <source-name> [<template-args>]`, which is not a substitution candidate.
+	fn simple_id(&mut self) -> Option<String> {
+		let name = self.source_name()?;
+		if self.peek() != Some(b'I') {
+			return Some(name);
+		}
+		let name = self.name_node(name);
+		let args = self.template_args()?;
+		let id = self.push(Node::Template { name, args });
+		self.print(id)
+	}
+
+	/// Read expressions up to an `E`, returning them separated by commas.
+	fn expression_list(&mut self) -> Option<String> {
+		let mut list = String::new();
+		while !self.eat(b'E') {
+			let expr = self.expression()?;
+			if !list.is_empty() {
+				list.push_str(", ");
+			}
+			list.push_str(&self.print(expr)?);
+		}
+		Some(list)
+	}
+
+	fn expr_primary(&mut self) -> Option<usize> {
+		self.expect(b'L')?;
+		if self.eat_str("_Z") {
+			let encoding = self.encoding()?;
+			self.expect(b'E')?;
+			return Some(encoding);
+		}
+		let code = self.peek()?;
+		let ty = self.type_()?;
+		let sign = if self.eat(b'n') { "-" } else { "" };
+		let start = self.pos;
+		while self.peek()? != b'E' {
+			self.pos += 1;
+		}
+		let value = str::from_utf8(&self.input[start..self.pos]).ok()?;
+		self.pos += 1;
+		let text = match (code, value) {
+			(b'b', "0") => "false".into(),
+			(b'b', "1") => "true".into(),
+			(b'i', _) => format!("{sign}{value}"),
+			(b'j', _) => format!("{sign}{value}u"),
+			(b'l', _) => format!("{sign}{value}l"),
+			(b'm', _) => format!("{sign}{value}ul"),
+			(b'x', _) => format!("{sign}{value}ll"),
+			(b'y', _) => format!("{sign}{value}ull"),
+			_ => format!("({}){sign}{value}", self.print(ty)?),
+		};
+		Some(self.name_node(text))
+	}
+
+	fn builtin(&mut self) -> Option<&'static str> {
+		let name = match self.peek()? {
+			b'v' => "void",
+			b'w' => "wchar_t",
+			b'b' => "bool",
+			b'c' => "char",
+			b'a' => "signed char",
+			b'h' => "unsigned char",
+			b's' => "short",
+			b't' => "unsigned short",
+			b'i' => "int",
+			b'j' => "unsigned int",
+			b'l' => "long",
+			b'm' => "unsigned long",
+			b'x' => "long long",
+			b'y' => "unsigned long long",
+			b'n' => "__int128",
+			b'o' => "unsigned __int128",
+			b'f' => "float",
+			b'd' => "double",
+			b'e' => "long double",
+			b'g' => "__float128",
+			b'z' => "...",
+			b'D' => {
+				let name = match self.peek_at(1)? {
+					b'd' => "decimal64",
+					b'e' => "decimal128",
+					b'f' => "decimal32",
+					b'h' => "half",
+					b'i' => "char32_t",
+					b's' => "char16_t",
+					b'u' => "char8_t",
+					b'a' => "auto",
+					b'c' => "decltype(auto)",
+					b'n' => "decltype(nullptr)",
+					_ => return None,
+				};
+				self.pos += 2;
+				return Some(name);
+			}
+			_ => return None,
+		};
+		self.pos += 1;
+		Some(name)
+	}
+
+	fn type_(&mut self) -> Option<usize> {
+		self.enter()?;
+		let ty = self.type_inner();
+		self.depth -= 1;
+		ty
+	}
+
+	fn type_inner(&mut self) -> Option<usize> {
+		if let Some(builtin) = self.builtin() {
+			return Some(self.name_node(builtin));
+		}
+		let node = match self.peek()? {
+			b'r' | b'V' | b'K' => {
+				let quals = self.cv_qualifiers();
+				let inner = self.type_()?;
+				// Qualifiers of a template parameter combine with its own.
+				match self.nodes[inner] {
+					Node::Qualified { inner, quals: inner_quals } => Node::Qualified { inner, quals: quals | inner_quals },
+					_ => Node::Qualified { inner, quals },
+				}
+			}
+			b'P' | b'R' | b'O' => {
+				let sigil = match self.next()? {
+					b'P' => "*",
+					b'R' => "&",
+					_ => "&&",
+				};
+				let inner = match sigil {
+					"*" => self.type_()?,
+					_ => self.referenced_type()?,
+				};
+				Node::Pointer { inner, sigil }
+			}
+			b'C' | b'G' => {
+				let suffix = if self.next()? == b'C' { " _Complex" } else { " _Imaginary" };
+				let inner = self.type_()?;
+				Node::Name(self.print(inner)? + suffix)
+			}
+			b'u' => {
+				self.pos += 1;
+				Node::Name(self.source_name()?)
+			}
+			b'F' => {
+				self.pos += 1;
+				self.eat(b'Y');
+				let ret = self.type_()?;
+				let mut params = Vec::new();
+				let ref_qual = loop {
+					match (self.peek()?, self.peek_at(1)) {
+						(b'E', _) => break "",
+						(b'R', Some(b'E')) => break " &",
+						(b'O', Some(b'E')) => break " &&",
+						_ => params.push(self.type_()?),
+					}
+				};
+				self.pos += if ref_qual.is_empty() { 1 } else { 2 };
+				Node::Function { ret, params, ref_qual }
+			}
+			b'A' => {
+				self.pos += 1;
+				let dim = match self.peek()? {
+					b'0'..=b'9' => self.number()?.to_string(),
+					b'_' => String::new(),
+					_ => {
+						let expr = self.expression()?;
+						self.print(expr)?
+					}
+				};
+				self.expect(b'_')?;
+				Node::Array { inner: self.type_()?, dim }
+			}
+			b'M' => {
+				self.pos += 1;
+				let class = self.type_()?;
+				let quals = self.input[self.pos..].iter().take_while(|&&byte| matches!(byte, b'r' | b'V' | b'K')).count();
+				let member = if quals > 0 && self.peek_at(quals) == Some(b'F') {
+					// The qualifiers of a member function are part of its type,
+					// so the function type alone is not a substitution candidate.
+					let quals = self.cv_qualifiers();
+					let function = self.type_()?;
+					self.subs.pop();
+					let member = self.push(Node::Qualified { inner: function, quals });
+					self.subs.push(member);
+					member
+				} else {
+					self.type_()?
+				};
+				Node::Member { class, member }
+			}
+			b'T' => {
+				let index = self.template_param_index()?;
+				let param = *self.template_args.get(index)?;
+				let sub = self.push(Node::Param { index, arg: None });
+				self.subs.push(sub);
+				if self.peek() != Some(b'I') {
+					return Some(param);
+				}
+				Node::Template { name: param, args: self.template_args()? }
+			}
+			b'D' => match self.peek_at(1)? {
+				b'p' => {
+					self.pos += 2;
+					Node::Expansion(self.type_()?)
+				}
+				b't' | b'T' => {
+					self.pos += 2;
+					let expr = self.expression()?;
+					self.expect(b'E')?;
+					Node::Name(format!("decltype ({})", self.print(expr)?))
+				}
+				_ => return None,
+			},
+			b'S' if self.peek_at(1) != Some(b't') => {
+				let sub = self.substitution()?;
+				if self.peek() != Some(b'I') {
+					return Some(sub);
+				}
+				Node::Template { name: sub, args: self.template_args()? }
+			}
+			_ => {
+				let node = self.name()?.node;
+				self.subs.push(node);
+				return Some(node);
+			}
+		};
+		let node = self.push(node);
+		self.subs.push(node);
+		Some(node)
+	}
+
+	/// Parse the type of a reference. Like c++filt, a template parameter referred to by references
+	/// keeps the template arguments in scope at the first one, even when it is substituted elsewhere.
+	fn referenced_type(&mut self) -> Option<usize> {
+		let start = self.pos;
+		let param = match (self.peek()?, self.peek_at(1)) {
+			(b'T', _) => {
+				let inner = self.type_()?;
+				// A template template parameter with arguments ends with `E` instead.
+				match self.subs.last() {
+					Some(&sub) if matches!(self.nodes[sub], Node::Param { .. }) && self.input[self.pos - 1] == b'_' => sub,
+					_ => return Some(inner),
+				}
+			}
+			(b'S', Some(b'_' | b'0'..=b'9' | b'A'..=b'Z')) => {
+				self.pos += 1;
+				let id = self.seq_id()?;
+				let sub = *self.subs.get(id)?;
+				if !matches!(self.nodes[sub], Node::Param { .. }) || self.peek() == Some(b'I') {
+					self.pos = start;
+					return self.type_();
+				}
+				sub
+			}
+			_ => return self.type_(),
+		};
+		let Node::Param { index, arg } = &mut self.nodes[param] else {
+			return None;
+		};
+		match arg {
+			Some(arg) => Some(*arg),
+			None => {
+				let resolved = *self.template_args.get(*index)?;
+				*arg = Some(resolved);
+				Some(resolved)
+			}
+		}
+	}
+
+	/// Split a class type into its components.
+	fn path(&self, node: usize, path: &mut Vec<NamePart>) -> Option<()> {
+		match &self.nodes[node] {
+			Node::Name(name) => path.push(NamePart { name: name.clone(), template_args: Vec::new() }),
+			Node::Nested { scope, name } => {
+				self.path(*scope, path)?;
+				self.path(*name, path)?;
+			}
+			Node::Template { name, args } => {
+				self.path(*name, path)?;
+				let mut template_args = Vec::new();
+				for &arg in args {
+					match &self.nodes[arg] {
+						Node::Pack(items) => for &item in items {
+							template_args.push(self.print(item)?);
+						},
+						_ => template_args.push(self.print(arg)?),
+					}
+				}
+				path.last_mut()?.template_args = template_args;
+			}
+			Node::Local { function, entity } => {
+				path.push(NamePart { name: self.print(*function)?, template_args: Vec::new() });
+				self.path(*entity, path)?;
+			}
+			_ => return None,
+		}
+		Some(())
+	}
+}
+
+struct Printer<'a> {
+	nodes: &'a [Node],
+	out: String,
+	/// Packs being expanded, with the index of the element being printed.
+	packs: Vec<(usize, usize)>,
+	/// Whether the last element of a list was an empty pack, after which
+	/// `>`s are not separated, as c++filt still counts the removed `, `.
+	after_empty: bool,
+	depth: usize,
+}
+
+impl<'a> Printer<'a> {
+	fn new(nodes: &'a [Node]) -> Self {
+		Self { nodes, out: String::new(), packs: Vec::new(), after_empty: false, depth: 0 }
+	}
+
+	fn text(&mut self, text: &str) -> Option<()> {
+		self.after_empty &= text.is_empty();
+		self.out.push_str(text);
+		(self.out.len() <= MAX_LEN).then_some(())
+	}
+
+	fn print(&mut self, node: usize) -> Option<()> {
+		self.depth += 1;
+		if self.depth > MAX_DEPTH * 4 {
+			return None;
+		}
+		self.left(node)?;
+		self.right(node)?;
+		self.depth -= 1;
+		Some(())
+	}
+
+	/// Return the current element of a pack, while printing a pack expansion of it.
+	/// 
+	/// Packs within the elements are not part of the expansion, and are printed as lists.
+	fn resolve(&self, node: usize) -> usize {
+		match &self.nodes[node] {
+			Node::Pack(items) => self.packs.iter()
+				.find(|&&(pack, _)| pack == node)
+				.and_then(|&(_, index)| items.get(index).copied())
+				.unwrap_or(node),
+			_ => node,
+		}
+	}
+
+	/// Collect the packs that a pack expansion expands.
+	fn expanded_packs(&self, node: usize, packs: &mut Vec<usize>) {
+		let node = self.resolve(node);
+		match &self.nodes[node] {
+			Node::Pack(_) if !packs.contains(&node) => packs.push(node),
+			Node::Nested { scope, name } => {
+				self.expanded_packs(*scope, packs);
+				self.expanded_packs(*name, packs);
+			}
+			Node::Template { name, args } => {
+				self.expanded_packs(*name, packs);
+				args.iter().for_each(|&arg| self.expanded_packs(arg, packs));
+			}
+			Node::Qualified { inner, .. } | Node::Pointer { inner, .. } | Node::Array { inner, .. } => {
+				self.expanded_packs(*inner, packs);
+			}
+			Node::Function { ret, params, .. } => {
+				self.expanded_packs(*ret, packs);
+				params.iter().for_each(|&param| self.expanded_packs(param, packs));
+			}
+			Node::Member { class, member } => {
+				self.expanded_packs(*class, packs);
+				self.expanded_packs(*member, packs);
+			}
+			_ => {}
+		}
+	}
+
+	/// Collapse references to references, as in `T&&` where `T` is `int&`.
+	fn pointer(&self, inner: usize, sigil: &'static str) -> (usize, &'static str) {
+		let (mut inner, mut sigil) = (self.resolve(inner), sigil);
+		while sigil != "*" {
+			match self.nodes[inner] {
+				Node::Pointer { inner: next, sigil: next_sigil } if next_sigil != "*" => {
+					sigil = if sigil == "&" || next_sigil == "&" { "&" } else { "&&" };
+					inner = self.resolve(next);
+				}
+				_ => break,
+			}
+		}
+		(inner, sigil)
+	}
+
+	fn is_function(&self, node: usize) -> bool {
+		matches!(self.nodes[self.resolve(node)], Node::Function { .. })
+	}
+
+	/// Return `true` if the type is written around its declarator,
+	/// so pointers to it need parentheses.
+	fn is_declarator(&self, node: usize) -> bool {
+		match self.nodes[self.resolve(node)] {
+			Node::Function { .. } | Node::Array { .. } => true,
+			Node::Qualified { inner, .. } => self.is_function(inner) || self.is_array(inner),
+			_ => false,
+		}
+	}
+
+	/// Return `true` if the type is a pointer that is written inside parentheses.
+	fn is_declarator_pointer(&self, node: usize) -> bool {
+		match self.nodes[self.resolve(node)] {
+			Node::Pointer { inner, sigil } => self.is_declarator(self.pointer(inner, sigil).0),
+			Node::Member { member, .. } => self.is_declarator(member),
+			_ => false,
+		}
+	}
+
+	fn is_array(&self, node: usize) -> bool {
+		match self.nodes[self.resolve(node)] {
+			Node::Array { .. } => true,
+			Node::Qualified { inner, .. } => self.is_array(inner),
+			_ => false,
+		}
+	}
+
+	/// Print the part of a type before its declarator.
+	fn left(&mut self, node: usize) -> Option<()> {
+		let nodes = self.nodes;
+		match &nodes[self.resolve(node)] {
+			Node::Name(name) | Node::Id(name) => self.text(name),
+			Node::Nested { scope, name } => {
+				self.print(*scope)?;
+				self.text("::")?;
+				self.print(*name)
+			}
+			Node::Template { name, args } => {
+				self.print(*name)?;
+				if self.out.ends_with('<') {
+					self.text(" ")?;
+				}
+				self.text("<")?;
+				self.list(args)?;
+				if self.out.ends_with('>') && !self.after_empty {
+					self.text(" ")?;
+				}
+				self.text(">")
+			}
+			Node::Pack(items) => self.list(items),
+			Node::Expansion(inner) => {
+				let mut packs = Vec::new();
+				self.expanded_packs(*inner, &mut packs);
+				let Some(len) = packs.iter()
+					.map(|&pack| match &nodes[pack] {
+						Node::Pack(items) => items.len(),
+						_ => 0,
+					})
+					.min()
+				else {
+					self.print(*inner)?;
+					return self.text("...");
+				};
+				let outer = self.packs.len();
+				let mut first = true;
+				for index in 0..len {
+					self.packs.truncate(outer);
+					self.packs.extend(packs.iter().map(|&pack| (pack, index)));
+					first &= !self.item(*inner, first)?;
+				}
+				self.packs.truncate(outer);
+				Some(())
+			}
+			Node::Qualified { inner, quals } => {
+				// Qualifiers of an array apply to its elements.
+				if let Node::Array { inner: element, .. } = nodes[self.resolve(*inner)] {
+					self.left(element)?;
+					self.quals(*quals)?;
+					return match self.is_array(element) || self.is_declarator_pointer(element) {
+						true => Some(()),
+						false => self.text(" "),
+					};
+				}
+				self.left(*inner)?;
+				match self.is_function(*inner) {
+					true => Some(()),
+					false => self.quals(*quals),
+				}
+			}
+			Node::Pointer { inner, sigil } => {
+				let (inner, sigil) = self.pointer(*inner, sigil);
+				self.left(inner)?;
+				if self.is_declarator(inner) {
+					self.text("(")?;
+				}
+				self.text(sigil)
+			}
+			Node::Function { ret: inner, .. } | Node::Array { inner, .. } => {
+				self.left(*inner)?;
+				match self.is_array(*inner) || self.is_declarator_pointer(*inner) {
+					true => Some(()),
+					false => self.text(" "),
+				}
+			}
+			Node::Member { class, member } => {
+				if self.is_declarator(*member) {
+					self.left(*member)?;
+					self.text("(")?;
+				} else {
+					self.print(*member)?;
+					self.text(" ")?;
+				}
+				self.print(*class)?;
+				self.text("::*")
+			}
+			Node::Encoding { .. } => self.encoding(node, true),
+			Node::Local { function, entity } => {
+				// The return type of the enclosing function is omitted.
+				self.encoding(*function, false)?;
+				self.text("::")?;
+				self.print(*entity)
+			}
+			Node::Concat(parts) => parts.iter().try_for_each(|&part| self.print(part)),
+			// Only found in the substitution table, which resolves it.
+			Node::Param { .. } => None,
+		}
+	}
+
+	fn encoding(&mut self, node: usize, with_ret: bool) -> Option<()> {
+		let Node::Encoding { name, ret, params, quals, ref_qual } = &self.nodes[node] else {
+			return self.print(node);
+		};
+		if let (Some(ret), true) = (ret, with_ret) {
+			self.print(*ret)?;
+			self.text(" ")?;
+		}
+		self.print(*name)?;
+		self.text("(")?;
+		self.params(params)?;
+		self.text(")")?;
+		self.quals(*quals)?;
+		self.text(ref_qual)
+	}
+
+	/// Print the part of a type after its declarator.
+	fn right(&mut self, node: usize) -> Option<()> {
+		let nodes = self.nodes;
+		match &nodes[self.resolve(node)] {
+			Node::Qualified { inner, quals } => {
+				self.right(*inner)?;
+				match self.is_function(*inner) {
+					true => self.quals(*quals),
+					false => Some(()),
+				}
+			}
+			Node::Pointer { inner, sigil } => {
+				let (inner, _) = self.pointer(*inner, sigil);
+				if self.is_declarator(inner) {
+					self.text(")")?;
+					if self.is_array(inner) {
+						self.text(" ")?;
+					}
+				}
+				self.right(inner)
+			}
+			Node::Function { ret, params, ref_qual } => {
+				self.text("(")?;
+				self.params(params)?;
+				self.text(")")?;
+				self.text(ref_qual)?;
+				self.right(*ret)
+			}
+			Node::Array { inner, dim } => {
+				self.text("[")?;
+				self.text(dim)?;
+				self.text("]")?;
+				self.right(*inner)
+			}
+			Node::Member { member, .. } if self.is_declarator(*member) => {
+				self.text(")")?;
+				self.right(*member)
+			}
+			_ => Some(()),
+		}
+	}
+
+	fn quals(&mut self, quals: u8) -> Option<()> {
+		if quals & CONST != 0 {
+			self.text(" const")?;
+		}
+		if quals & VOLATILE != 0 {
+			self.text(" volatile")?;
+		}
+		if quals & RESTRICT != 0 {
+			self.text(" restrict")?;
+		}
+		Some(())
+	}
+
+	/// Print a comma separated list, skipping empty packs.
+	fn list(&mut self, items: &[usize]) -> Option<()> {
+		let mut first = true;
+		for &item in items {
+			first &= !self.item(item, first)?;
+		}
+		Some(())
+	}
+
+	/// Print an element of a list, returning whether anything was printed.
+	fn item(&mut self, item: usize, first: bool) -> Option<bool> {
+		let start = self.out.len();
+		if !first {
+			self.text(", ")?;
+		}
+		let mark = self.out.len();
+		self.print(item)?;
+		if self.out.len() == mark {
+			self.out.truncate(start);
+			self.after_empty = !first;
+			return Some(false);
+		}
+		Some(true)
+	}
+
+	fn params(&mut self, params: &[usize]) -> Option<()> {
+		match params {
+			[param] if matches!(&self.nodes[*param], Node::Name(name) if name == "void") => Some(()),
+			_ => self.list(params),
+		}
+	}
+}
+
+/// Demangle a symbol mangled according to the Itanium C++ ABI.
+/// 
+/// Returns `None` if the symbol is not mangled, or uses constructs that are not supported,
+/// such as most expressions in template arguments.
+/// The output follows the format of `c++filt`.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::demangle;
+/// assert_eq!(demangle("_ZNK3zoo3Pet4legsEv").as_deref(), Some("zoo::Pet::legs() const"));
+/// assert_eq!(demangle("_ZThn8_N3zoo6Lizard4shedEv").as_deref(), Some("non-virtual thunk to zoo::Lizard::shed()"));
+/// assert_eq!(demangle("_ZTVN3zoo6LizardE").as_deref(), Some("vtable for zoo::Lizard"));
+/// assert_eq!(
+/// 	demangle("_ZNSt6vectorIS_IiSaIiEESaIS1_EE9push_backERKS1_").as_deref(),
+/// 	Some("std::vector<std::vector<int, std::allocator<int> >, std::allocator<std::vector<int, std::allocator<int> > > >::push_back(std::vector<int, std::allocator<int> > const&)"),
+/// );
+/// assert_eq!(demangle("_Z1fPFviEPA3_iM1AKFivE").as_deref(), Some("f(void (*)(int), int (*) [3], int (A::*)() const)"));
+/// assert_eq!(demangle("_ZN3zoo3PenIiE4feedIJidEEEvDpT_").as_deref(), Some("void zoo::Pen<int>::feed<int, double>(int, double)"));
+/// assert_eq!(demangle("_ZN3fooltIiEEbv").as_deref(), Some("bool foo::operator< <int>()"));
+/// assert_eq!(demangle("_ZZ4mainENKUlvE_clEv.cold").as_deref(), Some("main::{lambda()#1}::operator()() const [clone .cold]"));
+/// assert_eq!(demangle("speak"), None);
+/// ```
+pub fn demangle(symbol: &str) -> Option<String> {
+	let mut parser = Parser::new(symbol.strip_prefix("_Z")?);
+	let encoding = parser.encoding()?;
+	let mut out = parser.print(encoding)?;
+	// Suffixes of compiler generated clones, such as `.cold` and `.constprop.0`.
+	let mut rest = str::from_utf8(parser.rest()).ok()?;
+	while !rest.is_empty() {
+		let suffix = rest.strip_prefix('.')?;
+		let mut end = suffix.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(suffix.len());
+		if end == 0 {
+			return None;
+		}
+		while let Some(digits) = suffix[end..].strip_prefix('.') {
+			let len = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+			if len == 0 {
+				break;
+			}
+			end += 1 + len;
+		}
+		write!(out, " [clone .{}]", &suffix[..end]).ok()?;
+		rest = &suffix[end..];
+	}
+	Some(out)
+}
+
+/// Demangle a type mangled according to the Itanium C++ ABI,
+/// such as the name in a `std::type_info`.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::demangle_type;
+/// assert_eq!(demangle_type("N3zoo6LizardE").as_deref(), Some("zoo::Lizard"));
+/// assert_eq!(demangle_type("PKc").as_deref(), Some("char const*"));
+/// ```
+pub fn demangle_type(mangled: &str) -> Option<String> {
+	let mut parser = Parser::new(mangled);
+	let ty = parser.type_()?;
+	if !parser.rest().is_empty() {
+		return None;
+	}
+	parser.print(ty)
+}
+
+/// A component of a [`ClassName`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NamePart {
+	/// The identifier, such as `Pen`, or `(anonymous namespace)`.
+	pub name: String,
+	/// The demangled template arguments, such as `int`.
+	pub template_args: Vec<String>,
+}
+
+impl fmt::Display for NamePart {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.name)?;
+		if !self.template_args.is_empty() {
+			f.write_str(if self.name.ends_with('<') { " <" } else { "<" })?;
+			for (index, arg) in self.template_args.iter().enumerate() {
+				if index != 0 {
+					f.write_str(", ")?;
+				}
+				f.write_str(arg)?;
+			}
+			f.write_str(if self.template_args.last().is_some_and(|arg| arg.ends_with('>')) { " >" } else { ">" })?;
+		}
+		Ok(())
+	}
+}
+
+/// A class or function name split into the namespaces and classes that it is nested in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClassName {
+	/// The components of the name, from the outermost namespace to the class itself.
+	pub path: Vec<NamePart>,
+}
+
+impl ClassName {
+	/// Parse a class name mangled according to the Itanium C++ ABI,
+	/// such as `N3zoo3PenIiE6LizardE`, or a VTable, typeinfo or typeinfo name symbol for it.
+	/// 
+	/// Returns `None` if the name is not a class name.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::ClassName;
+	/// let name = ClassName::from_itanium("_ZTVN3zoo3PenIiSt6vectorIiSaIiEEE6LizardE").unwrap();
+	/// assert_eq!(name.name(), "Lizard");
+	/// assert_eq!(name.path[1].name, "Pen");
+	/// assert_eq!(name.path[1].template_args, ["int", "std::vector<int, std::allocator<int> >"]);
+	/// assert_eq!(name.to_string(), "zoo::Pen<int, std::vector<int, std::allocator<int> > >::Lizard");
+	/// assert_eq!(ClassName::from_itanium("PKc"), None);
+	/// ```
+	pub fn from_itanium(mangled: &str) -> Option<Self> {
+		let mangled = ["_ZTV", "_ZTI", "_ZTS"].iter()
+			.find_map(|prefix| mangled.strip_prefix(prefix))
+			.unwrap_or(mangled);
+		let mut parser = Parser::new(mangled);
+		let ty = parser.type_()?;
+		if !parser.rest().is_empty() {
+			return None;
+		}
+		let mut path = Vec::new();
+		parser.path(ty, &mut path)?;
+		Some(Self { path })
+	}
+
+	/// Parse the name of a function symbol mangled according to the Itanium C++ ABI,
+	/// such as `_ZN3zoo6Lizard5speakEv`, whose last component is the function.
+	/// 
+	/// Thunks are parsed as the function that they adjust `this` for,
+	/// and suffixes of compiler generated clones are ignored.
+	/// Returns `None` if the symbol is not a function.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::ClassName;
+	/// let name = ClassName::from_itanium_function("_ZNSt13basic_filebufIcSt11char_traitsIcEE7seekoffElSt12_Ios_SeekdirSt13_Ios_Openmode").unwrap();
+	/// assert_eq!(name.name(), "seekoff");
+	/// assert_eq!(name.to_string(), "std::basic_filebuf<char, std::char_traits<char> >::seekoff");
+	/// 
+	/// let name = ClassName::from_itanium_function("_ZThn8_N3zoo6LizardD0Ev").unwrap();
+	/// assert_eq!(name.to_string(), "zoo::Lizard::~Lizard");
+	/// assert_eq!(ClassName::from_itanium_function("_ZTVN3zoo6LizardE"), None);
+	/// ```
+	pub fn from_itanium_function(symbol: &str) -> Option<Self> {
+		let mut parser = Parser::new(symbol.strip_prefix("_Z")?);
+		loop {
+			match parser.input.get(parser.pos..parser.pos + 2) {
+				Some(b"Th" | b"Tv") => {
+					parser.pos += 1;
+					parser.call_offset()?;
+				}
+				Some(b"Tc") => {
+					parser.pos += 2;
+					parser.call_offset()?;
+					parser.call_offset()?;
+				}
+				_ => break,
+			}
+		}
+		let encoding = parser.encoding()?;
+		let &Node::Encoding { name, .. } = &parser.nodes[encoding] else {
+			return None;
+		};
+		if parser.rest().first().is_some_and(|&byte| byte != b'.') {
+			return None;
+		}
+		let mut path = Vec::new();
+		parser.path(name, &mut path)?;
+		Some(Self { path })
+	}
+
+	/// Return the unqualified name of the class or function, without template arguments.
+	pub fn name(&self) -> &str {
+		self.path.last().map_or("", |part| &part.name)
+	}
+
+	/// Return the namespaces and classes that the class is nested in.
+	pub fn scope(&self) -> &[NamePart] {
+		self.path.split_last().map_or(&[], |(_, scope)| scope)
+	}
+}
+
+impl fmt::Display for ClassName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, part) in self.path.iter().enumerate() {
+			if index != 0 {
+				f.write_str("::")?;
+			}
+			fmt::Display::fmt(part, f)?;
+		}
+		Ok(())
+	}
+}
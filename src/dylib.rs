@@ -0,0 +1,198 @@
+use ::core::{
+	ffi::{
+		c_char, c_int, c_void, CStr,
+	},
+	fmt,
+	ptr::{
+		self, NonNull,
+	},
+};
+
+#[cfg(unix)]
+mod sys {
+	use super::*;
+
+	pub const RTLD_NOW: c_int = 2;
+	#[cfg(target_vendor = "apple")]
+	pub const RTLD_LOCAL: c_int = 4;
+	#[cfg(not(target_vendor = "apple"))]
+	pub const RTLD_LOCAL: c_int = 0;
+
+	#[cfg_attr(all(target_os = "linux", target_env = "gnu"), link(name = "dl"))]
+	unsafe extern "C" {
+		pub fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+		pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+		pub fn dlclose(handle: *mut c_void) -> c_int;
+		pub fn dlerror() -> *const c_char;
+	}
+}
+
+#[cfg(windows)]
+mod sys {
+	use super::*;
+
+	#[link(name = "kernel32")]
+	unsafe extern "system" {
+		pub fn LoadLibraryA(filename: *const c_char) -> *mut c_void;
+		pub fn GetModuleHandleA(filename: *const c_char) -> *mut c_void;
+		pub fn GetProcAddress(module: *mut c_void, symbol: *const c_char) -> *mut c_void;
+		pub fn FreeLibrary(module: *mut c_void) -> c_int;
+		pub fn GetLastError() -> u32;
+	}
+}
+
+/// Error returned when a [`Library`] cannot be loaded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LibraryError {
+	message: [u8; 119],
+	len: u8,
+}
+
+impl LibraryError {
+	#[cfg(unix)]
+	fn last() -> Self {
+		// SAFETY: `dlerror` returns null or a C string.
+		let message = unsafe { sys::dlerror() };
+		let message = if message.is_null() {
+			b"unknown error" as &[u8]
+		} else {
+			unsafe { CStr::from_ptr(message) }.to_bytes()
+		};
+		Self::new(message)
+	}
+
+	#[cfg(windows)]
+	fn last() -> Self {
+		use ::core::fmt::Write;
+
+		let mut error = Self::new(b"");
+		let _ = write!(error, "error code {}", unsafe { sys::GetLastError() });
+		error
+	}
+
+	fn new(message: &[u8]) -> Self {
+		let mut error = Self { message: [0; 119], len: 0 };
+		let len = message.len().min(error.message.len());
+		error.message[..len].copy_from_slice(&message[..len]);
+		error.len = len as u8;
+		error
+	}
+
+	/// Return the message of the error, as reported by the system,
+	/// which may be truncated.
+	pub fn message(&self) -> &str {
+		let message = &self.message[..self.len as usize];
+		match ::core::str::from_utf8(message) {
+			Ok(message) => message,
+			Err(error) => {
+				// SAFETY: The bytes up to `valid_up_to` are valid UTF-8.
+				unsafe { ::core::str::from_utf8_unchecked(&message[..error.valid_up_to()]) }
+			}
+		}
+	}
+}
+
+impl fmt::Write for LibraryError {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let start = self.len as usize;
+		let len = s.len().min(self.message.len() - start);
+		self.message[start..start + len].copy_from_slice(&s.as_bytes()[..len]);
+		self.len += len as u8;
+		Ok(())
+	}
+}
+
+impl fmt::Debug for LibraryError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("LibraryError")
+			.field(&self.message())
+			.finish()
+	}
+}
+
+impl fmt::Display for LibraryError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.message())
+	}
+}
+
+/// Handle to a dynamically loaded library, such as a C++ shared library,
+/// used to look up symbols like constructors and VTables at runtime.
+/// 
+/// The library is unloaded when the handle is dropped,
+/// unless it was obtained with [`Library::this_program`].
+#[derive(Debug)]
+pub struct Library {
+	handle: NonNull<c_void>,
+	owned: bool,
+}
+
+// SAFETY: The system loader is thread-safe.
+unsafe impl Send for Library {}
+// SAFETY: The system loader is thread-safe.
+unsafe impl Sync for Library {}
+
+impl Library {
+	/// Load the library at `path`, or return it if it is already loaded.
+	/// 
+	/// # Safety
+	/// Loading a library runs its initialization routines,
+	/// such as the constructors of C++ globals, which may do anything.
+	/// 
+	/// # Errors
+	/// Returns the error reported by the system if the library could not be loaded.
+	pub unsafe fn open(path: &CStr) -> Result<Self, LibraryError> {
+		#[cfg(unix)]
+		let handle = unsafe { sys::dlopen(path.as_ptr(), sys::RTLD_NOW | sys::RTLD_LOCAL) };
+		#[cfg(windows)]
+		let handle = unsafe { sys::LoadLibraryA(path.as_ptr()) };
+		match NonNull::new(handle) {
+			Some(handle) => Ok(Self { handle, owned: true }),
+			None => Err(LibraryError::last()),
+		}
+	}
+
+	/// Return a handle to the program itself, including the libraries it was linked with.
+	/// 
+	/// # Errors
+	/// Returns the error reported by the system if the handle could not be obtained.
+	pub fn this_program() -> Result<Self, LibraryError> {
+		#[cfg(unix)]
+		let handle = unsafe { sys::dlopen(ptr::null(), sys::RTLD_NOW | sys::RTLD_LOCAL) };
+		#[cfg(windows)]
+		let handle = unsafe { sys::GetModuleHandleA(ptr::null()) };
+		match NonNull::new(handle) {
+			Some(handle) => Ok(Self { handle, owned: cfg!(unix) }),
+			None => Err(LibraryError::last()),
+		}
+	}
+
+	/// Return the address of the symbol `name`, such as a mangled C++ name,
+	/// or [`None`] if it is not defined by the library.
+	/// 
+	/// The address is only valid while the library stays loaded.
+	pub fn symbol(&self, name: &CStr) -> Option<NonNull<c_void>> {
+		#[cfg(unix)]
+		let symbol = unsafe { sys::dlsym(self.handle.as_ptr(), name.as_ptr()) };
+		#[cfg(windows)]
+		let symbol = unsafe { sys::GetProcAddress(self.handle.as_ptr(), name.as_ptr()) };
+		NonNull::new(symbol)
+	}
+
+	/// Return the raw handle of the library,
+	/// as returned by `dlopen` or `LoadLibraryA`.
+	pub fn as_raw(&self) -> NonNull<c_void> {
+		self.handle
+	}
+}
+
+impl Drop for Library {
+	fn drop(&mut self) {
+		if self.owned {
+			#[cfg(unix)]
+			unsafe { sys::dlclose(self.handle.as_ptr()) };
+			#[cfg(windows)]
+			unsafe { sys::FreeLibrary(self.handle.as_ptr()) };
+		}
+	}
+}
@@ -0,0 +1,612 @@
+use ::core::{
+	fmt,
+	str,
+};
+use ::alloc::vec::Vec;
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+const SHT_DYNSYM: u32 = 11;
+const SHF_EXECINSTR: u64 = 4;
+const STT_FUNC: u8 = 2;
+
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const R_X86_64_64: u32 = 1;
+const R_X86_64_RELATIVE: u32 = 8;
+const R_AARCH64_ABS64: u32 = 257;
+const R_AARCH64_RELATIVE: u32 = 1027;
+
+/// Error returned when an [`ElfFile`] cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElfError {
+	/// The data does not start with the ELF magic number.
+	NotElf,
+	/// The file is not a 64-bit little-endian ELF file.
+	Unsupported,
+	/// A header or table extends past the end of the data.
+	Truncated,
+}
+
+impl fmt::Display for ElfError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::NotElf => "not an ELF file",
+			Self::Unsupported => "not a 64-bit little-endian ELF file",
+			Self::Truncated => "ELF file is truncated",
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Section {
+	kind: u32,
+	flags: u64,
+	addr: u64,
+	offset: u64,
+	size: u64,
+	link: u32,
+	entsize: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Relocation<'a> {
+	offset: u64,
+	kind: RelocationKind,
+	symbol: Option<ElfSymbol<'a>>,
+	addend: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RelocationKind {
+	Relative,
+	Absolute,
+}
+
+/// A symbol of an [`ElfFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElfSymbol<'a> {
+	/// Name of the symbol, such as `_ZTV6Lizard`.
+	pub name: &'a str,
+	/// Virtual address of the symbol, or zero if it is undefined.
+	pub value: u64,
+	/// Size of the symbol in bytes.
+	pub size: u64,
+	/// `true` if the symbol is a function.
+	pub is_function: bool,
+	/// `true` if the symbol is defined by the file.
+	pub is_defined: bool,
+}
+
+/// Value of a pointer stored in an [`ElfFile`], with relocations applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElfPointer<'a> {
+	/// A null pointer.
+	Null,
+	/// A virtual address in the file.
+	Address(u64),
+	/// An address in another module, given as a symbol and an addend.
+	Import {
+		/// Name of the symbol.
+		symbol: &'a str,
+		/// Offset from the symbol.
+		addend: i64,
+	},
+}
+
+/// A VTable symbol (`_ZTV*`) of an [`ElfFile`].
+/// 
+/// A VTable symbol contains one VTable for each base class
+/// with a VTable that is not shared with the primary base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfVTable<'a> {
+	/// The VTable symbol.
+	pub symbol: ElfSymbol<'a>,
+	/// The VTables in the symbol, starting with the primary VTable.
+	pub address_points: Vec<ElfAddressPoint<'a>>,
+}
+
+impl<'a> ElfVTable<'a> {
+	/// Return the mangled name of the class, such as `6Lizard`.
+	pub fn class_name(&self) -> &'a str {
+		self.symbol.name.strip_prefix("_ZTV").unwrap_or(self.symbol.name)
+	}
+
+	/// Return the primary VTable.
+	pub fn primary(&self) -> Option<&ElfAddressPoint<'a>> {
+		self.address_points.first()
+	}
+}
+
+/// A VTable within an [`ElfVTable`],
+/// laid out like the VTables that [`VTablePtr`](crate::VTablePtr)s point to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfAddressPoint<'a> {
+	/// Virtual address of the address point,
+	/// which is where the VTable pointers of objects point to.
+	pub address: u64,
+	/// Offset from the subobject with this VTable to the complete object,
+	/// stored two words before the address point.
+	pub offset_to_top: i64,
+	/// Pointer to the typeinfo of the class,
+	/// stored one word before the address point.
+	pub typeinfo: ElfPointer<'a>,
+	/// Slots of the VTable, starting at the address point.
+	pub slots: Vec<ElfSlot<'a>>,
+}
+
+/// A slot of an [`ElfAddressPoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElfSlot<'a> {
+	/// The function pointer in the slot.
+	pub target: ElfPointer<'a>,
+	/// The name of the function symbol the slot points to, if known.
+	pub symbol: Option<&'a str>,
+}
+
+/// Kind of an Itanium typeinfo object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElfTypeInfoKind {
+	/// `__class_type_info`, for classes without bases.
+	Class,
+	/// `__si_class_type_info`, for classes with a single, public, non-virtual base
+	/// at offset zero.
+	SingleInheritance,
+	/// `__vmi_class_type_info`, for all other classes.
+	VirtualMultipleInheritance,
+	/// Any other typeinfo, or a typeinfo whose kind could not be determined.
+	Other,
+}
+
+/// A base class described by an [`ElfTypeInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElfBaseInfo<'a> {
+	/// Pointer to the typeinfo of the base class.
+	pub typeinfo: ElfPointer<'a>,
+	/// Offset of the base class in the class, or, for virtual bases,
+	/// the offset of the virtual base offset in the VTable.
+	pub offset: i64,
+	/// `true` if the base class is virtual.
+	pub is_virtual: bool,
+	/// `true` if the base class is public.
+	pub is_public: bool,
+}
+
+/// An Itanium typeinfo object (`_ZTI*`) of an [`ElfFile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfTypeInfo<'a> {
+	/// Address of the typeinfo.
+	pub address: u64,
+	/// Name of the typeinfo symbol, such as `_ZTI6Lizard`, if any.
+	pub symbol: Option<&'a str>,
+	/// Kind of the typeinfo.
+	pub kind: ElfTypeInfoKind,
+	/// Mangled name of the type, such as `6Lizard`.
+	pub name: Option<&'a str>,
+	/// `__vmi_class_type_info` flags.
+	pub flags: u32,
+	/// Base classes.
+	pub bases: Vec<ElfBaseInfo<'a>>,
+}
+
+/// A 64-bit little-endian ELF file, such as a shared library,
+/// parsed from a byte slice to find the VTables and typeinfo in it.
+/// 
+/// Relocations are applied when reading pointers,
+/// so this works for both position-independent and position-dependent code.
+/// Relocatable object files are not supported.
+/// 
+/// # Examples
+/// ```no_run
+/// # use cppdvt::ElfFile;
+/// let data = std::fs::read("libserver.so").unwrap();
+/// let elf = ElfFile::parse(&data).unwrap();
+/// for vtable in elf.vtables() {
+/// 	let primary = vtable.primary().unwrap();
+/// 	println!("{} at {:#x}:", vtable.class_name(), primary.address);
+/// 	for (index, slot) in primary.slots.iter().enumerate() {
+/// 		println!("\t{index}: {}", slot.symbol.unwrap_or("?"));
+/// 	}
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElfFile<'a> {
+	data: &'a [u8],
+	sections: Vec<Section>,
+	/// Sorted by address.
+	symbols: Vec<ElfSymbol<'a>>,
+	/// The highest end address of the defined symbols up to each index of `symbols`,
+	/// which bounds the search for a symbol that contains an address.
+	symbol_ends: Vec<u64>,
+	functions: Vec<ElfSymbol<'a>>,
+	relocations: Vec<Relocation<'a>>,
+}
+
+fn read<const N: usize>(data: &[u8], offset: u64) -> Result<[u8; N], ElfError> {
+	let offset = usize::try_from(offset).map_err(|_| ElfError::Truncated)?;
+	data.get(offset..offset.checked_add(N).ok_or(ElfError::Truncated)?)
+		.map(|bytes| bytes.try_into().unwrap())
+		.ok_or(ElfError::Truncated)
+}
+
+fn u16_at(data: &[u8], offset: u64) -> Result<u16, ElfError> {
+	read(data, offset).map(u16::from_le_bytes)
+}
+
+fn u32_at(data: &[u8], offset: u64) -> Result<u32, ElfError> {
+	read(data, offset).map(u32::from_le_bytes)
+}
+
+fn u64_at(data: &[u8], offset: u64) -> Result<u64, ElfError> {
+	read(data, offset).map(u64::from_le_bytes)
+}
+
+/// Return the offset of the entry `index` of `size` bytes in a table at `base`.
+fn entry_at(base: u64, index: u64, size: u64) -> Result<u64, ElfError> {
+	index.checked_mul(size)
+		.and_then(|offset| base.checked_add(offset))
+		.ok_or(ElfError::Truncated)
+}
+
+fn add(offset: u64, field: u64) -> Result<u64, ElfError> {
+	offset.checked_add(field).ok_or(ElfError::Truncated)
+}
+
+fn c_str_at(data: &[u8], offset: u64) -> Option<&str> {
+	let bytes = data.get(usize::try_from(offset).ok()?..)?;
+	let len = bytes.iter().position(|&byte| byte == 0)?;
+	str::from_utf8(&bytes[..len]).ok()
+}
+
+impl<'a> ElfFile<'a> {
+	/// Parse the section headers, symbols and relocations of an ELF file.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::ElfFile;
+	/// # #[cfg(all(target_os = "linux", target_pointer_width = "64", target_endian = "little"))] {
+	/// let data = std::fs::read("/proc/self/exe").unwrap();
+	/// let elf = ElfFile::parse(&data).unwrap();
+	/// assert!(elf.symbol("main").is_some());
+	/// # }
+	/// ```
+	/// 
+	/// # Errors
+	/// Returns an error if `data` is not a 64-bit little-endian ELF file,
+	/// or if its headers are truncated.
+	pub fn parse(data: &'a [u8]) -> Result<Self, ElfError> {
+		if !data.starts_with(b"\x7fELF") {
+			return Err(ElfError::NotElf);
+		}
+		// `ELFCLASS64` and `ELFDATA2LSB`.
+		if data.get(4..6) != Some(&[2, 1]) {
+			return Err(ElfError::Unsupported);
+		}
+		let machine = u16_at(data, 18)?;
+		let shoff = u64_at(data, 40)?;
+		let shentsize = u16_at(data, 58)? as u64;
+		let shnum = u16_at(data, 60)? as u64;
+
+		let mut sections = Vec::new();
+		for index in 0..shnum {
+			let header = entry_at(shoff, index, shentsize)?;
+			sections.push(Section {
+				kind: u32_at(data, add(header, 4)?)?,
+				flags: u64_at(data, add(header, 8)?)?,
+				addr: u64_at(data, add(header, 16)?)?,
+				offset: u64_at(data, add(header, 24)?)?,
+				size: u64_at(data, add(header, 32)?)?,
+				link: u32_at(data, add(header, 40)?)?,
+				entsize: u64_at(data, add(header, 56)?)?,
+			});
+		}
+
+		let mut elf = Self {
+			data,
+			sections,
+			symbols: Vec::new(),
+			symbol_ends: Vec::new(),
+			functions: Vec::new(),
+			relocations: Vec::new(),
+		};
+		for section in elf.sections.iter().filter(|section| matches!(section.kind, SHT_SYMTAB | SHT_DYNSYM)) {
+			let symbols = elf.symbol_table(section)?;
+			elf.symbols.extend(symbols.into_iter().flatten().filter(|symbol| !symbol.name.is_empty()));
+		}
+		// Symbols in both tables are only kept once.
+		elf.symbols.sort_unstable_by_key(|symbol| {
+			(symbol.value, symbol.name, symbol.size, symbol.is_function, symbol.is_defined)
+		});
+		elf.symbols.dedup();
+		elf.symbol_ends = elf.symbols.iter()
+			.scan(0, |end, symbol| {
+				if symbol.is_defined {
+					*end = symbol.value.saturating_add(symbol.size).max(*end);
+				}
+				Some(*end)
+			})
+			.collect();
+		elf.functions = elf.symbols.iter()
+			.filter(|symbol| symbol.is_function && symbol.is_defined)
+			.copied()
+			.collect();
+
+		for section in elf.sections.iter().filter(|section| section.kind == SHT_RELA) {
+			let symbols = match elf.sections.get(section.link as usize) {
+				Some(symbols) if matches!(symbols.kind, SHT_SYMTAB | SHT_DYNSYM) => elf.symbol_table(symbols)?,
+				_ => Vec::new(),
+			};
+			let count = section.size / section.entsize.max(24);
+			for index in 0..count {
+				let entry = entry_at(section.offset, index, 24)?;
+				let info = u64_at(data, add(entry, 8)?)?;
+				let kind = match (machine, info as u32) {
+					(EM_X86_64, R_X86_64_RELATIVE) | (EM_AARCH64, R_AARCH64_RELATIVE) => RelocationKind::Relative,
+					(EM_X86_64, R_X86_64_64) | (EM_AARCH64, R_AARCH64_ABS64) => RelocationKind::Absolute,
+					_ => continue,
+				};
+				elf.relocations.push(Relocation {
+					offset: u64_at(data, entry)?,
+					kind,
+					symbol: symbols.get((info >> 32) as usize).copied().flatten(),
+					addend: u64_at(data, add(entry, 16)?)? as i64,
+				});
+			}
+		}
+		elf.relocations.sort_by_key(|relocation| relocation.offset);
+		Ok(elf)
+	}
+
+	fn symbol_table(&self, section: &Section) -> Result<Vec<Option<ElfSymbol<'a>>>, ElfError> {
+		let data = self.data;
+		let strings = self.sections.get(section.link as usize).ok_or(ElfError::Truncated)?;
+		let count = section.size / section.entsize.max(24);
+		let mut symbols = Vec::new();
+		for index in 0..count {
+			let entry = entry_at(section.offset, index, 24)?;
+			let name = u32_at(data, entry)? as u64;
+			let info = read::<1>(data, add(entry, 4)?)?[0];
+			let shndx = u16_at(data, add(entry, 6)?)?;
+			let name = strings.offset.checked_add(name).and_then(|name| c_str_at(data, name));
+			symbols.push(name.map(|name| ElfSymbol {
+				name,
+				value: add(entry, 8).and_then(|value| u64_at(data, value)).unwrap_or(0),
+				size: add(entry, 16).and_then(|size| u64_at(data, size)).unwrap_or(0),
+				is_function: info & 0xf == STT_FUNC,
+				is_defined: shndx != 0,
+			}));
+		}
+		Ok(symbols)
+	}
+
+	/// Return the symbols of the file, from both the static and dynamic symbol tables,
+	/// sorted by address.
+	pub fn symbols(&self) -> &[ElfSymbol<'a>] {
+		&self.symbols
+	}
+
+	/// Return the defined symbol `name`.
+	pub fn symbol(&self, name: &str) -> Option<&ElfSymbol<'a>> {
+		self.symbols.iter().find(|symbol| symbol.is_defined && symbol.name == name)
+	}
+
+	/// Return the function symbol at `address`.
+	pub fn function_at(&self, address: u64) -> Option<&ElfSymbol<'a>> {
+		let index = self.functions.partition_point(|symbol| symbol.value < address);
+		self.functions.get(index).filter(|symbol| symbol.value == address)
+	}
+
+	/// Return the defined symbol that contains `address`,
+	/// preferring one that starts there.
+	pub fn symbol_at(&self, address: u64) -> Option<&ElfSymbol<'a>> {
+		let index = self.symbols.partition_point(|symbol| symbol.value < address);
+		self.symbols[index..].iter()
+			.take_while(|symbol| symbol.value == address)
+			.find(|symbol| symbol.is_defined)
+			.or_else(|| {
+				self.symbols[..index].iter()
+					.zip(&self.symbol_ends[..index])
+					.rev()
+					.take_while(|&(_, &end)| end > address)
+					.map(|(symbol, _)| symbol)
+					.find(|symbol| symbol.is_defined && address - symbol.value < symbol.size)
+			})
+	}
+
+	fn section_at(&self, address: u64) -> Option<&Section> {
+		self.sections.iter().find(|section| {
+			section.addr != 0 && section.addr <= address && address - section.addr < section.size
+		})
+	}
+
+	/// Return `true` if `address` is in a section with executable code.
+	pub fn is_code(&self, address: u64) -> bool {
+		self.section_at(address).is_some_and(|section| section.flags & SHF_EXECINSTR != 0)
+	}
+
+	/// Return the bytes of the file at the virtual address `address`,
+	/// up to the end of its section.
+	pub fn bytes_at(&self, address: u64) -> Option<&'a [u8]> {
+		let section = self.section_at(address)?;
+		if section.kind == SHT_NOBITS {
+			return None;
+		}
+		let start = usize::try_from(section.offset.checked_add(address - section.addr)?).ok()?;
+		let end = usize::try_from(section.offset.checked_add(section.size)?).ok()?;
+		self.data.get(start..end)
+	}
+
+	/// Read a 64-bit word at the virtual address `address`,
+	/// without applying relocations.
+	pub fn read_u64(&self, address: u64) -> Option<u64> {
+		let bytes = self.bytes_at(address)?;
+		Some(u64::from_le_bytes(bytes.get(..8)?.try_into().unwrap()))
+	}
+
+	/// Read a pointer at the virtual address `address`, applying relocations.
+	pub fn read_pointer(&self, address: u64) -> Option<ElfPointer<'a>> {
+		let index = self.relocations.partition_point(|relocation| relocation.offset < address);
+		if let Some(relocation) = self.relocations.get(index).filter(|relocation| relocation.offset == address) {
+			let base = match (relocation.kind, relocation.symbol) {
+				(RelocationKind::Relative, _) => 0,
+				(RelocationKind::Absolute, Some(symbol)) if symbol.is_defined => symbol.value,
+				(RelocationKind::Absolute, Some(symbol)) => {
+					return Some(ElfPointer::Import { symbol: symbol.name, addend: relocation.addend });
+				}
+				(RelocationKind::Absolute, None) => 0,
+			};
+			return Some(ElfPointer::Address(base.wrapping_add(relocation.addend as u64)));
+		}
+		match self.read_u64(address)? {
+			0 => Some(ElfPointer::Null),
+			value => Some(ElfPointer::Address(value)),
+		}
+	}
+
+	fn has_relocation(&self, address: u64) -> bool {
+		let index = self.relocations.partition_point(|relocation| relocation.offset < address);
+		self.relocations.get(index).is_some_and(|relocation| relocation.offset == address)
+	}
+
+	/// Return the function pointer at `address` if it looks like a VTable slot.
+	fn slot_at(&self, address: u64) -> Option<ElfSlot<'a>> {
+		let target = self.read_pointer(address)?;
+		let symbol = match target {
+			ElfPointer::Address(target) if self.is_code(target) => self.function_at(target).map(|symbol| symbol.name),
+			ElfPointer::Import { symbol, addend: 0 } => Some(symbol),
+			_ => return None,
+		};
+		Some(ElfSlot { target, symbol })
+	}
+
+	/// Return `true` if `pointer` looks like a typeinfo pointer.
+	fn is_typeinfo(&self, pointer: ElfPointer<'a>) -> bool {
+		match pointer {
+			ElfPointer::Null => true,
+			ElfPointer::Address(address) => self.symbol_at(address).is_some_and(|symbol| symbol.name.starts_with("_ZTI")),
+			ElfPointer::Import { symbol, .. } => symbol.starts_with("_ZTI"),
+		}
+	}
+
+	/// Return every VTable symbol in the file.
+	pub fn vtables(&self) -> impl Iterator<Item = ElfVTable<'a>> + '_ {
+		self.symbols.iter()
+			.filter(|symbol| symbol.is_defined && symbol.name.starts_with("_ZTV"))
+			.filter_map(|symbol| self.vtable(symbol))
+	}
+
+	/// Read the VTables in the VTable symbol `symbol`.
+	/// 
+	/// Each VTable is found by its offset-to-top and typeinfo pointer,
+	/// which are followed by slots that point to code.
+	pub fn vtable(&self, symbol: &ElfSymbol<'a>) -> Option<ElfVTable<'a>> {
+		if !symbol.is_defined {
+			return None;
+		}
+		let words = symbol.size / 8;
+		// The symbol must fit in the address space for its words to be read.
+		symbol.value.checked_add(symbol.size)?;
+		let word = |index: u64| symbol.value + index * 8;
+		let mut address_points = Vec::new();
+		let mut typeinfo = None;
+		let mut index = 2;
+		while index < words {
+			let header = self.read_pointer(word(index - 1))
+				.filter(|&pointer| typeinfo.map_or_else(|| self.is_typeinfo(pointer), |typeinfo| pointer == typeinfo))
+				.filter(|_| !self.has_relocation(word(index - 2)));
+			let Some(header) = header else {
+				index += 1;
+				continue;
+			};
+			let mut slots = Vec::new();
+			while index + (slots.len() as u64) < words {
+				match self.slot_at(word(index + slots.len() as u64)) {
+					Some(slot) => slots.push(slot),
+					None => break,
+				}
+			}
+			if slots.is_empty() && typeinfo.is_none() && header == ElfPointer::Null {
+				index += 1;
+				continue;
+			}
+			typeinfo = Some(header);
+			address_points.push(ElfAddressPoint {
+				address: word(index),
+				offset_to_top: self.read_u64(word(index - 2)).unwrap_or(0) as i64,
+				typeinfo: header,
+				slots,
+			});
+			index += address_points.last().map_or(0, |point: &ElfAddressPoint<'a>| point.slots.len() as u64) + 2;
+		}
+		Some(ElfVTable { symbol: *symbol, address_points })
+	}
+
+	/// Decode the typeinfo object at `pointer`.
+	/// 
+	/// Typeinfo in other modules can only be identified by their symbol,
+	/// so for [`ElfPointer::Import`], only [`ElfTypeInfo::symbol`] is set.
+	pub fn typeinfo(&self, pointer: ElfPointer<'a>) -> Option<ElfTypeInfo<'a>> {
+		let address = match pointer {
+			ElfPointer::Null => return None,
+			ElfPointer::Address(address) => address,
+			ElfPointer::Import { symbol, .. } => {
+				return Some(ElfTypeInfo {
+					address: 0,
+					symbol: Some(symbol),
+					kind: ElfTypeInfoKind::Other,
+					name: symbol.strip_prefix("_ZTI"),
+					flags: 0,
+					bases: Vec::new(),
+				});
+			}
+		};
+		let vtable = match self.read_pointer(address)? {
+			ElfPointer::Import { symbol, .. } => Some(symbol),
+			ElfPointer::Address(vtable) => self.symbol_at(vtable).map(|symbol| symbol.name),
+			ElfPointer::Null => None,
+		};
+		let kind = match vtable {
+			Some("_ZTVN10__cxxabiv117__class_type_infoE") => ElfTypeInfoKind::Class,
+			Some("_ZTVN10__cxxabiv120__si_class_type_infoE") => ElfTypeInfoKind::SingleInheritance,
+			Some("_ZTVN10__cxxabiv121__vmi_class_type_infoE") => ElfTypeInfoKind::VirtualMultipleInheritance,
+			_ => ElfTypeInfoKind::Other,
+		};
+		let name = match self.read_pointer(address.checked_add(8)?)? {
+			ElfPointer::Address(name) => self.bytes_at(name).and_then(|bytes| c_str_at(bytes, 0)),
+			_ => None,
+		};
+		let mut typeinfo = ElfTypeInfo {
+			address,
+			symbol: self.symbol_at(address).filter(|symbol| symbol.value == address).map(|symbol| symbol.name),
+			kind,
+			name,
+			flags: 0,
+			bases: Vec::new(),
+		};
+		match kind {
+			ElfTypeInfoKind::SingleInheritance => typeinfo.bases.push(ElfBaseInfo {
+				typeinfo: self.read_pointer(address.checked_add(16)?)?,
+				offset: 0,
+				is_virtual: false,
+				is_public: true,
+			}),
+			ElfTypeInfoKind::VirtualMultipleInheritance => {
+				let header = self.read_u64(address.checked_add(16)?)?;
+				typeinfo.flags = header as u32;
+				for index in 0..(header >> 32) {
+					let base = entry_at(address.checked_add(24)?, index, 16).ok()?;
+					let offset_flags = self.read_u64(base.checked_add(8)?)? as i64;
+					typeinfo.bases.push(ElfBaseInfo {
+						typeinfo: self.read_pointer(base)?,
+						offset: offset_flags >> 8,
+						is_virtual: offset_flags & 1 != 0,
+						is_public: offset_flags & 2 != 0,
+					});
+				}
+			}
+			_ => {}
+		}
+		Some(typeinfo)
+	}
+}
@@ -0,0 +1,280 @@
+use ::core::fmt::{
+	self, Write,
+};
+
+use super::SlotInfo;
+
+/// Description of a C++ class whose VTable is described by [`SlotInfo`]s,
+/// used to generate C and C++ headers.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{ClassDecl, VTableInfo, vtable, write_cpp_class, write_c_vtbl};
+/// vtable! {
+/// 	RendererVt {
+/// 		pub fn destructor();
+/// 		pub fn deleting_destructor();
+/// 		pub fn draw(x: i32, y: i32) -> bool;
+/// 		pub fn name() -> *const core::ffi::c_char;
+/// 	}
+/// }
+/// 
+/// let class = ClassDecl::new("IRenderer", RendererVt::SLOTS);
+/// 
+/// let mut cpp = String::new();
+/// write_cpp_class(&mut cpp, &class).unwrap();
+/// assert!(cpp.contains("\tvirtual ~IRenderer() {}\n"));
+/// assert!(cpp.contains("\tvirtual bool draw(int32_t x, int32_t y) = 0;\n"));
+/// assert!(cpp.contains("\tvirtual const char* name() = 0;\n"));
+/// 
+/// let mut c = String::new();
+/// write_c_vtbl(&mut c, &class).unwrap();
+/// assert!(c.contains("\tbool (CPPDVT_THISCALL *draw)(struct IRenderer* self, int32_t x, int32_t y);\n"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ClassDecl<'a> {
+	/// Name of the class.
+	pub name: &'a str,
+	/// Name of the base class, if any.
+	pub base: Option<&'a str>,
+	/// Slots that the base class declares, which are only written to C headers.
+	pub inherited: &'a [SlotInfo],
+	/// Slots that the class itself declares.
+	pub slots: &'a [SlotInfo],
+	/// Additional mappings from Rust types to C++ types,
+	/// such as `("VtObjectPtr<PetVt>", "Pet*")`,
+	/// which take precedence over the built-in mappings.
+	/// 
+	/// Whitespace in the Rust types is ignored.
+	pub types: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> ClassDecl<'a> {
+	/// Describe a class named `name` that has the slots `slots`.
+	pub const fn new(name: &'a str, slots: &'a [SlotInfo]) -> Self {
+		Self {
+			name,
+			base: None,
+			inherited: &[],
+			slots,
+			types: &[],
+		}
+	}
+
+	/// Describe the base class of the class, and the slots it declares.
+	pub const fn with_base(self, base: &'a str, inherited: &'a [SlotInfo]) -> Self {
+		Self {
+			base: Some(base),
+			inherited,
+			..self
+		}
+	}
+
+	/// Add mappings from Rust types to C++ types.
+	pub const fn with_types(self, types: &'a [(&'a str, &'a str)]) -> Self {
+		Self { types, ..self }
+	}
+}
+
+/// Return `true` if the slot is one of the slots of a virtual destructor,
+/// based on its name.
+fn is_destructor(slot: &SlotInfo) -> bool {
+	matches!(
+		slot.name,
+		"destructor" | "complete_destructor" | "deleting_destructor" | "scalar_deleting_destructor"
+	)
+}
+
+/// Write the C++ equivalent of the Rust type `ty`.
+fn write_type(out: &mut impl Write, ty: &str, types: &[(&str, &str)]) -> fmt::Result {
+	let ty = ty.trim();
+	for (rust, cpp) in types {
+		if eq_ignoring_whitespace(rust, ty) {
+			return out.write_str(cpp);
+		}
+	}
+
+	if let Some(pointee) = ty.strip_prefix("*const") {
+		out.write_str("const ")?;
+		write_type(out, pointee, types)?;
+		return out.write_str("*");
+	}
+	if let Some(pointee) = ty.strip_prefix("*mut") {
+		write_type(out, pointee, types)?;
+		return out.write_str("*");
+	}
+	if let Some(pointee) = ty.strip_prefix("&mut") {
+		write_type(out, pointee, types)?;
+		return out.write_str("&");
+	}
+	if let Some(pointee) = ty.strip_prefix('&') {
+		out.write_str("const ")?;
+		write_type(out, pointee, types)?;
+		return out.write_str("&");
+	}
+	if let Some(pointee) = generic_argument(ty, "NonNull")
+		.or_else(|| generic_argument(ty, "Option").and_then(|ty| generic_argument(ty, "NonNull")))
+	{
+		write_type(out, pointee, types)?;
+		return out.write_str("*");
+	}
+	if generic_argument(ty, "VtObjectPtr").is_some() {
+		return out.write_str("void*");
+	}
+
+	let name = ty.rsplit("::").next().unwrap_or(ty).trim();
+	let cpp = match name {
+		"" | "()" | "c_void" => "void",
+		"bool" => "bool",
+		"i8" => "int8_t",
+		"u8" => "uint8_t",
+		"i16" => "int16_t",
+		"u16" => "uint16_t",
+		"i32" => "int32_t",
+		"u32" => "uint32_t",
+		"i64" => "int64_t",
+		"u64" => "uint64_t",
+		"isize" => "ptrdiff_t",
+		"usize" => "size_t",
+		"f32" | "c_float" => "float",
+		"f64" | "c_double" => "double",
+		"c_char" => "char",
+		"c_schar" => "signed char",
+		"c_uchar" => "unsigned char",
+		"c_short" => "short",
+		"c_ushort" => "unsigned short",
+		"c_int" => "int",
+		"c_uint" => "unsigned int",
+		"c_long" => "long",
+		"c_ulong" => "unsigned long",
+		"c_longlong" => "long long",
+		"c_ulonglong" => "unsigned long long",
+		name if name.chars().all(|c| c.is_alphanumeric() || c == '_') => name,
+		_ => {
+			// Types that cannot be mapped are kept in a comment.
+			return write!(out, "void* /* {ty} */");
+		}
+	};
+	out.write_str(cpp)
+}
+
+fn eq_ignoring_whitespace(a: &str, b: &str) -> bool {
+	a.chars().filter(|c| !c.is_whitespace()).eq(b.chars().filter(|c| !c.is_whitespace()))
+}
+
+/// If `ty` is `name<T>`, possibly with a path, return `T`.
+fn generic_argument<'t>(ty: &'t str, name: &str) -> Option<&'t str> {
+	let (path, argument) = ty.trim().split_once('<')?;
+	let path = path.trim().strip_suffix(name)?;
+	if !path.is_empty() && !path.ends_with("::") {
+		return None;
+	}
+	argument.trim_end().strip_suffix('>')
+}
+
+/// Write the parameters of `slot`, prefixed by `this` if given.
+fn write_params(
+	out: &mut impl Write,
+	this: Option<fmt::Arguments<'_>>,
+	slot: &SlotInfo,
+	types: &[(&str, &str)],
+) -> fmt::Result {
+	let mut first = true;
+	if let Some(this) = this {
+		out.write_fmt(this)?;
+		first = false;
+	}
+	for (name, ty) in slot.params() {
+		if !first {
+			out.write_str(", ")?;
+		}
+		first = false;
+		if name == "..." {
+			out.write_str("...")?;
+		} else {
+			write_type(out, ty, types)?;
+			if name != "_" {
+				write!(out, " {name}")?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Write a C++ abstract class with the slots of `class` as pure virtual methods.
+/// 
+/// Consecutive slots named `destructor`, `complete_destructor`, `deleting_destructor`
+/// or `scalar_deleting_destructor` are written as a single virtual destructor.
+/// 
+/// # Errors
+/// Returns an error if `out` returns an error.
+pub fn write_cpp_class(out: &mut impl Write, class: &ClassDecl<'_>) -> fmt::Result {
+	writeln!(out, "// Generated from Rust VTable definitions. Do not edit.")?;
+	writeln!(out, "#pragma once")?;
+	writeln!(out)?;
+	writeln!(out, "#include <stddef.h>")?;
+	writeln!(out, "#include <stdint.h>")?;
+	writeln!(out)?;
+	match class.base {
+		Some(base) => writeln!(out, "class {} : public {base} {{", class.name)?,
+		None => writeln!(out, "class {} {{", class.name)?,
+	}
+	writeln!(out, "public:")?;
+	let mut in_destructor = false;
+	for slot in class.slots {
+		if is_destructor(slot) {
+			if !in_destructor {
+				writeln!(out, "\tvirtual ~{}() {{}}", class.name)?;
+			}
+			in_destructor = true;
+			continue;
+		}
+		in_destructor = false;
+		out.write_str("\tvirtual ")?;
+		write_type(out, slot.ret, class.types)?;
+		write!(out, " {}(", slot.name)?;
+		write_params(out, None, slot, class.types)?;
+		writeln!(out, ") = 0;")?;
+	}
+	writeln!(out, "}};")
+}
+
+/// Write a C struct for the VTable of `class`, including the slots of its base class,
+/// and a C struct for objects of the class.
+/// 
+/// # Errors
+/// Returns an error if `out` returns an error.
+pub fn write_c_vtbl(out: &mut impl Write, class: &ClassDecl<'_>) -> fmt::Result {
+	let name = class.name;
+	writeln!(out, "/* Generated from Rust VTable definitions. Do not edit. */")?;
+	writeln!(out, "#pragma once")?;
+	writeln!(out)?;
+	writeln!(out, "#include <stdbool.h>")?;
+	writeln!(out, "#include <stddef.h>")?;
+	writeln!(out, "#include <stdint.h>")?;
+	writeln!(out)?;
+	writeln!(out, "#ifndef CPPDVT_THISCALL")?;
+	writeln!(out, "#if defined(_MSC_VER) && defined(_M_IX86)")?;
+	writeln!(out, "#define CPPDVT_THISCALL __thiscall")?;
+	writeln!(out, "#else")?;
+	writeln!(out, "#define CPPDVT_THISCALL")?;
+	writeln!(out, "#endif")?;
+	writeln!(out, "#endif")?;
+	writeln!(out)?;
+	writeln!(out, "struct {name};")?;
+	writeln!(out)?;
+	writeln!(out, "struct {name}Vtbl {{")?;
+	for slot in class.inherited.iter().chain(class.slots) {
+		let convention = if slot.is_variadic() { "" } else { "CPPDVT_THISCALL " };
+		out.write_str("\t")?;
+		write_type(out, slot.ret, class.types)?;
+		write!(out, " ({convention}*{})(", slot.name)?;
+		write_params(out, Some(format_args!("struct {name}* self")), slot, class.types)?;
+		writeln!(out, ");")?;
+	}
+	writeln!(out, "}};")?;
+	writeln!(out)?;
+	writeln!(out, "struct {name} {{")?;
+	writeln!(out, "\tconst struct {name}Vtbl* vtbl;")?;
+	writeln!(out, "}};")
+}
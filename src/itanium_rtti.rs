@@ -0,0 +1,227 @@
+use ::core::ffi::{
+	c_char, CStr,
+};
+
+use super::{
+	PrefixedVTable, VTablePtr,
+};
+
+unsafe extern "C" {
+	#[link_name = "_ZTVN10__cxxabiv117__class_type_infoE"]
+	static CLASS_TYPE_INFO_VTABLE: PrefixedVTable<(), 2>;
+	#[link_name = "_ZTVN10__cxxabiv120__si_class_type_infoE"]
+	static SI_CLASS_TYPE_INFO_VTABLE: PrefixedVTable<(), 2>;
+	#[link_name = "_ZTVN10__cxxabiv121__vmi_class_type_infoE"]
+	static VMI_CLASS_TYPE_INFO_VTABLE: PrefixedVTable<(), 2>;
+}
+
+/// Layout of `std::type_info` as specified by the Itanium ABI,
+/// which every RTTI type descriptor starts with.
+/// 
+/// Type descriptors defined by C++ can be declared as `extern` statics of this type,
+/// by their symbols such as `_ZTI3Pet`.
+/// 
+/// The VTables of type descriptors are defined by the C++ runtime,
+/// so binaries that use type descriptors created in Rust must link to it,
+/// such as to `libstdc++` or `libc++abi`.
+#[repr(C)]
+pub struct TypeInfo {
+	vtable: VTablePtr<()>,
+	name: *const c_char,
+}
+
+// SAFETY: `TypeInfo` is immutable.
+unsafe impl Sync for TypeInfo {}
+
+impl TypeInfo {
+	const fn new(vtable: VTablePtr<()>, name: &'static CStr) -> Self {
+		Self { vtable, name: name.as_ptr() }
+	}
+
+	/// Return the mangled name of the type, such as `3Pet`.
+	pub fn name(&self) -> &CStr {
+		// SAFETY: Type descriptors have a valid name.
+		unsafe { CStr::from_ptr(self.name) }
+	}
+}
+
+/// Type descriptor of a class without bases, `__cxxabiv1::__class_type_info`.
+/// 
+/// These are usually created with [`itanium_type_info!`](crate::itanium_type_info!).
+#[repr(C)]
+pub struct ClassTypeInfo {
+	type_info: TypeInfo,
+}
+
+impl ClassTypeInfo {
+	/// Describe a class with the mangled name `name`, such as `3Pet`.
+	pub const fn new(name: &'static CStr) -> Self {
+		// SAFETY: The C++ runtime defines the VTable.
+		let vtable = unsafe { CLASS_TYPE_INFO_VTABLE.address_point() };
+		Self { type_info: TypeInfo::new(vtable, name) }
+	}
+
+	/// Return the type descriptor as a [`TypeInfo`].
+	pub const fn as_type_info(&self) -> &TypeInfo {
+		&self.type_info
+	}
+}
+
+/// Type descriptor of a class with a single public, non-virtual base at offset 0,
+/// `__cxxabiv1::__si_class_type_info`.
+/// 
+/// These are usually created with [`itanium_type_info!`](crate::itanium_type_info!).
+#[repr(C)]
+pub struct SiClassTypeInfo {
+	type_info: TypeInfo,
+	base: &'static TypeInfo,
+}
+
+impl SiClassTypeInfo {
+	/// Describe a class with the mangled name `name` that derives from `base`.
+	pub const fn new(name: &'static CStr, base: &'static TypeInfo) -> Self {
+		// SAFETY: The C++ runtime defines the VTable.
+		let vtable = unsafe { SI_CLASS_TYPE_INFO_VTABLE.address_point() };
+		Self { type_info: TypeInfo::new(vtable, name), base }
+	}
+
+	/// Return the type descriptor as a [`TypeInfo`].
+	pub const fn as_type_info(&self) -> &TypeInfo {
+		&self.type_info
+	}
+}
+
+/// Description of a base class in a [`VmiClassTypeInfo`],
+/// `__cxxabiv1::__base_class_type_info`.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct BaseClassTypeInfo {
+	base: &'static TypeInfo,
+	offset_flags: isize,
+}
+
+impl BaseClassTypeInfo {
+	/// Describe the non-virtual base `base` at `offset` bytes from the start of the class.
+	pub const fn new(base: &'static TypeInfo, offset: isize, is_public: bool) -> Self {
+		Self {
+			base,
+			offset_flags: offset << 8 | if is_public { 2 } else { 0 },
+		}
+	}
+}
+
+/// Type descriptor of a class with any other bases, `__cxxabiv1::__vmi_class_type_info`.
+/// 
+/// These are usually created with [`itanium_type_info!`](crate::itanium_type_info!).
+#[repr(C)]
+pub struct VmiClassTypeInfo<const N: usize> {
+	type_info: TypeInfo,
+	flags: u32,
+	base_count: u32,
+	bases: [BaseClassTypeInfo; N],
+}
+
+impl<const N: usize> VmiClassTypeInfo<N> {
+	/// Describe a class with the mangled name `name` that derives from `bases`,
+	/// which must not repeat a class.
+	pub const fn new(name: &'static CStr, bases: [BaseClassTypeInfo; N]) -> Self {
+		// SAFETY: The C++ runtime defines the VTable.
+		let vtable = unsafe { VMI_CLASS_TYPE_INFO_VTABLE.address_point() };
+		Self {
+			type_info: TypeInfo::new(vtable, name),
+			flags: 0,
+			base_count: N as u32,
+			bases,
+		}
+	}
+
+	/// Return the type descriptor as a [`TypeInfo`].
+	pub const fn as_type_info(&self) -> &TypeInfo {
+		&self.type_info
+	}
+}
+
+/// Layout of a VTable with the prefix specified by the Itanium ABI,
+/// which C++ uses for `dynamic_cast` and `typeid`.
+/// 
+/// These are usually created with [`itanium_vtable!`](crate::itanium_vtable!).
+/// Generic VTables can be created in constants instead.
+/// 
+/// # Examples
+/// ```
+/// # #[cfg_attr(target_os = "linux", link(name = "stdc++"))]
+/// # #[cfg_attr(target_vendor = "apple", link(name = "c++"))]
+/// # unsafe extern "C" {}
+/// # use core::ptr::NonNull;
+/// # use cppdvt::{ItaniumVTable, VtImpl, VTablePtr, VtObjectPtr, itanium_type_info, vtable, virtual_fn};
+/// vtable! {
+/// 	StackVt[T] {
+/// 		pub fn push(value: NonNull<T>);
+/// 		pub fn length() -> usize;
+/// 	}
+/// }
+/// 
+/// #[repr(C)]
+/// struct VecStack<T> {
+/// 	vtable: VTablePtr<StackVt<T>>,
+/// 	stack: Vec<T>,
+/// }
+/// 
+/// itanium_type_info! {
+/// 	static VEC_STACK_TYPE_INFO = "8VecStack";
+/// }
+/// 
+/// impl<T: 'static> VecStack<T> {
+/// 	virtual_fn! {
+/// 		fn push(this: VtObjectPtr<StackVt<T>>, value: NonNull<T>) {
+/// 			this.cast::<Self>().as_mut().stack.push(value.read());
+/// 		}
+/// 	}
+/// 	virtual_fn! {
+/// 		fn length(this: VtObjectPtr<StackVt<T>>) -> usize {
+/// 			this.cast::<Self>().as_ref().stack.len()
+/// 		}
+/// 	}
+/// 
+/// 	const ITANIUM_VTABLE: &'static ItaniumVTable<StackVt<T>> = &ItaniumVTable {
+/// 		offset_to_top: 0,
+/// 		type_info: VEC_STACK_TYPE_INFO.as_type_info(),
+/// 		vtable: StackVt { push: Self::push, length: Self::length },
+/// 	};
+/// }
+/// 
+/// unsafe impl<T: 'static> VtImpl<StackVt<T>> for VecStack<T> {
+/// 	const VTABLE: &'static StackVt<T> = &Self::ITANIUM_VTABLE.vtable;
+/// }
+/// 
+/// let vtable = VTablePtr::from_ref(VecStack::<u8>::VTABLE);
+/// let type_info = unsafe { ItaniumVTable::type_info_of(&vtable) };
+/// assert_eq!(type_info.name(), c"8VecStack");
+/// ```
+#[repr(C)]
+pub struct ItaniumVTable<VTable> {
+	/// Offset from the VTable pointer to the start of the object,
+	/// which is negative for VTables of base classes that are not at offset 0.
+	pub offset_to_top: isize,
+	/// Type descriptor of the most derived class.
+	pub type_info: &'static TypeInfo,
+	/// VTable at the address point.
+	pub vtable: VTable,
+}
+
+impl<VTable> ItaniumVTable<VTable> {
+	/// Returns a [`VTablePtr`] to the address point.
+	pub const fn address_point(&'static self) -> VTablePtr<VTable> {
+		VTablePtr::from_ref(&self.vtable)
+	}
+
+	/// Return the type descriptor in the prefix of the VTable at `vtable`.
+	/// 
+	/// # Safety
+	/// `vtable` must point to the address point of a VTable with an Itanium prefix.
+	pub unsafe fn type_info_of<'a>(vtable: &VTablePtr<VTable>) -> &'a TypeInfo {
+		let prefix = vtable.as_ptr().cast::<&'a TypeInfo>();
+		// SAFETY: The caller guarantees that the type descriptor precedes the address point.
+		unsafe { prefix.sub(1).read() }
+	}
+}
@@ -5,6 +5,10 @@
 #![no_std]
 #![allow(clippy::tabs_in_doc_comments)]
 
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc;
+
 #[cfg(feature = "macros")]
 mod macros;
 
@@ -12,3 +16,63 @@ mod vtable_ptr;
 pub use vtable_ptr::*;
 mod vt_object;
 pub use vt_object::*;
+mod closure;
+pub use closure::*;
+mod pin_init;
+pub use pin_init::*;
+#[cfg(feature = "alloc")]
+mod runtime_vtable;
+#[cfg(feature = "alloc")]
+pub use runtime_vtable::*;
+#[cfg(any(unix, windows))]
+mod dylib;
+#[cfg(any(unix, windows))]
+pub use dylib::*;
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+mod cpp_box;
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+pub use cpp_box::*;
+#[cfg(feature = "elf")]
+mod elf;
+#[cfg(feature = "elf")]
+pub use elf::*;
+#[cfg(feature = "pe")]
+mod pe;
+#[cfg(feature = "pe")]
+pub use pe::*;
+#[cfg(feature = "demangle")]
+mod demangle;
+#[cfg(feature = "demangle")]
+pub use demangle::*;
+#[cfg(feature = "demangle")]
+mod undecorate;
+#[cfg(feature = "demangle")]
+pub use undecorate::*;
+mod mangle;
+pub use mangle::*;
+mod itanium_rtti;
+pub use itanium_rtti::*;
+mod msvc_rtti;
+pub use msvc_rtti::*;
+mod com;
+pub use com::*;
+#[cfg(feature = "alloc")]
+mod com_server;
+#[cfg(feature = "alloc")]
+pub use com_server::*;
+#[cfg(feature = "alloc")]
+mod automation;
+#[cfg(feature = "alloc")]
+pub use automation::*;
+#[cfg(feature = "vst3")]
+mod vst3;
+#[cfg(feature = "vst3")]
+pub use vst3::*;
+mod create_interface;
+pub use create_interface::*;
+mod plugin;
+pub use plugin::*;
+mod meta;
+pub use meta::*;
+mod header;
+pub use header::*;
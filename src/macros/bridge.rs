@@ -0,0 +1,320 @@
+/// Implementation detail of [`bridge_trait!`](crate::bridge_trait!).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! bridge_trait_impl {
+	{
+		@parse
+		$Trait:ident $VTable:ident
+		/* methods */ {$($method:tt)*}
+		$(#[$fn_attr:meta])*
+		fn $fn_name:ident(&self $(, $param:ident: $Param:ty)* $(,)?) $(-> $Ret:ty)?;
+		$($rest:tt)*
+	} => {
+		$crate::bridge_trait_impl! {
+			@parse
+			$Trait $VTable
+			/* methods */ {$($method)* {ref $fn_name ($($param: $Param),*) ($($Ret)?)}}
+			$($rest)*
+		}
+	};
+	{
+		@parse
+		$Trait:ident $VTable:ident
+		/* methods */ {$($method:tt)*}
+		$(#[$fn_attr:meta])*
+		fn $fn_name:ident(&mut self $(, $param:ident: $Param:ty)* $(,)?) $(-> $Ret:ty)?;
+		$($rest:tt)*
+	} => {
+		$crate::bridge_trait_impl! {
+			@parse
+			$Trait $VTable
+			/* methods */ {$($method)* {mut $fn_name ($($param: $Param),*) ($($Ret)?)}}
+			$($rest)*
+		}
+	};
+	{
+		@parse
+		$Trait:ident $VTable:ident
+		/* methods */ {$($method:tt)*}
+	} => {
+		$crate::bridge_trait_impl! {
+			@create
+			$Trait $VTable
+			$($method)*
+		}
+	};
+	{
+		@parse
+		$Trait:ident $VTable:ident
+		$methods:tt
+		$($whatever:tt)*
+	} => {
+		::core::compile_error! {
+			"only methods that take `&self` or `&mut self` and named parameters are allowed in bridged traits"
+		}
+	};
+
+	{
+		@create
+		$Trait:ident $VTable:ident
+		$({$kind:ident $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)})*
+	} => {
+		$($crate::bridge_trait_impl! {
+			@forward $VTable $kind $fn_name ($($param: $Param),*) ($($Ret)?)
+		})*
+	};
+
+	{
+		@forward $VTable:ident ref $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)
+	} => {
+		$crate::virtual_fn! {
+			fn $fn_name(this: $crate::VtObjectPtr<$VTable>, $($param: $Param),*) $(-> $Ret)? {
+				(&**this.cast::<Self>().as_ref().inner.get()).$fn_name($($param),*)
+			}
+		}
+	};
+	{
+		@forward $VTable:ident mut $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)
+	} => {
+		$crate::virtual_fn! {
+			fn $fn_name(this: $crate::VtObjectPtr<$VTable>, $($param: $Param),*) $(-> $Ret)? {
+				(&mut **this.cast::<Self>().as_ref().inner.get()).$fn_name($($param),*)
+			}
+		}
+	};
+
+	{
+		@call ref $Object:ident $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)
+	} => {
+		fn $fn_name(&self, $($param: $Param),*) $(-> $Ret)? {
+			unsafe { $crate::virtual_call!(<$Object> self.0 => $fn_name($($param),*)) }
+		}
+	};
+	{
+		@call mut $Object:ident $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)
+	} => {
+		fn $fn_name(&mut self, $($param: $Param),*) $(-> $Ret)? {
+			unsafe { $crate::virtual_call!(mut <$Object> self.0 => $fn_name($($param),*)) }
+		}
+	};
+
+	(@vtable $VTable:ident {
+		$(
+			$(#[$fn_attr:meta])*
+			fn $fn_name:ident($($param:tt)*) $(-> $Ret:ty)?;
+		)*
+	}) => {
+		$VTable {
+			$($fn_name: Self::$fn_name,)*
+		}
+	};
+
+	(@trait_impl $Trait:ident $Object:ident $Cpp:ident {$($method:tt)*}) => {
+		impl $Trait for $Cpp {
+			$crate::bridge_trait_impl!(@trait_methods $Object {} $($method)*);
+		}
+	};
+
+	(
+		@trait_methods $Object:ident {$($done:tt)*}
+		$(#[$fn_attr:meta])*
+		fn $fn_name:ident(&self $(, $param:ident: $Param:ty)* $(,)?) $(-> $Ret:ty)?;
+		$($rest:tt)*
+	) => {
+		$crate::bridge_trait_impl!(@trait_methods $Object {
+			$($done)*
+			$crate::bridge_trait_impl! {
+				@call ref $Object $fn_name ($($param: $Param),*) ($($Ret)?)
+			}
+		} $($rest)*);
+	};
+	(
+		@trait_methods $Object:ident {$($done:tt)*}
+		$(#[$fn_attr:meta])*
+		fn $fn_name:ident(&mut self $(, $param:ident: $Param:ty)* $(,)?) $(-> $Ret:ty)?;
+		$($rest:tt)*
+	) => {
+		$crate::bridge_trait_impl!(@trait_methods $Object {
+			$($done)*
+			$crate::bridge_trait_impl! {
+				@call mut $Object $fn_name ($($param: $Param),*) ($($Ret)?)
+			}
+		} $($rest)*);
+	};
+	(@trait_methods $Object:ident {$($done:tt)*}) => {
+		$($done)*
+	};
+}
+
+/// Bridges a Rust trait and a C++ VTable in both directions.
+/// 
+/// Given `Trait <=> VTable { /* methods */ }`,
+/// where the methods are the methods of `Trait` in the order of the slots of `VTable`,
+/// followed by two `struct` declarations, this generates:
+/// - A `repr(C)` object wrapping a `Box<dyn Trait>`,
+///   whose VTable forwards every slot to the trait object.
+///   It is created pinned on the heap with `new`, and used with C++
+///   through `as_object` and `as_ptr`.
+/// - A `repr(transparent)` wrapper around [`VtObject<VTable>`](crate::VtObject)
+///   that implements `Trait` through [`virtual_call!`](crate::virtual_call!).
+///   It is created with `from_object`, `from_object_mut` or `from_ptr`.
+///   If the C++ object is actually the first generated type,
+///   the trait object is called without going through the VTable.
+/// 
+/// Every method must take `&self` or `&mut self`, and its parameters must be named.
+/// The slots of `VTable` must use the calling convention of [`vtable!`](crate::vtable!).
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{vtable, bridge_trait};
+/// trait Renderer {
+/// 	fn draw(&mut self, x: i32, y: i32);
+/// 	fn draw_count(&self) -> u32;
+/// }
+/// 
+/// vtable! {
+/// 	RendererVt {
+/// 		pub fn draw(x: i32, y: i32);
+/// 		pub fn draw_count() -> u32;
+/// 	}
+/// }
+/// 
+/// bridge_trait! {
+/// 	Renderer <=> RendererVt {
+/// 		fn draw(&mut self, x: i32, y: i32);
+/// 		fn draw_count(&self) -> u32;
+/// 	}
+/// 
+/// 	/// A [`Renderer`] that can be used by C++.
+/// 	pub struct RendererObject;
+/// 	/// A C++ `IRenderer` that can be used as a [`Renderer`].
+/// 	pub struct CppRenderer;
+/// }
+/// 
+/// struct Counter(u32);
+/// 
+/// impl Renderer for Counter {
+/// 	fn draw(&mut self, _x: i32, _y: i32) {
+/// 		self.0 += 1;
+/// 	}
+/// 	fn draw_count(&self) -> u32 {
+/// 		self.0
+/// 	}
+/// }
+/// 
+/// let object = RendererObject::new(Box::new(Counter(0)));
+/// // Pretend that C++ gave this pointer back to Rust.
+/// let ptr = object.as_ref().as_ptr();
+/// let renderer = unsafe { CppRenderer::from_ptr(ptr) };
+/// renderer.draw(1, 2);
+/// renderer.draw(3, 4);
+/// assert_eq!(renderer.draw_count(), 2);
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! bridge_trait {
+	{
+		$Trait:ident <=> $VTable:ident {
+			$($method:tt)*
+		}
+
+		$(#[$object_attr:meta])*
+		$object_vis:vis struct $Object:ident;
+		$(#[$cpp_attr:meta])*
+		$cpp_vis:vis struct $Cpp:ident;
+	} => {
+		$(#[$object_attr])*
+		#[repr(C)]
+		$object_vis struct $Object {
+			vtable: $crate::VTablePtr<$VTable>,
+			inner: ::core::cell::UnsafeCell<$crate::alloc::boxed::Box<dyn $Trait>>,
+			_pinned: ::core::marker::PhantomPinned,
+		}
+
+		impl $Object {
+			/// Create a new object that forwards its VTable to `inner`.
+			pub fn new(
+				inner: $crate::alloc::boxed::Box<dyn $Trait>,
+			) -> ::core::pin::Pin<$crate::alloc::boxed::Box<Self>> {
+				$crate::alloc::boxed::Box::pin(Self {
+					vtable: $crate::VTablePtr::from_ref(<Self as $crate::VtImpl<$VTable>>::VTABLE),
+					inner: ::core::cell::UnsafeCell::new(inner),
+					_pinned: ::core::marker::PhantomPinned,
+				})
+			}
+
+			/// Return the trait object of the object.
+			pub fn into_inner(
+				this: ::core::pin::Pin<$crate::alloc::boxed::Box<Self>>,
+			) -> $crate::alloc::boxed::Box<dyn $Trait> {
+				// SAFETY: The object itself is dropped,
+				// so nothing can observe its address anymore.
+				let this = unsafe { ::core::pin::Pin::into_inner_unchecked(this) };
+				this.inner.into_inner()
+			}
+
+			/// Return a reference that represents the C++ object.
+			pub fn as_object(self: ::core::pin::Pin<&Self>) -> &$crate::VtObject<$VTable> {
+				unsafe { $crate::VtObject::from_ptr(self.as_ptr()) }
+			}
+
+			/// Return a pointer that can be used with C++.
+			/// 
+			/// The pointer stays valid until the object is dropped.
+			pub fn as_ptr(self: ::core::pin::Pin<&Self>) -> $crate::VtObjectPtr<$VTable> {
+				$crate::VtObjectPtr::from_ref(&self.get_ref().vtable)
+			}
+
+			$crate::bridge_trait_impl! {
+				@parse
+				$Trait $VTable
+				{}
+				$($method)*
+			}
+		}
+
+		unsafe impl $crate::VtImpl<$VTable> for $Object {
+			const VTABLE: &'static $VTable = &$crate::bridge_trait_impl!(@vtable $VTable {$($method)*});
+		}
+
+		$(#[$cpp_attr])*
+		#[repr(transparent)]
+		$cpp_vis struct $Cpp($crate::VtObject<$VTable>);
+
+		impl $Cpp {
+			/// Return a reference to a C++ object as a trait implementation.
+			pub fn from_object(object: &$crate::VtObject<$VTable>) -> &Self {
+				// SAFETY: `Self` is `repr(transparent)`.
+				unsafe { &*(object as *const $crate::VtObject<$VTable>).cast::<Self>() }
+			}
+
+			/// Return a mutable reference to a C++ object as a trait implementation.
+			pub fn from_object_mut(object: &mut $crate::VtObject<$VTable>) -> &mut Self {
+				// SAFETY: `Self` is `repr(transparent)`.
+				unsafe { &mut *(object as *mut $crate::VtObject<$VTable>).cast::<Self>() }
+			}
+
+			/// Return a mutable reference to a C++ object as a trait implementation
+			/// that is valid for the duration of `'a`.
+			/// 
+			/// # Safety
+			/// See `VtObject::from_ptr_mut`.
+			pub unsafe fn from_ptr<'a>(ptr: $crate::VtObjectPtr<$VTable>) -> &'a mut Self {
+				unsafe { Self::from_object_mut($crate::VtObject::from_ptr_mut(ptr)) }
+			}
+		}
+
+		impl ::core::convert::AsRef<$crate::VtObject<$VTable>> for $Cpp {
+			fn as_ref(&self) -> &$crate::VtObject<$VTable> {
+				&self.0
+			}
+		}
+		impl ::core::convert::AsMut<$crate::VtObject<$VTable>> for $Cpp {
+			fn as_mut(&mut self) -> &mut $crate::VtObject<$VTable> {
+				&mut self.0
+			}
+		}
+
+		$crate::bridge_trait_impl!(@trait_impl $Trait $Object $Cpp {$($method)*});
+	};
+}
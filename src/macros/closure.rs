@@ -0,0 +1,149 @@
+/// Implementation detail of [`closure_vtable!`](crate::closure_vtable!).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! closure_vtable_impl {
+	{
+		@assign
+		$VTable:ident
+		/* pool */ {$($pool:tt)*}
+		/* slots */ {$($slot:tt)*}
+		/* generics */ {$($generic:ident)*}
+		$(#[$fn_attr:meta])*
+		$fn_vis:vis fn $fn_name:ident($($param:ident: $Param:ty),* $(,)?) $(-> $Ret:ty)?;
+		$($rest:tt)*
+	} => {
+		$crate::closure_vtable_impl! {
+			@assign_next
+			$VTable
+			/* pool */ {$($pool)*}
+			/* slots */ {$($slot)*}
+			/* generics */ {$($generic)*}
+			/* slot */ {$fn_name ($($param: $Param),*) ($($Ret)?)}
+			$($rest)*
+		}
+	};
+	{
+		@assign_next
+		$VTable:ident
+		/* pool */ {($F:ident $index:tt) $($pool:tt)*}
+		/* slots */ {$($slot:tt)*}
+		/* generics */ {$($generic:ident)*}
+		/* slot */ {$($new_slot:tt)*}
+		$($rest:tt)*
+	} => {
+		$crate::closure_vtable_impl! {
+			@assign
+			$VTable
+			/* pool */ {$($pool)*}
+			/* slots */ {$($slot)* {$F $index $($new_slot)*}}
+			/* generics */ {$($generic)* $F}
+			$($rest)*
+		}
+	};
+	{
+		@assign_next
+		$VTable:ident
+		/* pool */ {}
+		$($whatever:tt)*
+	} => {
+		::core::compile_error! {
+			"too many slots in closure vtable"
+		}
+	};
+	{
+		@assign
+		$VTable:ident
+		/* pool */ $pool:tt
+		/* slots */ $slots:tt
+		/* generics */ {$($generic:ident)*}
+	} => {
+		$crate::closure_vtable_impl! {
+			@create
+			$VTable
+			/* closures */ ($($generic,)*)
+			/* generics */ {$($generic)*}
+			$slots
+		}
+	};
+	{
+		@assign
+		$VTable:ident
+		$pool:tt
+		$slots:tt
+		$generics:tt
+		$($whatever:tt)*
+	} => {
+		::core::compile_error! {
+			"only `fn` items with named parameters are allowed in closure vtable bodies"
+		}
+	};
+
+	{
+		@create
+		$VTable:ident
+		$Closures:tt
+		{$($generic:ident)*}
+		{$({$F:ident $index:tt $fn_name:ident ($($param:ident: $Param:ty),*) ($($Ret:ty)?)})*}
+	} => {
+		const _: () = {
+			struct Slots<F>(::core::marker::PhantomData<F>);
+
+			#[allow(non_snake_case)]
+			impl<$($generic),*> Slots<$Closures>
+			where
+				$($F: ::core::ops::FnMut($($Param),*) $(-> $Ret)?,)*
+			{
+				$(
+					$crate::virtual_fn! {
+						fn $fn_name(this: $crate::VtObjectPtr<$VTable>, $($param: $Param),*) $(-> $Ret)? {
+							let closures = $crate::ClosureObject::<$VTable, $Closures>::closures_from_ptr(this);
+							(closures.$index)($($param),*)
+						}
+					}
+				)*
+			}
+
+			unsafe impl<$($generic),*> $crate::ClosureVTable<$Closures> for $VTable
+			where
+				$($F: ::core::ops::FnMut($($Param),*) $(-> $Ret)?,)*
+			{
+				const VTABLE: &'static Self = &$VTable {
+					$($fn_name: Slots::<$Closures>::$fn_name,)*
+				};
+			}
+		};
+	};
+}
+
+/// Implements [`ClosureVTable`](crate::ClosureVTable) for a VTable,
+/// so that [`ClosureObject`](crate::ClosureObject)s can be created for it.
+/// 
+/// The body repeats the slots of the VTable in the same syntax as [`vtable!`](crate::vtable!),
+/// but every parameter must be named.
+/// The closures are given as a tuple with one closure per slot, in order,
+/// and each closure receives the parameters of its slot, without `this`.
+/// Each tuple of closure types gets its own VTable, generated at compile time.
+/// 
+/// Generic VTables, variadic slots and more than 16 slots are not supported.
+/// 
+/// See [`ClosureObject`](crate::ClosureObject) for an example.
+#[macro_export]
+macro_rules! closure_vtable {
+	{
+		$VTable:ident {
+			$($body:tt)*
+		}
+	} => {
+		$crate::closure_vtable_impl! {
+			@assign
+			$VTable
+			/* pool */ {
+				(F0 0) (F1 1) (F2 2) (F3 3) (F4 4) (F5 5) (F6 6) (F7 7)
+				(F8 8) (F9 9) (F10 10) (F11 11) (F12 12) (F13 13) (F14 14) (F15 15)
+			}
+			/* slots */ {}
+			/* generics */ {}
+			$($body)*
+		}
+	};
+}
@@ -0,0 +1,323 @@
+/// Expands to a `fn` type or `fn` item
+/// that represents a method of a COM interface
+/// with the calling convention of COM, which is `stdcall` on
+/// `cfg(all(windows, target_arch = "x86"))` and `C` elsewhere.
+/// 
+/// See also [`virtual_fn!`](crate::virtual_fn!).
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{HRESULT, VtObjectPtr, com_fn};
+/// # #[repr(C)] struct IStreamVt {}
+/// // `IStreamVt` is some COM interface.
+/// type CommitFn = com_fn!(fn(this: VtObjectPtr<IStreamVt>, flags: u32) -> HRESULT);
+/// com_fn! {
+/// 	fn commit(this: VtObjectPtr<IStreamVt>, flags: u32) -> HRESULT {
+/// 		HRESULT::S_OK
+/// 	}
+/// }
+/// 
+/// let _: CommitFn = commit;
+/// ```
+#[macro_export]
+macro_rules! com_fn {
+	{
+		$(#[$attr:meta])*
+		fn $($name:ident)?
+		$([$($generic:tt)*])?
+		($($param:tt)*)
+		$($tt:tt)*
+	} => {
+		$(#[$attr])*
+		unsafe extern "system" fn $($name)?
+		$(<$($generic)*>)?
+		($($param)*) $($tt)*
+	};
+}
+
+/// Like [`vtable!`](crate::vtable!),
+/// but uses the calling convention of COM methods, as [`com_fn!`](crate::com_fn!) does.
+/// 
+/// COM interfaces start with [`IUnknownVt`](crate::IUnknownVt),
+/// so their methods are usually declared as an extension whose `this` is the full VTable.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{GUID, HRESULT, IUnknownVt, Interface, VtObjectPtr, com_vtable};
+/// com_vtable! {
+/// 	/// Methods of `IPersist`.
+/// 	pub IPersistVtExt for VtObjectPtr<IPersistVt> {
+/// 		pub fn get_class_id(class_id: *mut GUID) -> HRESULT;
+/// 	}
+/// }
+/// 
+/// /// VTable of `IPersist`.
+/// #[repr(C)]
+/// pub struct IPersistVt {
+/// 	pub base: IUnknownVt,
+/// 	pub persist: IPersistVtExt,
+/// }
+/// 
+/// unsafe impl Interface for IPersistVt {
+/// 	const IID: GUID = GUID::from_u128(0x0000010C_0000_0000_C000_000000000046);
+/// }
+/// ```
+#[macro_export]
+macro_rules! com_vtable {
+	{
+		$(#[$vt_attr:meta])*
+		$vt_vis:vis $VTable:ident
+		[$($generic:tt)*]
+		$($rest:tt)*
+	} => {
+		$crate::vtable_impl! {
+			@parse_after_name
+			$crate::com_fn;
+			{<$($generic)*>}
+			{}
+			{}
+			{$(#[$vt_attr])*}
+			$vt_vis $VTable
+			$($rest)*
+		}
+	};
+	{
+		$(#[$vt_attr:meta])*
+		$vt_vis:vis $VTable:ident
+		$($rest:tt)*
+	} => {
+		$crate::vtable_impl! {
+			@parse_after_name
+			$crate::com_fn;
+			{}
+			{}
+			{}
+			{$(#[$vt_attr])*}
+			$vt_vis $VTable
+			$($rest)*
+		}
+	};
+}
+
+/// Implements [`ComClass`](crate::ComClass) for Rust types,
+/// so that a [`ComObject`](crate::ComObject) of them implements several COM interfaces.
+/// 
+/// Each type is written as `unsafe Type`, followed by its interfaces in braces,
+/// written as `Interface => vtable`,
+/// where `Interface` implements [`Interface`](crate::Interface)
+/// and `vtable` is a constant expression of that type.
+/// In `vtable`, `UNKNOWN` is the [`IUnknownVt`](crate::IUnknownVt) of the type,
+/// which handles `QueryInterface`, `AddRef` and `Release`.
+/// The first interface is the one returned for `IUnknown`.
+/// 
+/// The type can be preceded by `#[no_interface = code]` to set
+/// [`ComClass::NO_INTERFACE`](crate::ComClass::NO_INTERFACE).
+/// 
+/// Methods of the interfaces get the object with
+/// [`ComObject::from_this`](crate::ComObject::from_this),
+/// which adjusts `this` for the interface that it points to.
+/// The types must not be generic.
+/// 
+/// # Safety
+/// Each `vtable` must start with the `UNKNOWN` of its type,
+/// such as [`ComObject::<Type>::UNKNOWN`](crate::ComObject::UNKNOWN)
+/// or [`ComObject::<Type>::DISPATCH`](crate::ComObject::DISPATCH),
+/// and not with the `IUnknownVt` of another type.
+/// 
+/// # Examples
+/// ```
+/// # use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+/// # use cppdvt::{GUID, IUnknownVt, Interface, ComObject, VtObjectPtr, com_fn, com_object, com_vtable, virtual_call};
+/// com_vtable! {
+/// 	IAnimalVtExt for VtObjectPtr<IAnimalVt> {
+/// 		pub fn legs() -> u32;
+/// 	}
+/// }
+/// #[repr(C)]
+/// struct IAnimalVt {
+/// 	base: IUnknownVt,
+/// 	animal: IAnimalVtExt,
+/// }
+/// unsafe impl Interface for IAnimalVt {
+/// 	const IID: GUID = GUID::parse("6A1D5F3E-0C2B-4E7A-9D3C-2F4B8E1A7C01").unwrap();
+/// }
+/// 
+/// com_vtable! {
+/// 	ISwimmerVtExt for VtObjectPtr<ISwimmerVt> {
+/// 		pub fn swim(distance: u32) -> u32;
+/// 	}
+/// }
+/// #[repr(C)]
+/// struct ISwimmerVt {
+/// 	base: IUnknownVt,
+/// 	swimmer: ISwimmerVtExt,
+/// }
+/// unsafe impl Interface for ISwimmerVt {
+/// 	const IID: GUID = GUID::parse("6A1D5F3E-0C2B-4E7A-9D3C-2F4B8E1A7C02").unwrap();
+/// }
+/// 
+/// static DROPPED: AtomicBool = AtomicBool::new(false);
+/// 
+/// struct Duck {
+/// 	swum: AtomicU32,
+/// }
+/// 
+/// impl Duck {
+/// 	com_fn! {
+/// 		fn legs(this: VtObjectPtr<IAnimalVt>) -> u32 {
+/// 			2
+/// 		}
+/// 	}
+/// 	com_fn! {
+/// 		fn swim(this: VtObjectPtr<ISwimmerVt>, distance: u32) -> u32 {
+/// 			let this = unsafe { ComObject::<Self>::from_this(this) };
+/// 			this.swum.fetch_add(distance, Ordering::Relaxed) + distance
+/// 		}
+/// 	}
+/// }
+/// 
+/// impl Drop for Duck {
+/// 	fn drop(&mut self) {
+/// 		DROPPED.store(true, Ordering::Relaxed);
+/// 	}
+/// }
+/// 
+/// com_object! {
+/// 	unsafe Duck {
+/// 		IAnimalVt => IAnimalVt {
+/// 			base: UNKNOWN,
+/// 			animal: IAnimalVtExt { legs: Duck::legs },
+/// 		},
+/// 		ISwimmerVt => ISwimmerVt {
+/// 			base: UNKNOWN,
+/// 			swimmer: ISwimmerVtExt { swim: Duck::swim },
+/// 		},
+/// 	}
+/// }
+/// 
+/// let duck = ComObject::create(Duck { swum: AtomicU32::new(0) });
+/// let animal = duck.query_interface::<IAnimalVt>().unwrap();
+/// let swimmer = animal.query_interface::<ISwimmerVt>().unwrap();
+/// drop(duck);
+/// drop(animal);
+/// 
+/// assert_eq!(unsafe { virtual_call!(swimmer => swimmer.swim(3)) }, 3);
+/// assert_eq!(unsafe { virtual_call!(swimmer => swimmer.swim(4)) }, 7);
+/// let animal = swimmer.query_interface::<IAnimalVt>().unwrap();
+/// assert_eq!(unsafe { virtual_call!(animal => animal.legs()) }, 2);
+/// 
+/// drop(swimmer);
+/// assert!(!DROPPED.load(Ordering::Relaxed));
+/// drop(animal);
+/// assert!(DROPPED.load(Ordering::Relaxed));
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! com_object {
+	{} => {};
+
+	{
+		$(#[no_interface = $no_interface:expr])?
+		unsafe $Type:ty {
+			$($Interface:ty => $vtable:expr),+ $(,)?
+		}
+		$($rest:tt)*
+	} => {
+		// SAFETY: The caller guarantees that each VTable starts with `ComObject::UNKNOWN`,
+		// and each VTable is a static that is found by the IIDs of its interface.
+		unsafe impl $crate::ComClass for $Type {
+			type VTables = [$crate::VTablePtr<()>; [$($crate::com_object!(@unit $Interface)),+].len()];
+
+			fn vtables() -> Self::VTables {
+				[$({
+					static VTABLE: $Interface = {
+						#[allow(unused)]
+						const UNKNOWN: $crate::IUnknownVt = $crate::ComObject::<$Type>::UNKNOWN;
+						$vtable
+					};
+					// SAFETY: `VTABLE` is a valid VTable.
+					unsafe { $crate::VTablePtr::new(::core::ptr::NonNull::from(&VTABLE).cast()) }
+				}),+]
+			}
+
+			fn interface_index(iid: &$crate::GUID) -> ::core::option::Option<usize> {
+				let matches: &[fn(&$crate::GUID) -> bool] = &[$(<$Interface as $crate::Interface>::matches),+];
+				matches.iter().position(|matches| matches(iid))
+			}
+
+			$(const NO_INTERFACE: $crate::HRESULT = $no_interface;)?
+		}
+
+		$crate::com_object! {$($rest)*}
+	};
+
+	{@unit $Interface:ty} => { () };
+}
+
+/// Defines the entry points of an in-process COM server,
+/// `DllGetClassObject` and `DllCanUnloadNow`,
+/// so that hosts can create objects of its classes by CLSID.
+/// 
+/// Each class is written as `CLSID => create`,
+/// where `create` is a [`CreateObjectFn`](crate::CreateObjectFn).
+/// The class objects are created with [`class_factory`](crate::class_factory).
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{ClassRegistry, ComObject, GUID, HRESULT, IUnknownVt, com_object, com_server};
+/// struct Plugin;
+/// com_object! {
+/// 	unsafe Plugin {
+/// 		IUnknownVt => UNKNOWN,
+/// 	}
+/// }
+/// 
+/// const CLSID_PLUGIN: GUID = GUID::parse("0B5A8F1C-7E2D-4C3A-9B6E-5D4F3A2B1C02").unwrap();
+/// 
+/// com_server! {
+/// 	CLSID_PLUGIN => || ComObject::create(Plugin),
+/// }
+/// 
+/// // A host would look up `DllGetClassObject` in the library instead.
+/// let mut registry = ClassRegistry::new();
+/// unsafe { registry.register_server(CLSID_PLUGIN, DllGetClassObject) };
+/// 
+/// let plugin = registry.create_instance::<IUnknownVt>(&CLSID_PLUGIN).unwrap();
+/// assert_eq!(unsafe { DllCanUnloadNow() }, HRESULT::S_FALSE);
+/// drop(plugin);
+/// assert_eq!(unsafe { DllCanUnloadNow() }, HRESULT::S_OK);
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! com_server {
+	{
+		$($clsid:expr => $create:expr),* $(,)?
+	} => {
+		/// Write a reference to the interface `iid` of the class object of `clsid` to `object`.
+		/// 
+		/// # Safety
+		/// The arguments must be valid for `DllGetClassObject`.
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub unsafe extern "system" fn DllGetClassObject(
+			clsid: *const $crate::GUID,
+			iid: *const $crate::GUID,
+			object: *mut *mut ::core::ffi::c_void,
+		) -> $crate::HRESULT {
+			static CLASSES: &[$crate::ClassEntry] = &[$(
+				$crate::ClassEntry { clsid: $clsid, create: $create },
+			)*];
+			unsafe { $crate::get_class_object(CLASSES, clsid, iid, object) }
+		}
+
+		/// Return whether the library can be unloaded.
+		/// 
+		/// # Safety
+		/// This is only meant to be called by COM hosts.
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub unsafe extern "system" fn DllCanUnloadNow() -> $crate::HRESULT {
+			$crate::dll_can_unload_now()
+		}
+	};
+}
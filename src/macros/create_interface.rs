@@ -0,0 +1,84 @@
+/// Exposes interfaces the way modules of the Source engine do,
+/// by defining `CreateInterface` and the list of [`InterfaceReg`](crate::InterfaceReg)s behind it.
+/// 
+/// Each interface is written as `name => create`, where `name` is its versioned name as a
+/// C string literal, and `create` is an [`InstantiateInterfaceFn`](crate::InstantiateInterfaceFn).
+/// The head of the list is the static `INTERFACE_REGS`, which is exported as
+/// [`INTERFACE_REGS_SYMBOL`](crate::INTERFACE_REGS_SYMBOL) so that tools can walk it.
+/// 
+/// # Examples
+/// ```
+/// # use core::ffi::c_void;
+/// # use cppdvt::{InterfaceFactory, VTablePtr, VtObject, VtObjectPtr, expose_interfaces, virtual_call, virtual_fn, vtable};
+/// vtable! {
+/// 	IServerVt {
+/// 		pub fn max_players() -> i32;
+/// 	}
+/// }
+/// 
+/// virtual_fn! {
+/// 	fn max_players(this: VtObjectPtr<IServerVt>) -> i32 {
+/// 		32
+/// 	}
+/// }
+/// 
+/// static SERVER_VT: IServerVt = IServerVt { max_players };
+/// static SERVER: VTablePtr<IServerVt> = VTablePtr::from_ref(&SERVER_VT);
+/// 
+/// unsafe extern "C" fn create_server() -> *mut c_void {
+/// 	core::ptr::from_ref(&SERVER).cast_mut().cast()
+/// }
+/// 
+/// expose_interfaces! {
+/// 	c"IServer002" => create_server,
+/// 	c"IServer003" => create_server,
+/// }
+/// 
+/// let names: Vec<_> = INTERFACE_REGS.iter().map(|reg| reg.name()).collect();
+/// assert_eq!(names, [c"IServer002", c"IServer003"]);
+/// assert_eq!(INTERFACE_REGS.iter().latest("IServer"), Some((c"IServer003", 3)));
+/// 
+/// // A host would look up `CreateInterface` in the library instead.
+/// let factory = unsafe { InterfaceFactory::new(CreateInterface) };
+/// let server: &VtObject<IServerVt> = unsafe { factory.get(c"IServer003") }.unwrap();
+/// assert_eq!(unsafe { virtual_call!(server => max_players()) }, 32);
+/// ```
+#[macro_export]
+macro_rules! expose_interfaces {
+	{
+		$($name:expr => $create:expr),+ $(,)?
+	} => {
+		/// Head of the list of exposed interfaces.
+		#[unsafe(export_name = "_ZN12InterfaceReg15s_pInterfaceRegsE")]
+		pub static INTERFACE_REGS: &$crate::InterfaceReg = $crate::expose_interfaces!(@list $($name => $create),+);
+
+		/// Return the exposed interface `name`, writing whether it was found to `return_code`.
+		/// 
+		/// # Safety
+		/// The arguments must be valid for `CreateInterface`,
+		/// and the functions that return the interfaces must be safe to call.
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub unsafe extern "C" fn CreateInterface(
+			name: *const ::core::ffi::c_char,
+			return_code: *mut ::core::ffi::c_int,
+		) -> *mut ::core::ffi::c_void {
+			unsafe { $crate::create_interface(INTERFACE_REGS, name, return_code) }
+		}
+	};
+
+	{@list $name:expr => $create:expr $(, $rest_name:expr => $rest_create:expr)*} => {{
+		static REG: $crate::InterfaceReg = $crate::InterfaceReg::new(
+			$name,
+			$create,
+			$crate::expose_interfaces!(@next $($rest_name => $rest_create),*),
+		);
+		&REG
+	}};
+
+	{@next} => { ::core::option::Option::None };
+
+	{@next $($rest:tt)+} => {
+		::core::option::Option::Some($crate::expose_interfaces!(@list $($rest)+))
+	};
+}
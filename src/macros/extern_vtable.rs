@@ -0,0 +1,80 @@
+/// Declares statics of type [`VTablePtr`](crate::VTablePtr) that point to the address points
+/// of VTables defined by C++ code linked into the binary, such as a static library,
+/// by the names of their symbols.
+/// 
+/// Each static is written as `unsafe static NAME: VTable = "symbol";`.
+/// By default, the address point is found the way the C++ ABI of the target lays out VTables:
+/// two words after the start of Itanium symbols such as `_ZTV3Foo`,
+/// skipping the offset-to-top and the RTTI pointer,
+/// and at the start of MSVC symbols such as `??_7Foo@@6B@`,
+/// which are placed after the pointer to the RTTI Complete Object Locator.
+/// Writing `= "symbol" + words` instead sets the number of words before the address point,
+/// which is needed for secondary VTables of Itanium VTable groups.
+/// 
+/// The statics can be used to construct objects,
+/// through [`VTablePtr::from_ref`](crate::VTablePtr::from_ref) and [`VTablePtr::as_ref`](crate::VTablePtr::as_ref),
+/// and to recognise objects by comparing their VTable pointers.
+/// 
+/// # Safety
+/// Each symbol must be a VTable with the layout of `VTable` at its address point,
+/// preceded by the number of words that is skipped.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{PrefixedVTable, VTablePtr, VtObject, VtObjectPtr, extern_vtable, vtable, virtual_fn};
+/// vtable! {
+/// 	PetVt {
+/// 		pub fn legs() -> u32;
+/// 	}
+/// }
+/// 
+/// // Pretend that this is `vtable for Pet`, defined by C++.
+/// virtual_fn! {
+/// 	fn pet_legs(this: VtObjectPtr<PetVt>) -> u32 {
+/// 		4
+/// 	}
+/// }
+/// #[export_name = "_ZTV3Pet"]
+/// static PET_VTABLE: PrefixedVTable<PetVt, 2> = PrefixedVTable {
+/// 	prefix: [0; 2],
+/// 	vtable: PetVt { legs: pet_legs },
+/// };
+/// 
+/// extern_vtable! {
+/// 	unsafe static PET_VT: PetVt = "_ZTV3Pet" + 2;
+/// }
+/// 
+/// assert_eq!(PET_VT, PET_VTABLE.address_point());
+/// let mut pet = VTablePtr::from_ref(PET_VT.as_ref());
+/// let pet = unsafe { VtObject::<PetVt>::from_ptr_mut(VtObjectPtr::from_mut(&mut pet)) };
+/// assert_eq!(*pet.vtable_ptr(), PET_VT);
+/// ```
+#[macro_export]
+macro_rules! extern_vtable {
+	{
+		$(
+			$(#[$attr:meta])*
+			$vis:vis unsafe static $name:ident: $VTable:ty = $symbol:literal $(+ $prefix:expr)?;
+		)*
+	} => {
+		$(
+			$(#[$attr])*
+			$vis static $name: $crate::VTablePtr<$VTable> = {
+				unsafe extern "C" {
+					#[link_name = $symbol]
+					static VTABLE: $crate::PrefixedVTable<
+						$VTable,
+						{ $crate::extern_vtable!(@prefix $($prefix)?) },
+					>;
+				}
+				// SAFETY: The caller names a VTable symbol with this layout.
+				unsafe { VTABLE.address_point() }
+			};
+		)*
+	};
+
+	{@prefix $prefix:expr} => { $prefix };
+	{@prefix} => {
+		if ::core::cfg!(target_env = "msvc") { 0 } else { 2 }
+	};
+}
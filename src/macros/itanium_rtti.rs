@@ -0,0 +1,246 @@
+/// Declares statics that are Itanium RTTI type descriptors for classes implemented in Rust,
+/// for use with [`itanium_vtable!`](crate::itanium_vtable!).
+/// 
+/// Each static is written as `static NAME = "mangled name": bases;`,
+/// where the mangled name is that of the class without the `_ZTS` prefix, such as `8VecStack`,
+/// and the optional bases are separated by commas.
+/// A base is either the symbol of a type descriptor defined by C++, such as `"_ZTI5Stack"`,
+/// or the name of a static declared by this macro,
+/// followed by `+ offset` if it is not at offset 0 of the class.
+/// Statics with bases defined by C++ are written as `unsafe static` instead.
+/// 
+/// The static is a [`ClassTypeInfo`](crate::ClassTypeInfo) for classes without bases,
+/// a [`SiClassTypeInfo`](crate::SiClassTypeInfo) for classes with a single base at offset 0,
+/// and a [`VmiClassTypeInfo`](crate::VmiClassTypeInfo) otherwise.
+/// All bases are public and non-virtual.
+/// 
+/// # Safety
+/// The symbols of bases defined by C++ must be type descriptors.
+/// 
+/// # Examples
+/// ```
+/// # #[cfg_attr(target_os = "linux", link(name = "stdc++"))]
+/// # #[cfg_attr(target_vendor = "apple", link(name = "c++"))]
+/// # unsafe extern "C" {}
+/// # use cppdvt::itanium_type_info;
+/// itanium_type_info! {
+/// 	static ANIMAL_TYPE_INFO = "6Animal";
+/// 	static SWIMMER_TYPE_INFO = "7Swimmer";
+/// 	static DOG_TYPE_INFO = "3Dog": ANIMAL_TYPE_INFO;
+/// 	static DUCK_TYPE_INFO = "4Duck": ANIMAL_TYPE_INFO, SWIMMER_TYPE_INFO + 16;
+/// 	// `std::exception` is defined by the C++ runtime.
+/// 	unsafe static ERROR_TYPE_INFO = "5Error": "_ZTISt9exception";
+/// }
+/// 
+/// assert_eq!(DUCK_TYPE_INFO.as_type_info().name(), c"4Duck");
+/// assert_eq!(ERROR_TYPE_INFO.as_type_info().name(), c"5Error");
+/// ```
+#[macro_export]
+macro_rules! itanium_type_info {
+	{} => {};
+
+	{
+		$(#[$attr:meta])*
+		$vis:vis unsafe static $($rest:tt)*
+	} => {
+		$crate::itanium_type_info! {@static [unsafe] $(#[$attr])* $vis static $($rest)*}
+	};
+
+	{
+		$(#[$attr:meta])*
+		$vis:vis static $($rest:tt)*
+	} => {
+		$crate::itanium_type_info! {@static [safe] $(#[$attr])* $vis static $($rest)*}
+	};
+
+	{
+		@static [$safety:ident]
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident = $mangled:literal;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::ClassTypeInfo = $crate::ClassTypeInfo::new(
+			$crate::itanium_type_info!(@name $mangled),
+		);
+		$crate::itanium_type_info! {$($rest)*}
+	};
+
+	{
+		@static [$safety:ident]
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident = $mangled:literal: $base:tt;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::SiClassTypeInfo = $crate::SiClassTypeInfo::new(
+			$crate::itanium_type_info!(@name $mangled),
+			$crate::itanium_type_info!(@base [$safety] $base),
+		);
+		$crate::itanium_type_info! {$($rest)*}
+	};
+
+	{
+		@static [$safety:ident]
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident = $mangled:literal: $($base:tt $(+ $offset:expr)?),+;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::VmiClassTypeInfo<{ [$($crate::itanium_type_info!(@unit $base)),+].len() }> =
+			$crate::VmiClassTypeInfo::new(
+				$crate::itanium_type_info!(@name $mangled),
+				[$(
+					$crate::BaseClassTypeInfo::new(
+						$crate::itanium_type_info!(@base [$safety] $base),
+						$crate::itanium_type_info!(@offset $($offset)?),
+						true,
+					)
+				),+],
+			);
+		$crate::itanium_type_info! {$($rest)*}
+	};
+
+	{@name $mangled:literal} => {
+		match ::core::ffi::CStr::from_bytes_with_nul(::core::concat!($mangled, "\0").as_bytes()) {
+			Ok(name) => name,
+			Err(_) => ::core::panic!("mangled names cannot contain nul bytes"),
+		}
+	};
+
+	{@base [unsafe] $symbol:literal} => {{
+		unsafe extern "C" {
+			#[link_name = $symbol]
+			static BASE: $crate::TypeInfo;
+		}
+		// SAFETY: The caller names a type descriptor.
+		unsafe { &BASE }
+	}};
+	{@base [safe] $symbol:literal} => {
+		::core::compile_error!("statics with bases defined by C++ must be declared as `unsafe static`")
+	};
+	{@base [$safety:ident] $base:ident} => {
+		$base.as_type_info()
+	};
+
+	{@offset} => { 0 };
+	{@offset $offset:expr} => { $offset };
+
+	{@unit $base:tt} => { () };
+}
+
+/// Declares statics of type [`VTablePtr`](crate::VTablePtr) that point to the address points
+/// of VTables with the prefix specified by the Itanium ABI,
+/// so that C++ can use `dynamic_cast` and `typeid` on objects implemented in Rust.
+/// 
+/// Each static is preceded by `#[type_info(TYPE_INFO)]`,
+/// where `TYPE_INFO` is a static declared by [`itanium_type_info!`](crate::itanium_type_info!)
+/// for the most derived class.
+/// The VTables of bases that are not at offset 0 of the class
+/// are preceded by `#[type_info(TYPE_INFO, offset = offset)]` instead.
+/// 
+/// See [`ItaniumVTable`](crate::ItaniumVTable) for generic VTables.
+/// 
+/// # Examples
+/// ```
+/// # #[cfg_attr(target_os = "linux", link(name = "stdc++"))]
+/// # #[cfg_attr(target_vendor = "apple", link(name = "c++"))]
+/// # unsafe extern "C" {}
+/// # use core::{ffi::c_void, ptr};
+/// # use cppdvt::{ItaniumVTable, TypeInfo, VTablePtr, VtObjectPtr, itanium_type_info, itanium_vtable, vtable, virtual_fn};
+/// vtable! {
+/// 	AnimalVt {
+/// 		pub fn legs() -> u32;
+/// 	}
+/// }
+/// vtable! {
+/// 	SwimmerVt {
+/// 		pub fn swim();
+/// 	}
+/// }
+/// 
+/// virtual_fn! {
+/// 	fn duck_legs(this: VtObjectPtr<AnimalVt>) -> u32 {
+/// 		2
+/// 	}
+/// }
+/// virtual_fn! {
+/// 	fn duck_swim(this: VtObjectPtr<SwimmerVt>) {}
+/// }
+/// 
+/// itanium_type_info! {
+/// 	static ANIMAL_TYPE_INFO = "6Animal";
+/// 	static SWIMMER_TYPE_INFO = "7Swimmer";
+/// 	static DOG_TYPE_INFO = "3Dog": ANIMAL_TYPE_INFO;
+/// 	static DUCK_TYPE_INFO = "4Duck": ANIMAL_TYPE_INFO, SWIMMER_TYPE_INFO + 8;
+/// }
+/// 
+/// // `Duck` derives from `Animal`, and from `Swimmer` at offset 8.
+/// itanium_vtable! {
+/// 	#[type_info(DUCK_TYPE_INFO)]
+/// 	static DUCK_VT: AnimalVt = AnimalVt { legs: duck_legs };
+/// 	#[type_info(DUCK_TYPE_INFO, offset = 8)]
+/// 	static DUCK_SWIMMER_VT: SwimmerVt = SwimmerVt { swim: duck_swim };
+/// }
+/// 
+/// let type_info = unsafe { ItaniumVTable::type_info_of(&DUCK_SWIMMER_VT) };
+/// assert_eq!(type_info.name(), c"4Duck");
+/// 
+/// // C++ can now cast between the classes of a `Duck` with `dynamic_cast`.
+/// #[repr(C)]
+/// struct Duck {
+/// 	animal: VTablePtr<AnimalVt>,
+/// 	swimmer: VTablePtr<SwimmerVt>,
+/// }
+/// 
+/// unsafe extern "C" {
+/// 	fn __dynamic_cast(
+/// 		src: *const c_void,
+/// 		src_type: *const TypeInfo,
+/// 		dst_type: *const TypeInfo,
+/// 		src2dst: isize,
+/// 	) -> *mut c_void;
+/// }
+/// 
+/// let duck = unsafe {
+/// 	Duck {
+/// 		animal: VTablePtr::new(DUCK_VT.as_ptr()),
+/// 		swimmer: VTablePtr::new(DUCK_SWIMMER_VT.as_ptr()),
+/// 	}
+/// };
+/// let animal = ptr::from_ref(&duck).cast::<c_void>();
+/// let swimmer = ptr::from_ref(&duck.swimmer).cast::<c_void>();
+/// unsafe {
+/// 	let cast = |src, src_type: &TypeInfo, dst_type: &TypeInfo| {
+/// 		__dynamic_cast(src, src_type, dst_type, -1).cast_const()
+/// 	};
+/// 	// Downcast from `Animal` to `Duck`.
+/// 	assert_eq!(cast(animal, ANIMAL_TYPE_INFO.as_type_info(), DUCK_TYPE_INFO.as_type_info()), animal);
+/// 	// Cross-cast from `Animal` to `Swimmer`.
+/// 	assert_eq!(cast(animal, ANIMAL_TYPE_INFO.as_type_info(), SWIMMER_TYPE_INFO.as_type_info()), swimmer);
+/// 	// A `Duck` is not a `Dog`.
+/// 	assert!(cast(swimmer, SWIMMER_TYPE_INFO.as_type_info(), DOG_TYPE_INFO.as_type_info()).is_null());
+/// }
+/// ```
+#[macro_export]
+macro_rules! itanium_vtable {
+	{} => {};
+
+	{
+		#[type_info($type_info:path $(, offset = $offset:expr)?)]
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident: $VTable:ty = $vtable:expr;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::VTablePtr<$VTable> = {
+			static VTABLE: $crate::ItaniumVTable<$VTable> = $crate::ItaniumVTable {
+				offset_to_top: -$crate::itanium_type_info!(@offset $($offset)?),
+				type_info: $type_info.as_type_info(),
+				vtable: $vtable,
+			};
+			VTABLE.address_point()
+		};
+		$crate::itanium_vtable! {$($rest)*}
+	};
+}
@@ -0,0 +1,166 @@
+/// Expands to the name of a C++ function mangled according to the Itanium C++ ABI,
+/// as a `&'static str` constant.
+/// 
+/// The function is written as in C++: its qualified name, the types of its parameters
+/// and an optional `const` qualifier. Parameter types can be builtin types such as
+/// `unsigned int`, classes such as `zoo::Pet`, and pointers and references to them,
+/// with `const` and `volatile` on either side.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::itanium_mangle;
+/// assert_eq!(itanium_mangle!(zoo::Pet::speak()), "_ZN3zoo3Pet5speakEv");
+/// assert_eq!(itanium_mangle!(zoo::Pet::legs() const), "_ZNK3zoo3Pet4legsEv");
+/// assert_eq!(itanium_mangle!(zoo::Pet::rename(const char*, unsigned long)), "_ZN3zoo3Pet6renameEPKcm");
+/// assert_eq!(itanium_mangle!(zoo::Pet::play(zoo::Pet&, const zoo::Pet*)), "_ZN3zoo3Pet4playERS0_PKS0_");
+/// assert_eq!(itanium_mangle!(log(const char*, char const*)), "_Z3logPKcS0_");
+/// 
+/// const FEED: &str = itanium_mangle!(zoo::Pet::feed(std::thread&&, long long));
+/// assert_eq!(FEED, "_ZN3zoo3Pet4feedEOSt6threadx");
+/// ```
+#[macro_export]
+macro_rules! itanium_mangle {
+	($($path:ident)::+ ($($param:tt)*) const) => {
+		$crate::itanium_mangle_impl!(@mangle [$($path)::+] [$($param)*] true)
+	};
+
+	($($path:ident)::+ ($($param:tt)*)) => {
+		$crate::itanium_mangle_impl!(@mangle [$($path)::+] [$($param)*] false)
+	};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! itanium_mangle_impl {
+	(@mangle [$($path:ident)::+] [$($param:tt)*] $is_const:literal) => {{
+		const FN: $crate::ItaniumFn = $crate::itanium_mangle_impl!(@fn [$($path)::+] [$($param)*] $is_const);
+		const LEN: usize = FN.mangled_len();
+		const MANGLED: [u8; LEN] = FN.mangle();
+		const NAME: &str = match ::core::str::from_utf8(&MANGLED) {
+			Ok(name) => name,
+			Err(_) => ::core::panic!("mangled name is not UTF-8"),
+		};
+		NAME
+	}};
+
+	(@fn [$($path:ident)::+] [$($param:tt)*] $is_const:literal) => {
+		$crate::ItaniumFn {
+			path: &[$(::core::stringify!($path)),+],
+			params: &$crate::itanium_mangle_impl!(@params [] [] $($param)*),
+			is_const: $is_const,
+		}
+	};
+
+	// Checks that `$name` is the mangled name of the function.
+	(@check $name:literal, $($path:ident)::+ ($($param:tt)*) const) => {
+		$crate::itanium_mangle_impl!(@check $name, [$($path)::+] [$($param)*] true);
+	};
+	(@check $name:literal, $($path:ident)::+ ($($param:tt)*)) => {
+		$crate::itanium_mangle_impl!(@check $name, [$($path)::+] [$($param)*] false);
+	};
+	(@check $name:literal, [$($path:ident)::+] [$($param:tt)*] $is_const:literal) => {
+		const _: () = {
+			const FN: $crate::ItaniumFn = $crate::itanium_mangle_impl!(@fn [$($path)::+] [$($param)*] $is_const);
+			if !FN.is_mangled_name($name) {
+				::core::panic!("{}", $crate::itanium_mangle_impl!(@mangle [$($path)::+] [$($param)*] $is_const));
+			}
+		};
+	};
+
+	// Splits the parameters at commas.
+	(@params [] [] void) => { [] };
+	(@params [$($done:tt)*] []) => { [$($done),*] };
+	(@params [$($done:tt)*] [$($cur:tt)+]) => {
+		[$($done,)* $crate::itanium_mangle_impl!(@type [] $($cur)+)]
+	};
+	(@params [$($done:tt)*] [$($cur:tt)+] , $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(
+			@params [$($done)* ($crate::itanium_mangle_impl!(@type [] $($cur)+))] [] $($rest)*
+		)
+	};
+	(@params $done:tt [$($cur:tt)*] $tok:tt $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@params $done [$($cur)* $tok] $($rest)*)
+	};
+
+	// Collects leading qualifiers, which apply to the type that follows them.
+	(@type [$($lead:tt)*] const $($rest:tt)+) => {
+		$crate::itanium_mangle_impl!(@type [$($lead)* const] $($rest)+)
+	};
+	(@type [$($lead:tt)*] volatile $($rest:tt)+) => {
+		$crate::itanium_mangle_impl!(@type [$($lead)* volatile] $($rest)+)
+	};
+
+	// Builtin types, longest first.
+	(@type $lead:tt unsigned long long int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "y" $lead $($rest)*) };
+	(@type $lead:tt unsigned long long $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "y" $lead $($rest)*) };
+	(@type $lead:tt unsigned long int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "m" $lead $($rest)*) };
+	(@type $lead:tt unsigned long $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "m" $lead $($rest)*) };
+	(@type $lead:tt unsigned short int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "t" $lead $($rest)*) };
+	(@type $lead:tt unsigned short $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "t" $lead $($rest)*) };
+	(@type $lead:tt unsigned char $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "h" $lead $($rest)*) };
+	(@type $lead:tt unsigned int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "j" $lead $($rest)*) };
+	(@type $lead:tt unsigned $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "j" $lead $($rest)*) };
+	(@type $lead:tt signed char $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "a" $lead $($rest)*) };
+	(@type $lead:tt signed int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "i" $lead $($rest)*) };
+	(@type $lead:tt signed $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "i" $lead $($rest)*) };
+	(@type $lead:tt long long int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "x" $lead $($rest)*) };
+	(@type $lead:tt long long $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "x" $lead $($rest)*) };
+	(@type $lead:tt long double $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "e" $lead $($rest)*) };
+	(@type $lead:tt long int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "l" $lead $($rest)*) };
+	(@type $lead:tt long $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "l" $lead $($rest)*) };
+	(@type $lead:tt short int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "s" $lead $($rest)*) };
+	(@type $lead:tt short $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "s" $lead $($rest)*) };
+	(@type $lead:tt int $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "i" $lead $($rest)*) };
+	(@type $lead:tt char $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "c" $lead $($rest)*) };
+	(@type $lead:tt bool $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "b" $lead $($rest)*) };
+	(@type $lead:tt void $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "v" $lead $($rest)*) };
+	(@type $lead:tt float $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "f" $lead $($rest)*) };
+	(@type $lead:tt double $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "d" $lead $($rest)*) };
+	(@type $lead:tt wchar_t $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "w" $lead $($rest)*) };
+	(@type $lead:tt char8_t $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "Du" $lead $($rest)*) };
+	(@type $lead:tt char16_t $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "Ds" $lead $($rest)*) };
+	(@type $lead:tt char32_t $($rest:tt)*) => { $crate::itanium_mangle_impl!(@builtin "Di" $lead $($rest)*) };
+
+	// Classes, unions and enums.
+	(@type $lead:tt $seg:ident $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@class $lead [$seg] $($rest)*)
+	};
+
+	(@type $lead:tt $($rest:tt)*) => {
+		::core::compile_error!(::core::concat!("unsupported C++ type: `", ::core::stringify!($($rest)*), "`"))
+	};
+
+	(@class $lead:tt [$($seg:ident)*] :: $next:ident $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@class $lead [$($seg)* $next] $($rest)*)
+	};
+	(@class [$($lead:tt)*] [$($seg:ident)*] $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(
+			@post [$crate::CppType::Class(&[$(::core::stringify!($seg)),*])] $($lead)* $($rest)*
+		)
+	};
+
+	(@builtin $code:literal [$($lead:tt)*] $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::Builtin($code)] $($lead)* $($rest)*)
+	};
+
+	// Applies qualifiers, pointers and references from left to right.
+	(@post [$($ty:tt)*]) => { $($ty)* };
+	(@post [$($ty:tt)*] const $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::Const(&$($ty)*)] $($rest)*)
+	};
+	(@post [$($ty:tt)*] volatile $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::Volatile(&$($ty)*)] $($rest)*)
+	};
+	(@post [$($ty:tt)*] * $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::Pointer(&$($ty)*)] $($rest)*)
+	};
+	(@post [$($ty:tt)*] && $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::RvalueReference(&$($ty)*)] $($rest)*)
+	};
+	(@post [$($ty:tt)*] & $($rest:tt)*) => {
+		$crate::itanium_mangle_impl!(@post [$crate::CppType::Reference(&$($ty)*)] $($rest)*)
+	};
+	(@post $ty:tt $($rest:tt)*) => {
+		::core::compile_error!(::core::concat!("unexpected tokens in a C++ type: `", ::core::stringify!($($rest)*), "`"))
+	};
+}
@@ -1,7 +1,19 @@
 mod cc;
+mod bridge;
+mod closure;
+mod com;
+mod create_interface;
+mod extern_vtable;
+mod itanium_rtti;
+mod mangle;
+mod msvc_rtti;
+mod pin_init;
+mod plugin;
 mod virtual_call;
 mod virtual_fn;
 mod vtable;
+#[cfg(feature = "vst3")]
+mod vst3;
 
 /// Given `$type` is a VTable type and `Self` has all of the virtual methods for
 /// that VTable with the same name, create a new VTable with those methods.
@@ -0,0 +1,193 @@
+/// Declares statics that are the MSVC RTTI of classes implemented in Rust,
+/// for use with [`msvc_vtable!`](crate::msvc_vtable!).
+/// 
+/// Each static is written as `static NAME = "decorated name" { bases };`,
+/// where the decorated name is that of the type descriptor, such as `.?AVDuck@@`.
+/// The optional bases are separated by commas and written the same way,
+/// followed by `+ offset` if they are not at offset 0 of the complete class.
+/// 
+/// The statics are [`StaticMsvcRtti`](crate::StaticMsvcRtti)s,
+/// which are written when the image is loaded.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{MsvcRttiForm, msvc_rtti};
+/// msvc_rtti! {
+/// 	static DUCK_RTTI = ".?AVDuck@@" {
+/// 		".?AVAnimal@@" {
+/// 			".?AVObject@@",
+/// 		},
+/// 		".?AVSwimmer@@" + 16,
+/// 	};
+/// }
+/// 
+/// let hierarchy = DUCK_RTTI.rtti().hierarchy;
+/// assert_eq!(hierarchy.len(), 4);
+/// assert_eq!((hierarchy[0].name, hierarchy[0].contained_bases), (".?AVDuck@@", 3));
+/// assert_eq!((hierarchy[1].name, hierarchy[1].contained_bases), (".?AVAnimal@@", 1));
+/// assert_eq!((hierarchy[3].name, hierarchy[3].mdisp), (".?AVSwimmer@@", 16));
+/// 
+/// // The RTTI has been written when the image was loaded.
+/// let offset = DUCK_RTTI.rtti().locator_offset(MsvcRttiForm::NATIVE, 16).unwrap();
+/// assert_eq!(DUCK_RTTI.bytes()[offset + 4], 16);
+/// ```
+#[macro_export]
+macro_rules! msvc_rtti {
+	{} => {};
+
+	{
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident = $class:literal $({$($bases:tt)*})?;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::StaticMsvcRtti<{
+			$crate::msvc_rtti!(@rtti $class $({$($bases)*})?).size($crate::MsvcRttiForm::NATIVE)
+		}> = $crate::StaticMsvcRtti::new($crate::msvc_rtti!(@rtti $class $({$($bases)*})?));
+
+		const _: () = {
+			unsafe extern "C" fn init() {
+				// SAFETY: Nothing reads the RTTI before the image is loaded.
+				unsafe { $name.init() }
+			}
+
+			#[used]
+			#[cfg_attr(windows, unsafe(link_section = ".CRT$XCU"))]
+			#[cfg_attr(target_vendor = "apple", unsafe(link_section = "__DATA,__mod_init_func"))]
+			#[cfg_attr(not(any(windows, target_vendor = "apple")), unsafe(link_section = ".init_array"))]
+			static INIT: unsafe extern "C" fn() = init;
+		};
+
+		$crate::msvc_rtti! {$($rest)*}
+	};
+
+	{@rtti $class:literal $({$($bases:tt)*})?} => {
+		$crate::MsvcRtti {
+			hierarchy: &$crate::msvc_rtti!(@flatten [] $class $({$($bases)*})?),
+		}
+	};
+
+	// Lists the classes in depth-first order.
+	{@flatten [$($done:tt)*]} => {
+		[$($done)*]
+	};
+	{@flatten $done:tt , $($rest:tt)*} => {
+		$crate::msvc_rtti!(@flatten $done $($rest)*)
+	};
+	{@flatten $done:tt $class:literal + $offset:tt {$($bases:tt)*} $($rest:tt)*} => {
+		$crate::msvc_rtti!(@flatten_class $done [$class $offset] {$($bases)*} $($rest)*)
+	};
+	{@flatten $done:tt $class:literal + $offset:tt $($rest:tt)*} => {
+		$crate::msvc_rtti!(@flatten_class $done [$class $offset] {} $($rest)*)
+	};
+	{@flatten $done:tt $class:literal {$($bases:tt)*} $($rest:tt)*} => {
+		$crate::msvc_rtti!(@flatten_class $done [$class] {$($bases)*} $($rest)*)
+	};
+	{@flatten $done:tt $class:literal $($rest:tt)*} => {
+		$crate::msvc_rtti!(@flatten_class $done [$class] {} $($rest)*)
+	};
+	{@flatten_class [$($done:tt)*] [$class:literal $($offset:tt)?] {$($bases:tt)*} $($rest:tt)*} => {
+		$crate::msvc_rtti!(
+			@flatten [
+				$($done)*
+				$crate::MsvcBaseClass {
+					name: $class,
+					contained_bases: $crate::msvc_rtti!(@count $($bases)*),
+					mdisp: $crate::msvc_rtti!(@offset $($offset)?),
+				},
+			]
+			$($bases)* , $($rest)*
+		)
+	};
+
+	// Counts the classes, including nested ones.
+	{@count} => { 0 };
+	{@count , $($rest:tt)*} => {
+		$crate::msvc_rtti!(@count $($rest)*)
+	};
+	{@count $class:literal + $offset:tt $($rest:tt)*} => {
+		$crate::msvc_rtti!(@count $class $($rest)*)
+	};
+	{@count $class:literal {$($bases:tt)*} $($rest:tt)*} => {
+		1 + $crate::msvc_rtti!(@count $($bases)*) + $crate::msvc_rtti!(@count $($rest)*)
+	};
+	{@count $class:literal $($rest:tt)*} => {
+		1 + $crate::msvc_rtti!(@count $($rest)*)
+	};
+
+	{@offset} => { 0 };
+	{@offset $offset:tt} => { $offset };
+}
+
+/// Declares statics of type [`VTablePtr`](crate::VTablePtr) that point to the address points
+/// of VTables with the prefix used by MSVC,
+/// so that C++ can use `dynamic_cast` and `typeid` on objects implemented in Rust.
+/// 
+/// Each static is preceded by `#[rtti(RTTI)]`,
+/// where `RTTI` is a static declared by [`msvc_rtti!`](crate::msvc_rtti!).
+/// The VTables of bases that are not at offset 0 of the class
+/// are preceded by `#[rtti(RTTI, offset = offset)]` instead.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{MsvcVTable, VtObjectPtr, msvc_rtti, msvc_vtable, vtable, virtual_fn};
+/// vtable! {
+/// 	AnimalVt {
+/// 		pub fn legs() -> u32;
+/// 	}
+/// }
+/// vtable! {
+/// 	SwimmerVt {
+/// 		pub fn swim();
+/// 	}
+/// }
+/// 
+/// virtual_fn! {
+/// 	fn duck_legs(this: VtObjectPtr<AnimalVt>) -> u32 {
+/// 		2
+/// 	}
+/// }
+/// virtual_fn! {
+/// 	fn duck_swim(this: VtObjectPtr<SwimmerVt>) {}
+/// }
+/// 
+/// msvc_rtti! {
+/// 	static DUCK_RTTI = ".?AVDuck@@" {
+/// 		".?AVAnimal@@",
+/// 		".?AVSwimmer@@" + 8,
+/// 	};
+/// }
+/// 
+/// // `Duck` derives from `Animal`, and from `Swimmer` at offset 8.
+/// msvc_vtable! {
+/// 	#[rtti(DUCK_RTTI)]
+/// 	static DUCK_VT: AnimalVt = AnimalVt { legs: duck_legs };
+/// 	#[rtti(DUCK_RTTI, offset = 8)]
+/// 	static DUCK_SWIMMER_VT: SwimmerVt = SwimmerVt { swim: duck_swim };
+/// }
+/// 
+/// // The Complete Object Locator is one pointer before the address point.
+/// let locator = unsafe { DUCK_SWIMMER_VT.as_ptr().cast::<*const u32>().sub(1).read() };
+/// assert_eq!(unsafe { locator.add(1).read() }, 8);
+/// ```
+#[macro_export]
+macro_rules! msvc_vtable {
+	{} => {};
+
+	{
+		#[rtti($rtti:path $(, offset = $offset:expr)?)]
+		$(#[$attr:meta])*
+		$vis:vis static $name:ident: $VTable:ty = $vtable:expr;
+		$($rest:tt)*
+	} => {
+		$(#[$attr])*
+		$vis static $name: $crate::VTablePtr<$VTable> = {
+			static VTABLE: $crate::MsvcVTable<$VTable> = $crate::MsvcVTable {
+				locator: $rtti.locator($crate::msvc_rtti!(@offset $(($offset))?)),
+				vtable: $vtable,
+			};
+			VTABLE.address_point()
+		};
+		$crate::msvc_vtable! {$($rest)*}
+	};
+}
@@ -0,0 +1,26 @@
+/// Initializes a `T` on the stack from an [`Init`](crate::Init) and pins it,
+/// binding a [`Pin<&mut T>`](core::pin::Pin) to the given name.
+/// 
+/// The `T` is dropped at the end of the enclosing scope.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{init_value, pin_init};
+/// pin_init!(let value = init_value(String::from("pinned")));
+/// assert_eq!(value.as_str(), "pinned");
+/// ```
+#[macro_export]
+macro_rules! pin_init {
+	(let mut $name:ident = $init:expr $(;)?) => {
+		let mut slot = ::core::mem::MaybeUninit::uninit();
+		// SAFETY: `slot` cannot be named outside of this macro, so it cannot be leaked.
+		let mut slot = unsafe { $crate::StackSlot::new(&mut slot, $init) };
+		let mut $name = slot.as_pin_mut();
+	};
+	(let $name:ident = $init:expr $(;)?) => {
+		let mut slot = ::core::mem::MaybeUninit::uninit();
+		// SAFETY: `slot` cannot be named outside of this macro, so it cannot be leaked.
+		let mut slot = unsafe { $crate::StackSlot::new(&mut slot, $init) };
+		let $name = slot.as_pin_mut();
+	};
+}
@@ -0,0 +1,48 @@
+/// Defines the entry point of a plugin, [`PLUGIN_ENTRY_SYMBOL`](crate::PLUGIN_ENTRY_SYMBOL),
+/// which returns a [`PluginManifest`](crate::PluginManifest) of its interfaces.
+/// 
+/// Each interface is written as `VTable => object`, where `VTable` implements
+/// [`PluginInterface`](crate::PluginInterface) and `object` is a `&'static` reference
+/// to a [`Sync`] type that implements [`VtImpl<VTable>`](crate::VtImpl).
+/// 
+/// See [`Plugin`](crate::Plugin) for an example.
+#[macro_export]
+macro_rules! export_plugin {
+	{
+		$($VTable:ty => $object:expr),* $(,)?
+	} => {
+		/// Return the manifest of the plugin.
+		#[unsafe(no_mangle)]
+		pub extern "C" fn cppdvt_plugin_entry() -> *const $crate::PluginManifest {
+			static EXPORTS: &[$crate::PluginExport] = &[$(
+				$crate::PluginExport::new::<$VTable, _>($object),
+			)*];
+			static MANIFEST: $crate::PluginManifest = $crate::PluginManifest::new(EXPORTS);
+			&MANIFEST
+		}
+	};
+}
+
+/// Given an invokation of the form `plugin_object => field1.field2.func(...)`,
+/// invoke the virtual method `func` of the [`PluginObject`](crate::PluginObject) `plugin_object`
+/// like [`virtual_call!`](crate::virtual_call!) does, returning its result in [`Some`],
+/// or return [`None`] if the plugin exports an older version of the interface without the method.
+/// 
+/// See [`Plugin`](crate::Plugin) for an example.
+#[macro_export]
+macro_rules! plugin_call {
+	($plugin_object:expr => $field:ident$(.$suffix:ident)*($($arg:tt)*)) => {{
+		let plugin_object = &$plugin_object;
+		let offset = $crate::PluginObject::slot_offset(plugin_object, |vtable| {
+			::core::ptr::addr_of!((*vtable).$field$(.$suffix)*)
+		});
+		if $crate::PluginObject::has_slot(plugin_object, offset) {
+			let this = $crate::PluginObject::as_ptr(plugin_object);
+			let vtable = this.as_ref().as_ptr().as_ptr();
+			let func = ::core::ptr::addr_of!((*vtable).$field$(.$suffix)*).read();
+			::core::option::Option::Some(func(this, $($arg)*))
+		} else {
+			::core::option::Option::None
+		}
+	}};
+}
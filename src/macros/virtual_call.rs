@@ -2,8 +2,84 @@
 /// invoke the virtual method `func` while traversing `field`s
 /// of the [`VtObject`](crate::VtObject) `vt_object`'s VTable
 /// with the specified arguments, if any.
+/// 
+/// # Devirtualization
+/// Given an invokation of the form `<Type> vt_object => field1.field2.func(...)`,
+/// where `Type` implements [`VtImpl`](crate::VtImpl),
+/// the method in [`VtImpl::VTABLE`](crate::VtImpl::VTABLE) of `Type` is called directly
+/// if `vt_object` is an instance of `Type`, which allows it to be inlined,
+/// since the VTable is a constant.
+/// Otherwise, the virtual method is invoked as usual.
+/// 
+/// ```
+/// # use cppdvt::{VTablePtr, VtImpl, VtObject, VtObjectPtr, vtable, virtual_fn, virtual_call};
+/// vtable! {
+/// 	ShapeVt {
+/// 		pub fn area() -> f32;
+/// 	}
+/// }
+/// 
+/// #[repr(C)]
+/// struct Square {
+/// 	vtable: VTablePtr<ShapeVt>,
+/// 	side: f32,
+/// }
+/// 
+/// impl Square {
+/// 	virtual_fn! {
+/// 		fn side_squared(this: VtObjectPtr<ShapeVt>) -> f32 {
+/// 			let this = this.cast::<Self>().as_ref();
+/// 			this.side * this.side
+/// 		}
+/// 	}
+/// 
+/// 	// Not called by `virtual_call!`, which uses the function in the VTable.
+/// 	fn area() -> f32 {
+/// 		0.0
+/// 	}
+/// }
+/// 
+/// unsafe impl VtImpl<ShapeVt> for Square {
+/// 	const VTABLE: &'static ShapeVt = &ShapeVt { area: Self::side_squared };
+/// }
+/// 
+/// let square = Square {
+/// 	vtable: VTablePtr::from_ref(<Square as VtImpl<ShapeVt>>::VTABLE),
+/// 	side: 2.0,
+/// };
+/// let shape = unsafe { VtObject::from_ptr(VtObjectPtr::from_ref(&square.vtable)) };
+/// assert_eq!(unsafe { virtual_call!(<Square> shape => area()) }, 4.0);
+/// ```
 #[macro_export]
 macro_rules! virtual_call {
+	(@impl_vtable $Type:ty; $vt_object:expr) => {{
+		const fn impl_vtable<VTable: 'static, T: $crate::VtImpl<VTable>>(
+			_: &$crate::VtObject<VTable>,
+		) -> &'static VTable {
+			T::VTABLE
+		}
+		impl_vtable::<_, $Type>($vt_object)
+	}};
+
+	(mut <$Type:ty> $vt_object:expr => $field:ident$(.$suffix:ident)*($($arg:tt)*)) => {{
+		let vt_object = &mut $vt_object;
+		let this = $crate::VtObject::as_mut_ptr(vt_object);
+		if $crate::VtObject::is::<$Type>(vt_object) {
+			($crate::virtual_call!(@impl_vtable $Type; vt_object).$field$(.$suffix)*)(this, $($arg)*)
+		} else {
+			($crate::VtObject::vtable(vt_object).$field$(.$suffix)*)(this, $($arg)*)
+		}
+	}};
+	(<$Type:ty> $vt_object:expr => $field:ident$(.$suffix:ident)*($($arg:tt)*)) => {{
+		let vt_object = &$vt_object;
+		let this = $crate::VtObject::as_ptr(vt_object);
+		if $crate::VtObject::is::<$Type>(vt_object) {
+			($crate::virtual_call!(@impl_vtable $Type; vt_object).$field$(.$suffix)*)(this, $($arg)*)
+		} else {
+			($crate::VtObject::vtable(vt_object).$field$(.$suffix)*)(this, $($arg)*)
+		}
+	}};
+
 	(mut $vt_object:expr => $field:ident$(.$suffix:ident)*($($arg:tt)*)) => {{
 		let vt_object = &mut $vt_object;
 		let this = $crate::VtObject::as_mut_ptr(vt_object);
@@ -17,7 +93,7 @@ macro_rules! virtual_call {
 
 	($($whatever:tt)*) => {
 		::core::compile_error! {
-			"expected invocation of the form `(mut)? <VtObject> => path.to.func(...)`"
+			"expected invocation of the form `(mut)? (<Type>)? <VtObject> => path.to.func(...)`"
 		}
 	};
 }
@@ -2,6 +2,14 @@
 /// that represents a C++ virtual method
 /// with the correct calling convention selected for the target.
 /// 
+/// An item can start with `#[itanium_export("mangled name", signature)]` to export it
+/// under the Itanium C++ ABI name of a method, so that C++ code declaring the method
+/// links to the Rust implementation.
+/// The mangled name must still be written by hand, because `export_name` only accepts a literal;
+/// the macro does not generate it. Instead, the signature, written as for
+/// [`itanium_mangle!`](crate::itanium_mangle!), is mangled at compile time
+/// and compared with the name, and when they differ, the error shows the expected name.
+/// 
 /// See also [`unwind_virtual_fn!`](crate::unwind_virtual_fn!).
 /// 
 /// # Examples
@@ -21,9 +29,28 @@
 /// let _: NextI32Fn = next_i32;
 /// // Types may or may not match on different targets!
 /// // let _: unsafe extern "thiscall" fn(VtObjectPtr<RngVt>) = next_i32;
+/// 
+/// // Implement `int rng::Rng::next(unsigned int) const` for C++ code.
+/// virtual_fn! {
+/// 	#[itanium_export("_ZNK3rng3Rng4nextEj", rng::Rng::next(unsigned int) const)]
+/// 	fn rng_next(this: VtObjectPtr<RngVt>, bound: u32) -> i32 {
+/// 		(193691999 % bound) as i32
+/// 	}
+/// }
 /// ```
 #[macro_export]
 macro_rules! virtual_fn {
+	{
+		#[itanium_export($name:literal, $($sig:tt)*)]
+		$($rest:tt)*
+	} => {
+		$crate::itanium_mangle_impl!(@check $name, $($sig)*);
+		$crate::virtual_fn! {
+			#[unsafe(export_name = $name)]
+			$($rest)*
+		}
+	};
+
 	{
 		$(#[$attr:meta])*
 		fn $($name:ident)?
@@ -60,6 +87,8 @@ macro_rules! virtual_fn {
 /// that represents a C++ virtual method
 /// with the correct `*-unwind` calling convention selected for the target.
 /// 
+/// Like [`virtual_fn!`](crate::virtual_fn!), items can start with `#[itanium_export(...)]`.
+/// 
 /// See also [`virtual_fn!`](crate::virtual_fn!).
 /// 
 /// # Examples
@@ -79,6 +108,17 @@ macro_rules! virtual_fn {
 /// ```
 #[macro_export]
 macro_rules! unwind_virtual_fn {
+	{
+		#[itanium_export($name:literal, $($sig:tt)*)]
+		$($rest:tt)*
+	} => {
+		$crate::itanium_mangle_impl!(@check $name, $($sig)*);
+		$crate::unwind_virtual_fn! {
+			#[unsafe(export_name = $name)]
+			$($rest)*
+		}
+	};
+
 	{
 		$(#[$attr:meta])*
 		fn $($name:ident)?
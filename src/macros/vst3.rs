@@ -0,0 +1,170 @@
+/// Defines the entry points of a VST3 plugin library:
+/// `GetPluginFactory`, which returns a [`plugin_factory`](crate::plugin_factory)
+/// for a [`PluginFactoryInfo`](crate::PluginFactoryInfo),
+/// and the module entry and exit functions of the platform,
+/// which are `ModuleEntry`/`ModuleExit` on Linux,
+/// `InitDll`/`ExitDll` on Windows and `bundleEntry`/`bundleExit` on Apple platforms.
+/// 
+/// Classes of the plugin implement VST3 interfaces with [`com_object!`](crate::com_object!),
+/// and set `#[no_interface = TResult::NO_INTERFACE.as_hresult()]`.
+/// 
+/// # Examples
+/// ```
+/// # use core::ptr::NonNull;
+/// # use cppdvt::{
+/// # 	ComObject, ComPtr, GUID, IPluginFactoryVt, IPluginFactory3Vt, IUnknownVt, Interface,
+/// # 	PluginClass, PluginFactoryInfo, TResult, VtObjectPtr, com_fn, com_object, com_vtable, virtual_call, vst3_plugin,
+/// # };
+/// com_vtable! {
+/// 	IGainVtExt for VtObjectPtr<IGainVt> {
+/// 		pub fn gain() -> f32;
+/// 	}
+/// }
+/// #[repr(C)]
+/// struct IGainVt {
+/// 	base: IUnknownVt,
+/// 	gain: IGainVtExt,
+/// }
+/// unsafe impl Interface for IGainVt {
+/// 	const IID: GUID = GUID::from_vst3_uid(0x3E1F7A20, 0x5B6C4D8E, 0x9F0A1B2C, 0x3D4E5F60);
+/// }
+/// 
+/// struct Gain;
+/// impl Gain {
+/// 	com_fn! {
+/// 		fn gain(this: VtObjectPtr<IGainVt>) -> f32 {
+/// 			0.5
+/// 		}
+/// 	}
+/// }
+/// com_object! {
+/// 	#[no_interface = TResult::NO_INTERFACE.as_hresult()]
+/// 	unsafe Gain {
+/// 		IGainVt => IGainVt {
+/// 			base: UNKNOWN,
+/// 			gain: IGainVtExt { gain: Gain::gain },
+/// 		},
+/// 	}
+/// }
+/// 
+/// const GAIN_CID: GUID = GUID::from_vst3_uid(0x3E1F7A20, 0x5B6C4D8E, 0x9F0A1B2C, 0x3D4E5F61);
+/// 
+/// vst3_plugin! {
+/// 	vendor: "Example",
+/// 	url: "https://example.com",
+/// 	email: "audio@example.com",
+/// 	classes: [
+/// 		PluginClass {
+/// 			sub_categories: "Fx",
+/// 			version: "1.0.0",
+/// 			..PluginClass::new(GAIN_CID, "Audio Module Class", "Gain", || ComObject::create(Gain))
+/// 		},
+/// 	],
+/// }
+/// 
+/// // A stand-in host, which would look up `GetPluginFactory` in the library instead.
+/// let factory = GetPluginFactory().unwrap();
+/// let factory = unsafe { ComPtr::<IPluginFactoryVt>::from_raw(factory.cast()) };
+/// assert_eq!(factory.factory_info().unwrap().vendor(), c"Example");
+/// assert_eq!(factory.class_count(), 1);
+/// assert_eq!(factory.class_info(0).unwrap().name(), c"Gain");
+/// 
+/// let factory3 = factory.query_interface::<IPluginFactory3Vt>().unwrap();
+/// let info = factory3.class_info_unicode(0).unwrap();
+/// assert_eq!(String::from_utf16(info.name()).unwrap(), "Gain");
+/// 
+/// let gain = factory.create_instance::<IGainVt>(&GAIN_CID).unwrap();
+/// assert_eq!(unsafe { virtual_call!(gain => gain.gain()) }, 0.5);
+/// let missing = gain.query_interface::<IPluginFactoryVt>().unwrap_err();
+/// assert_eq!(TResult::from(missing), TResult::NO_INTERFACE);
+/// 
+/// // Hosts pass `TUID`s, which are byte arrays that may be unaligned.
+/// #[repr(C, align(4))]
+/// struct Tuids([u8; 33]);
+/// let mut tuids = Tuids([0; 33]);
+/// tuids.0[1..17].copy_from_slice(&GAIN_CID.to_bytes());
+/// tuids.0[17..].copy_from_slice(&IGainVt::IID.to_bytes());
+/// let (cid, iid) = (tuids.0[1..].as_ptr().cast(), tuids.0[17..].as_ptr().cast());
+/// let mut object = core::ptr::null_mut();
+/// let result = unsafe { (factory.vtable().factory.create_instance)(ComPtr::as_raw(&factory), cid, iid, &mut object) };
+/// assert_eq!(result, TResult::OK);
+/// let gain = unsafe { ComPtr::<IGainVt>::from_raw(NonNull::new(object.cast()).unwrap()) };
+/// assert_eq!(unsafe { virtual_call!(gain => gain.gain()) }, 0.5);
+/// ```
+#[macro_export]
+macro_rules! vst3_plugin {
+	{
+		vendor: $vendor:expr,
+		url: $url:expr,
+		email: $email:expr,
+		classes: [$($class:expr),* $(,)?] $(,)?
+	} => {
+		/// Return a new reference to the `IPluginFactory` of the library.
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "system" fn GetPluginFactory() -> ::core::option::Option<
+			$crate::VtObjectPtr<$crate::IPluginFactoryVt>,
+		> {
+			static INFO: $crate::PluginFactoryInfo = $crate::PluginFactoryInfo {
+				vendor: $vendor,
+				url: $url,
+				email: $email,
+				classes: &[$($class),*],
+			};
+			let factory = $crate::plugin_factory(&INFO);
+			::core::option::Option::Some($crate::ComPtr::into_raw(factory).cast())
+		}
+
+		$crate::vst3_plugin!(@module);
+	};
+
+	{@module} => {
+		/// Initialize the library, returning `true` on success.
+		#[cfg(all(not(windows), not(target_vendor = "apple")))]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "C" fn ModuleEntry(_library: *mut ::core::ffi::c_void) -> bool {
+			true
+		}
+
+		/// Deinitialize the library, returning `true` on success.
+		#[cfg(all(not(windows), not(target_vendor = "apple")))]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "C" fn ModuleExit() -> bool {
+			true
+		}
+
+		/// Initialize the library, returning `true` on success.
+		#[cfg(windows)]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "system" fn InitDll() -> bool {
+			true
+		}
+
+		/// Deinitialize the library, returning `true` on success.
+		#[cfg(windows)]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "system" fn ExitDll() -> bool {
+			true
+		}
+
+		/// Initialize the bundle, returning `true` on success.
+		#[cfg(target_vendor = "apple")]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "C" fn bundleEntry(_bundle: *mut ::core::ffi::c_void) -> bool {
+			true
+		}
+
+		/// Deinitialize the bundle, returning `true` on success.
+		#[cfg(target_vendor = "apple")]
+		#[allow(non_snake_case)]
+		#[unsafe(no_mangle)]
+		pub extern "C" fn bundleExit() -> bool {
+			true
+		}
+	};
+}
@@ -261,6 +261,16 @@ macro_rules! vtable_impl {
 				),
 			)*
 		}
+
+		$crate::vtable_impl! {
+			@info
+			{$($generic)*}
+			$VTable
+			$(
+				{$(#[$fn_attr])*}
+				$fn_name ($($fn_param)*) ($($FnRet)?)
+			)*
+		}
 	};
 	{
 		@create
@@ -276,6 +286,56 @@ macro_rules! vtable_impl {
 			"only `fn` items are allowed in vtable bodies"
 		}
 	};
+
+	{
+		@info
+		{}
+		$VTable:ident
+		$(
+			{$($fn_attr:tt)*}
+			$fn_name:ident $params:tt ($($FnRet:ty)?)
+		)*
+	} => {
+		impl $crate::VTableInfo for $VTable {
+			const NAME: &'static str = ::core::stringify!($VTable);
+			#[allow(unused_doc_comments)]
+			const SLOTS: &'static [$crate::SlotInfo] = {
+				#[allow(dead_code)]
+				#[repr(C)]
+				struct Slots {
+					$(
+						$($fn_attr)*
+						$fn_name: u8,
+					)*
+				}
+
+				const SLOTS: [$crate::SlotInfo; ::core::mem::size_of::<Slots>()] = {
+					let mut slots = [$crate::SlotInfo::EMPTY; ::core::mem::size_of::<Slots>()];
+					let mut index = 0;
+					$(
+						$($fn_attr)*
+						let () = {
+							slots[index] = $crate::SlotInfo {
+								name: ::core::stringify!($fn_name),
+								params: ::core::stringify! $params,
+								ret: ::core::stringify!($($FnRet)?),
+							};
+							index += 1;
+						};
+					)*
+					let _ = index;
+					slots
+				};
+				&SLOTS
+			};
+		}
+	};
+	// Slot metadata is not generated for generic VTables.
+	{
+		@info
+		$generics:tt
+		$($whatever:tt)*
+	} => {};
 }
 
 /// Generates a VTable `struct` with a domain-specific language.
@@ -295,6 +355,11 @@ macro_rules! vtable_impl {
 /// 
 /// On `cfg(not(windows))`, there are *two* virtual destructors.
 /// 
+/// # Metadata
+/// Non-generic VTables implement [`VTableInfo`](crate::VTableInfo),
+/// which describes their slots as they are written,
+/// for example to [generate C++ headers](crate::write_cpp_class).
+/// 
 /// # Examples
 /// A simple VTable can be defined like this:
 /// ```
@@ -0,0 +1,331 @@
+/// A C++ type in the signature of an [`ItaniumFn`].
+/// 
+/// These are usually written with [`itanium_mangle!`](crate::itanium_mangle!).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CppType {
+	/// A builtin type, by its Itanium code, such as `i` for `int`.
+	Builtin(&'static str),
+	/// A class, union or enum, by its qualified name, such as `["zoo", "Pet"]`.
+	Class(&'static [&'static str]),
+	/// A pointer to a type.
+	Pointer(&'static CppType),
+	/// An lvalue reference to a type.
+	Reference(&'static CppType),
+	/// An rvalue reference to a type.
+	RvalueReference(&'static CppType),
+	/// A `const` type.
+	Const(&'static CppType),
+	/// A `volatile` type.
+	Volatile(&'static CppType),
+}
+
+/// A C++ function, which can be mangled according to the Itanium C++ ABI in `const` contexts.
+/// 
+/// These are usually written with [`itanium_mangle!`](crate::itanium_mangle!).
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{CppType, ItaniumFn};
+/// const PLAY: ItaniumFn = ItaniumFn {
+/// 	path: &["zoo", "Pet", "play"],
+/// 	params: &[CppType::Pointer(&CppType::Class(&["zoo", "Pet"])), CppType::Builtin("i")],
+/// 	is_const: false,
+/// };
+/// const LEN: usize = PLAY.mangled_len();
+/// const MANGLED: [u8; LEN] = PLAY.mangle();
+/// assert_eq!(&MANGLED, b"_ZN3zoo3Pet4playEPS0_i");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItaniumFn {
+	/// Qualified name of the function, such as `["zoo", "Pet", "speak"]`.
+	pub path: &'static [&'static str],
+	/// Types of the parameters, excluding `this`.
+	pub params: &'static [CppType],
+	/// Whether the function is a `const` member function.
+	pub is_const: bool,
+}
+
+/// Maximum number of substitution candidates in a mangled name.
+const MAX_SUBS: usize = 64;
+
+/// A component of a mangled name that later components can refer back to.
+#[derive(Clone, Copy)]
+enum Candidate {
+	/// A class or namespace, by its qualified name.
+	Path(&'static [&'static str]),
+	/// A type other than a class.
+	Type(&'static CppType),
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+	if a.len() != b.len() {
+		return false;
+	}
+	let mut index = 0;
+	while index < a.len() {
+		if a[index] != b[index] {
+			return false;
+		}
+		index += 1;
+	}
+	true
+}
+
+const fn path_eq(a: &[&str], b: &[&str]) -> bool {
+	if a.len() != b.len() {
+		return false;
+	}
+	let mut index = 0;
+	while index < a.len() {
+		if !str_eq(a[index], b[index]) {
+			return false;
+		}
+		index += 1;
+	}
+	true
+}
+
+/// Split a type into its `const` and `volatile` qualifiers, as bits, and the unqualified type.
+const fn unqualify(mut ty: &'static CppType) -> (u8, &'static CppType) {
+	let mut quals = 0;
+	loop {
+		ty = match ty {
+			CppType::Const(inner) => {
+				quals |= 1;
+				inner
+			}
+			CppType::Volatile(inner) => {
+				quals |= 2;
+				inner
+			}
+			_ => return (quals, ty),
+		};
+	}
+}
+
+const fn type_eq(a: &'static CppType, b: &'static CppType) -> bool {
+	let (a_quals, a) = unqualify(a);
+	let (b_quals, b) = unqualify(b);
+	if a_quals != b_quals {
+		return false;
+	}
+	match (a, b) {
+		(CppType::Builtin(a), CppType::Builtin(b)) => str_eq(a, b),
+		(CppType::Class(a), CppType::Class(b)) => path_eq(a, b),
+		(CppType::Pointer(a), CppType::Pointer(b))
+		| (CppType::Reference(a), CppType::Reference(b))
+		| (CppType::RvalueReference(a), CppType::RvalueReference(b)) => type_eq(a, b),
+		_ => false,
+	}
+}
+
+/// Writes a mangled name to a buffer of `N` bytes, counting bytes past its end,
+/// and compares it to an expected name.
+struct Writer<'a, const N: usize> {
+	buf: [u8; N],
+	len: usize,
+	expected: &'a [u8],
+	matches: bool,
+	subs: [Candidate; MAX_SUBS],
+	sub_count: usize,
+}
+
+impl<'a, const N: usize> Writer<'a, N> {
+	const fn new(expected: &'a [u8]) -> Self {
+		Self {
+			buf: [0; N],
+			len: 0,
+			expected,
+			matches: true,
+			subs: [Candidate::Path(&[]); MAX_SUBS],
+			sub_count: 0,
+		}
+	}
+
+	const fn byte(&mut self, byte: u8) {
+		if self.len < N {
+			self.buf[self.len] = byte;
+		}
+		if self.len >= self.expected.len() || self.expected[self.len] != byte {
+			self.matches = false;
+		}
+		self.len += 1;
+	}
+
+	const fn str(&mut self, s: &str) {
+		let bytes = s.as_bytes();
+		let mut index = 0;
+		while index < bytes.len() {
+			self.byte(bytes[index]);
+			index += 1;
+		}
+	}
+
+	const fn number(&mut self, n: usize, radix: usize) {
+		if n >= radix {
+			self.number(n / radix, radix);
+		}
+		self.byte(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"[n % radix]);
+	}
+
+	/// `<source-name> ::= <length> <identifier>`
+	const fn source_name(&mut self, name: &str) {
+		assert!(!name.is_empty(), "empty name in a mangled name");
+		self.number(name.len(), 10);
+		self.str(name);
+	}
+
+	const fn push(&mut self, candidate: Candidate) {
+		assert!(self.sub_count < MAX_SUBS, "too many substitutions in a mangled name");
+		self.subs[self.sub_count] = candidate;
+		self.sub_count += 1;
+	}
+
+	/// Write a substitution for `candidate` if there is one.
+	const fn substitute(&mut self, candidate: Candidate) -> bool {
+		let mut index = 0;
+		while index < self.sub_count {
+			let equal = match (self.subs[index], candidate) {
+				(Candidate::Path(a), Candidate::Path(b)) => path_eq(a, b),
+				(Candidate::Type(a), Candidate::Type(b)) => type_eq(a, b),
+				_ => false,
+			};
+			if equal {
+				// `S_` is the first substitution, then `S0_`, `S1_` and so on in base 36.
+				self.byte(b'S');
+				if index > 0 {
+					self.number(index - 1, 36);
+				}
+				self.byte(b'_');
+				return true;
+			}
+			index += 1;
+		}
+		false
+	}
+
+	/// Write the qualified name `path`, making its prefixes substitution candidates,
+	/// and the whole name too if it is a class.
+	const fn name(&mut self, path: &'static [&'static str], is_class: bool, quals: &str) {
+		assert!(!path.is_empty(), "empty qualified name in a mangled name");
+		if is_class && self.substitute(Candidate::Path(path)) {
+			return;
+		}
+		let is_std = str_eq(path[0], "std");
+		if path.len() == 1 || (path.len() == 2 && is_std && quals.is_empty()) {
+			// `<unscoped-name>`
+			if is_std {
+				self.str("St");
+			}
+			self.source_name(path[path.len() - 1]);
+			if is_class {
+				self.push(Candidate::Path(path));
+			}
+			return;
+		}
+		self.byte(b'N');
+		self.str(quals);
+		// Start from the longest prefix that is a substitution.
+		let mut start = path.len() - 1;
+		while start > 0 && !self.substitute(Candidate::Path(path.split_at(start).0)) {
+			start -= 1;
+		}
+		if start == 0 && is_std {
+			self.str("St");
+			start = 1;
+		}
+		while start < path.len() {
+			self.source_name(path[start]);
+			start += 1;
+			if start < path.len() || is_class {
+				self.push(Candidate::Path(path.split_at(start).0));
+			}
+		}
+		self.byte(b'E');
+	}
+
+	const fn ty(&mut self, ty: &'static CppType) {
+		match ty {
+			CppType::Builtin(code) => self.str(code),
+			CppType::Class(path) => self.name(path, true, ""),
+			_ => {
+				if self.substitute(Candidate::Type(ty)) {
+					return;
+				}
+				match ty {
+					CppType::Pointer(inner) => {
+						self.byte(b'P');
+						self.ty(inner);
+					}
+					CppType::Reference(inner) => {
+						self.byte(b'R');
+						self.ty(inner);
+					}
+					CppType::RvalueReference(inner) => {
+						self.byte(b'O');
+						self.ty(inner);
+					}
+					_ => {
+						let (quals, inner) = unqualify(ty);
+						if quals & 2 != 0 {
+							self.byte(b'V');
+						}
+						if quals & 1 != 0 {
+							self.byte(b'K');
+						}
+						self.ty(inner);
+					}
+				}
+				self.push(Candidate::Type(ty));
+			}
+		}
+	}
+}
+
+impl ItaniumFn {
+	const fn write<'a, const N: usize>(&self, expected: &'a [u8]) -> Writer<'a, N> {
+		let mut writer = Writer::new(expected);
+		writer.str("_Z");
+		writer.name(self.path, false, if self.is_const { "K" } else { "" });
+		if self.params.is_empty() {
+			writer.byte(b'v');
+		}
+		let mut index = 0;
+		while index < self.params.len() {
+			writer.ty(&self.params[index]);
+			index += 1;
+		}
+		writer
+	}
+
+	/// Return the length of the mangled name.
+	pub const fn mangled_len(&self) -> usize {
+		self.write::<0>(&[]).len
+	}
+
+	/// Return the mangled name, such as `_ZNK3zoo3Pet4legsEv`.
+	/// 
+	/// # Panics
+	/// Panics if `N` is not the [mangled length](Self::mangled_len),
+	/// or if the name has an empty component.
+	pub const fn mangle<const N: usize>(&self) -> [u8; N] {
+		let writer = self.write::<N>(&[]);
+		assert!(writer.len == N, "wrong length for a mangled name");
+		writer.buf
+	}
+
+	/// Return `true` if `name` is the mangled name of the function.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::{CppType, ItaniumFn};
+	/// let legs = ItaniumFn { path: &["zoo", "Pet", "legs"], params: &[], is_const: true };
+	/// assert!(legs.is_mangled_name("_ZNK3zoo3Pet4legsEv"));
+	/// assert!(!legs.is_mangled_name("_ZN3zoo3Pet4legsEv"));
+	/// ```
+	pub const fn is_mangled_name(&self, name: &str) -> bool {
+		let writer = self.write::<0>(name.as_bytes());
+		writer.matches && writer.len == name.len()
+	}
+}
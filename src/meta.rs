@@ -0,0 +1,94 @@
+/// Trait for VTables that describe their slots,
+/// which is implemented by [`vtable!`](crate::vtable!) for non-generic VTables.
+/// 
+/// This is used by tooling such as [`write_cpp_class`](crate::write_cpp_class),
+/// so that the Rust definition of a VTable is the single source of truth.
+pub trait VTableInfo {
+	/// Name of the VTable type.
+	const NAME: &'static str;
+	/// Slots of the VTable, in order.
+	const SLOTS: &'static [SlotInfo];
+}
+
+/// Description of a slot of a VTable, as written in Rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotInfo {
+	/// Name of the slot.
+	pub name: &'static str,
+	/// Parameters of the slot, excluding `this`, such as `x: i32, y: i32`.
+	pub params: &'static str,
+	/// Return type of the slot, or an empty string if it returns nothing.
+	pub ret: &'static str,
+}
+
+impl SlotInfo {
+	/// Placeholder slot without a name, parameters or a return type.
+	pub const EMPTY: Self = Self {
+		name: "",
+		params: "",
+		ret: "",
+	};
+
+	/// Return an iterator over the parameters of the slot,
+	/// as pairs of their name and their type.
+	/// 
+	/// A variadic parameter is returned as `("...", "")`.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::SlotInfo;
+	/// let slot = SlotInfo {
+	/// 	name: "push",
+	/// 	params: "value: NonNull<Pair<u8, u16>>, count: usize",
+	/// 	ret: "",
+	/// };
+	/// let params: Vec<_> = slot.params().collect();
+	/// assert_eq!(params, [("value", "NonNull<Pair<u8, u16>>"), ("count", "usize")]);
+	/// ```
+	pub fn params(&self) -> SlotParams {
+		SlotParams { rest: self.params }
+	}
+
+	/// Return `true` if the slot has a variadic parameter.
+	pub fn is_variadic(&self) -> bool {
+		self.params().any(|(name, _)| name == "...")
+	}
+}
+
+/// Iterator over the parameters of a slot.
+/// 
+/// See [`SlotInfo::params`].
+#[derive(Debug, Clone)]
+pub struct SlotParams {
+	rest: &'static str,
+}
+
+impl Iterator for SlotParams {
+	type Item = (&'static str, &'static str);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut depth = 0usize;
+		let mut end = self.rest.len();
+		for (index, c) in self.rest.char_indices() {
+			match c {
+				'<' | '(' | '[' => depth += 1,
+				'>' if !self.rest[..index].ends_with('-') => depth = depth.saturating_sub(1),
+				')' | ']' => depth = depth.saturating_sub(1),
+				',' if depth == 0 => {
+					end = index;
+					break;
+				}
+				_ => {}
+			}
+		}
+		let param = self.rest[..end].trim();
+		self.rest = self.rest.get(end + 1..).unwrap_or("");
+		if param.is_empty() {
+			return None;
+		}
+		Some(match param.split_once(':') {
+			Some((name, ty)) => (name.trim(), ty.trim()),
+			None => (param, ""),
+		})
+	}
+}
@@ -0,0 +1,385 @@
+use ::core::{
+	cell::UnsafeCell,
+	ptr::NonNull,
+};
+
+use super::VTablePtr;
+
+/// How MSVC RTTI records refer to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MsvcRttiForm {
+	/// 32-bit absolute addresses, as on x86.
+	Absolute,
+	/// 32-bit offsets from the image base, as on 64-bit targets.
+	ImageRelative,
+}
+
+impl MsvcRttiForm {
+	/// The form used by MSVC on the target.
+	pub const NATIVE: Self = if cfg!(target_pointer_width = "64") { Self::ImageRelative } else { Self::Absolute };
+
+	/// Return the size of the pointers in type descriptors.
+	pub const fn pointer_size(self) -> usize {
+		match self {
+			Self::Absolute => 4,
+			Self::ImageRelative => 8,
+		}
+	}
+
+	/// Return the size of a Complete Object Locator,
+	/// which refers to itself in the image-relative form.
+	const fn locator_size(self) -> usize {
+		match self {
+			Self::Absolute => 20,
+			Self::ImageRelative => 24,
+		}
+	}
+}
+
+/// A class in an [`MsvcRtti`] hierarchy, which becomes an RTTI Base Class Descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MsvcBaseClass<'a> {
+	/// Decorated name of the class, such as `.?AVAnimal@@`.
+	pub name: &'a str,
+	/// Number of bases that the class itself has,
+	/// which follow it in [`MsvcRtti::hierarchy`].
+	pub contained_bases: u32,
+	/// Offset of the class in the complete class.
+	pub mdisp: i32,
+}
+
+/// Description of the RTTI of an MSVC class,
+/// which can be written as the Type Descriptors, Complete Object Locators,
+/// Class Hierarchy Descriptors, Base Class Array and Base Class Descriptors that MSVC emits
+/// for a class compiled with `/GR`.
+/// 
+/// There is a Complete Object Locator for every class in the hierarchy,
+/// for the VTable at the offset of that class.
+/// Only non-virtual bases are supported.
+/// 
+/// These are usually written with [`msvc_rtti!`](crate::msvc_rtti!).
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::{MsvcBaseClass, MsvcRtti, MsvcRttiForm};
+/// let rtti = MsvcRtti {
+/// 	hierarchy: &[
+/// 		MsvcBaseClass { name: ".?AVDuck@@", contained_bases: 2, mdisp: 0 },
+/// 		MsvcBaseClass { name: ".?AVAnimal@@", contained_bases: 0, mdisp: 0 },
+/// 		MsvcBaseClass { name: ".?AVSwimmer@@", contained_bases: 0, mdisp: 8 },
+/// 	],
+/// };
+/// 
+/// let form = MsvcRttiForm::ImageRelative;
+/// let mut bytes = vec![0; rtti.size(form)];
+/// rtti.write(form, &mut bytes, 0x1_4000_3000, 0x1_4000_0000, 0x1_4000_2000);
+/// let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+/// 
+/// // The locator for the VTable of `Swimmer`.
+/// let locator = rtti.locator_offset(form, 8).unwrap();
+/// assert_eq!(u32_at(locator), 1);
+/// assert_eq!(u32_at(locator + 4), 8);
+/// assert_eq!(u32_at(locator + 20) as usize, 0x3000 + locator);
+/// 
+/// // The type descriptor of `Duck`.
+/// let type_descriptor = u32_at(locator + 12) as usize - 0x3000;
+/// assert_eq!(&bytes[type_descriptor..type_descriptor + 8], 0x1_4000_2000u64.to_le_bytes());
+/// assert_eq!(&bytes[type_descriptor + 16..type_descriptor + 27], b".?AVDuck@@\0");
+/// 
+/// // The class hierarchy has multiple inheritance.
+/// let hierarchy = u32_at(locator + 16) as usize - 0x3000;
+/// assert_eq!([u32_at(hierarchy), u32_at(hierarchy + 4), u32_at(hierarchy + 8)], [0, 1, 3]);
+/// let swimmer = u32_at(u32_at(hierarchy + 12) as usize - 0x3000 + 8) as usize - 0x3000;
+/// assert_eq!(u32_at(swimmer + 8), 8);
+/// 
+/// // On x86, records refer to each other by absolute addresses.
+/// let form = MsvcRttiForm::Absolute;
+/// let mut bytes = vec![0; rtti.size(form)];
+/// rtti.write(form, &mut bytes, 0x1000_3000, 0x1000_0000, 0x1000_2000);
+/// let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+/// 
+/// // Locators have 20 bytes and the signature 0, since they do not refer to themselves.
+/// let locator = rtti.locator_offset(form, 8).unwrap();
+/// assert_eq!(locator - rtti.locator_offset(form, 0).unwrap(), 2 * 20);
+/// assert_eq!(u32_at(locator), 0);
+/// assert_eq!(u32_at(locator + 4), 8);
+/// 
+/// // Type descriptors start with 4-byte pointers.
+/// let type_descriptor = u32_at(locator + 12) as usize - 0x1000_3000;
+/// assert_eq!([u32_at(type_descriptor), u32_at(type_descriptor + 4)], [0x1000_2000, 0]);
+/// assert_eq!(&bytes[type_descriptor + 8..type_descriptor + 19], b".?AVDuck@@\0");
+/// 
+/// let hierarchy = u32_at(locator + 16) as usize - 0x1000_3000;
+/// assert_eq!([u32_at(hierarchy), u32_at(hierarchy + 4), u32_at(hierarchy + 8)], [0, 1, 3]);
+/// let swimmer = u32_at(u32_at(hierarchy + 12) as usize - 0x1000_3000 + 8) as usize - 0x1000_3000;
+/// assert_eq!(u32_at(swimmer + 8), 8);
+/// let swimmer_type_descriptor = u32_at(swimmer) as usize - 0x1000_3000;
+/// assert_eq!(&bytes[swimmer_type_descriptor + 8..swimmer_type_descriptor + 22], b".?AVSwimmer@@\0");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MsvcRtti<'a> {
+	/// The class itself followed by all of its direct and indirect bases, in depth-first order.
+	pub hierarchy: &'a [MsvcBaseClass<'a>],
+}
+
+/// Offsets of the records of an [`MsvcRtti`].
+struct Layout {
+	pointer_size: usize,
+	type_descriptors: usize,
+	locators: usize,
+	hierarchies: usize,
+	array: usize,
+	bases: usize,
+	end: usize,
+}
+
+const fn align_up(offset: usize, align: usize) -> usize {
+	offset.div_ceil(align) * align
+}
+
+impl MsvcRtti<'_> {
+	const fn layout(&self, form: MsvcRttiForm) -> Layout {
+		let pointer_size = form.pointer_size();
+		let count = self.hierarchy.len();
+		let mut locators = 0;
+		let mut index = 0;
+		while index < count {
+			locators += align_up(2 * pointer_size + self.hierarchy[index].name.len() + 1, pointer_size);
+			index += 1;
+		}
+		let hierarchies = locators + count * form.locator_size();
+		let array = hierarchies + count * 16;
+		let bases = array + count * 4;
+		Layout {
+			pointer_size,
+			type_descriptors: 0,
+			locators,
+			hierarchies,
+			array,
+			bases,
+			end: align_up(bases + count * 28, pointer_size),
+		}
+	}
+
+	/// Return the size of the RTTI in bytes.
+	pub const fn size(&self, form: MsvcRttiForm) -> usize {
+		self.layout(form).end
+	}
+
+	/// Return the offset of the Complete Object Locator for the VTable
+	/// at `vtable_offset` bytes from the start of the class.
+	pub const fn locator_offset(&self, form: MsvcRttiForm, vtable_offset: i32) -> Option<usize> {
+		let mut index = 0;
+		while index < self.hierarchy.len() {
+			if self.hierarchy[index].mdisp == vtable_offset {
+				return Some(self.layout(form).locators + index * form.locator_size());
+			}
+			index += 1;
+		}
+		None
+	}
+
+	/// Return `true` if the subtree of the hierarchy at `index` is not a single chain of bases.
+	const fn is_multiple(&self, index: usize) -> bool {
+		let last = index + self.hierarchy[index].contained_bases as usize;
+		let mut base = index;
+		while base <= last {
+			if self.hierarchy[base].contained_bases as usize != last - base {
+				return true;
+			}
+			base += 1;
+		}
+		false
+	}
+
+	/// Write the RTTI to `out` as if it was at `address`,
+	/// in an image loaded at `image_base`,
+	/// where `type_info_vtable` is the address of the VTable of `type_info`.
+	/// 
+	/// # Panics
+	/// Panics if `out` is shorter than the [size](Self::size) of the RTTI,
+	/// or if an address does not fit in the form.
+	pub fn write(&self, form: MsvcRttiForm, out: &mut [u8], address: u64, image_base: u64, type_info_vtable: u64) {
+		let layout = self.layout(form);
+		let out = &mut out[..layout.end];
+		out.fill(0);
+		let reference = |offset: usize| {
+			let target = address + offset as u64;
+			let target = match form {
+				MsvcRttiForm::Absolute => target,
+				MsvcRttiForm::ImageRelative => target - image_base,
+			};
+			u32::try_from(target).expect("RTTI address does not fit in 32 bits")
+		};
+		let put = |out: &mut [u8], offset: usize, value: u32| {
+			out[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+		};
+
+		// Type Descriptors: the VTable of `type_info`, a spare pointer and the name.
+		let mut type_descriptor = layout.type_descriptors;
+		for (index, class) in self.hierarchy.iter().enumerate() {
+			let pointer_size = layout.pointer_size;
+			out[type_descriptor..type_descriptor + pointer_size]
+				.copy_from_slice(&type_info_vtable.to_le_bytes()[..pointer_size]);
+			let name = type_descriptor + 2 * pointer_size;
+			out[name..name + class.name.len()].copy_from_slice(class.name.as_bytes());
+
+			let base = layout.bases + index * 28;
+			put(out, base, reference(type_descriptor));
+			type_descriptor = align_up(name + class.name.len() + 1, pointer_size);
+		}
+
+		for (index, class) in self.hierarchy.iter().enumerate() {
+			let locator = layout.locators + index * form.locator_size();
+			put(out, locator, (form == MsvcRttiForm::ImageRelative) as u32);
+			put(out, locator + 4, class.mdisp as u32);
+			put(out, locator + 12, reference(layout.type_descriptors));
+			put(out, locator + 16, reference(layout.hierarchies));
+			if form == MsvcRttiForm::ImageRelative {
+				put(out, locator + 20, reference(locator));
+			}
+
+			// Every class gets a Class Hierarchy Descriptor for its part of the Base Class Array.
+			let hierarchy = layout.hierarchies + index * 16;
+			put(out, hierarchy + 4, self.is_multiple(index) as u32);
+			put(out, hierarchy + 8, class.contained_bases + 1);
+			put(out, hierarchy + 12, reference(layout.array + index * 4));
+
+			let base = layout.bases + index * 28;
+			put(out, layout.array + index * 4, reference(base));
+			put(out, base + 4, class.contained_bases);
+			put(out, base + 8, class.mdisp as u32);
+			put(out, base + 12, u32::MAX);
+			// The Base Class Descriptor refers to a Class Hierarchy Descriptor.
+			put(out, base + 20, 0x40);
+			put(out, base + 24, reference(hierarchy));
+		}
+	}
+}
+
+#[repr(C, align(8))]
+struct Bytes<const N: usize>([u8; N]);
+
+/// Storage in the image for the RTTI of an MSVC class,
+/// which is written when the image is loaded,
+/// since image-relative references can only be computed then.
+/// 
+/// These are created with [`msvc_rtti!`](crate::msvc_rtti!),
+/// which also registers the initializer.
+pub struct StaticMsvcRtti<const N: usize> {
+	rtti: MsvcRtti<'static>,
+	bytes: UnsafeCell<Bytes<N>>,
+}
+
+// SAFETY: The bytes are only written by `init`, before other code runs.
+unsafe impl<const N: usize> Sync for StaticMsvcRtti<N> {}
+
+impl<const N: usize> StaticMsvcRtti<N> {
+	/// Reserve storage for `rtti`, in the [native form](MsvcRttiForm::NATIVE).
+	/// 
+	/// # Panics
+	/// Panics if `N` is not the [size](MsvcRtti::size) of the RTTI.
+	pub const fn new(rtti: MsvcRtti<'static>) -> Self {
+		assert!(rtti.size(MsvcRttiForm::NATIVE) == N, "wrong size for MSVC RTTI");
+		Self { rtti, bytes: UnsafeCell::new(Bytes([0; N])) }
+	}
+
+	/// Return the description of the RTTI.
+	pub const fn rtti(&self) -> &MsvcRtti<'static> {
+		&self.rtti
+	}
+
+	/// Return a pointer to the Complete Object Locator for the VTable
+	/// at `vtable_offset` bytes from the start of the class.
+	/// 
+	/// # Panics
+	/// Panics if no class in the hierarchy is at `vtable_offset`.
+	pub const fn locator(&'static self, vtable_offset: i32) -> MsvcLocatorPtr {
+		let Some(offset) = self.rtti.locator_offset(MsvcRttiForm::NATIVE, vtable_offset) else {
+			panic!("no class in the RTTI hierarchy is at the offset of the VTable");
+		};
+		// SAFETY: The offset is within the bytes.
+		MsvcLocatorPtr(unsafe { NonNull::new_unchecked(self.bytes.get().cast::<u8>().add(offset)) })
+	}
+
+	/// Write the RTTI.
+	/// 
+	/// Offsets are relative to `__ImageBase` on Windows,
+	/// and to the start of the RTTI on other targets, which have no image base.
+	/// 
+	/// # Safety
+	/// This must not be called while the RTTI is being read,
+	/// which [`msvc_rtti!`](crate::msvc_rtti!) ensures by calling it when the image is loaded.
+	pub unsafe fn init(&'static self) {
+		let address = self.bytes.get() as u64;
+		#[cfg(windows)]
+		let image_base = {
+			unsafe extern "C" {
+				static __ImageBase: u8;
+			}
+			// SAFETY: The linker defines the symbol.
+			unsafe { ::core::ptr::addr_of!(__ImageBase) as u64 }
+		};
+		#[cfg(not(windows))]
+		let image_base = address;
+		#[cfg(target_env = "msvc")]
+		let type_info_vtable = {
+			#[cfg_attr(target_feature = "crt-static", link(name = "libvcruntime"))]
+			#[cfg_attr(not(target_feature = "crt-static"), link(name = "vcruntime", kind = "dylib"))]
+			unsafe extern "C" {
+				#[link_name = "??_7type_info@@6B@"]
+				static TYPE_INFO_VTABLE: usize;
+			}
+			// SAFETY: The C++ runtime defines the VTable.
+			unsafe { ::core::ptr::addr_of!(TYPE_INFO_VTABLE) as u64 }
+		};
+		#[cfg(not(target_env = "msvc"))]
+		let type_info_vtable = 0;
+		// SAFETY: The caller guarantees that nothing else accesses the bytes.
+		let bytes = unsafe { &mut (*self.bytes.get()).0 };
+		self.rtti.write(MsvcRttiForm::NATIVE, bytes, address, image_base, type_info_vtable);
+	}
+
+	/// Return the bytes of the RTTI, which are zero until it is [initialized](Self::init).
+	pub fn bytes(&self) -> &[u8] {
+		// SAFETY: The bytes are not written after initialization.
+		unsafe { &(*self.bytes.get()).0 }
+	}
+}
+
+/// Pointer to an MSVC RTTI Complete Object Locator in a [`StaticMsvcRtti`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct MsvcLocatorPtr(NonNull<u8>);
+
+// SAFETY: The locator is not written after initialization.
+unsafe impl Send for MsvcLocatorPtr {}
+// SAFETY: The locator is not written after initialization.
+unsafe impl Sync for MsvcLocatorPtr {}
+
+impl MsvcLocatorPtr {
+	/// Return the pointer to the locator.
+	pub const fn as_ptr(self) -> NonNull<u8> {
+		self.0
+	}
+}
+
+/// Layout of a VTable with the prefix used by MSVC,
+/// a pointer to the RTTI Complete Object Locator,
+/// which C++ uses for `dynamic_cast` and `typeid`.
+/// 
+/// These are usually created with [`msvc_vtable!`](crate::msvc_vtable!).
+#[repr(C)]
+pub struct MsvcVTable<VTable> {
+	/// Complete Object Locator of the VTable.
+	pub locator: MsvcLocatorPtr,
+	/// VTable at the address point.
+	pub vtable: VTable,
+}
+
+impl<VTable> MsvcVTable<VTable> {
+	/// Returns a [`VTablePtr`] to the address point.
+	pub const fn address_point(&'static self) -> VTablePtr<VTable> {
+		VTablePtr::from_ref(&self.vtable)
+	}
+}
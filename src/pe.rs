@@ -0,0 +1,385 @@
+use ::core::{
+	fmt,
+	str,
+};
+use ::alloc::vec::Vec;
+
+const IMAGE_SCN_CNT_CODE: u32 = 0x20;
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+/// Error returned when a [`PeFile`] cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PeError {
+	/// The data does not start with a DOS header or does not have a PE signature.
+	NotPe,
+	/// The optional header is neither PE32 nor PE32+.
+	Unsupported,
+	/// A header or table extends past the end of the data.
+	Truncated,
+}
+
+impl fmt::Display for PeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::NotPe => "not a PE file",
+			Self::Unsupported => "not a PE32 or PE32+ file",
+			Self::Truncated => "PE file is truncated",
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Section {
+	virtual_address: u32,
+	virtual_size: u32,
+	raw_offset: u32,
+	raw_size: u32,
+	characteristics: u32,
+}
+
+/// A VTable of an MSVC class in a [`PeFile`], found through its RTTI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeVTable<'a> {
+	/// RVA of the first slot, which is where the VTable pointers of objects point to.
+	pub rva: u32,
+	/// The Complete Object Locator, which is stored one pointer before the first slot.
+	pub locator: PeObjectLocator<'a>,
+	/// RVAs of the functions in the slots.
+	pub slots: Vec<u32>,
+}
+
+/// An MSVC RTTI Complete Object Locator, which describes a VTable of a class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeObjectLocator<'a> {
+	/// RVA of the Complete Object Locator.
+	pub rva: u32,
+	/// Offset of the VTable pointer in the complete object.
+	pub offset: u32,
+	/// Offset of the constructor displacement, for classes with virtual bases.
+	pub cd_offset: u32,
+	/// RVA of the type descriptor of the complete class.
+	pub type_descriptor: u32,
+	/// Decorated name of the complete class, such as `.?AVLizard@zoo@@`.
+	pub name: &'a str,
+	/// The class hierarchy of the complete class.
+	pub hierarchy: PeClassHierarchy<'a>,
+}
+
+/// An MSVC RTTI Class Hierarchy Descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeClassHierarchy<'a> {
+	/// Attributes: `1` for multiple inheritance and `2` for virtual inheritance.
+	pub attributes: u32,
+	/// The class itself followed by all of its direct and indirect bases,
+	/// in depth-first order.
+	pub bases: Vec<PeBaseClass<'a>>,
+}
+
+/// An MSVC RTTI Base Class Descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeBaseClass<'a> {
+	/// Decorated name of the base class.
+	pub name: &'a str,
+	/// Number of bases that the base class itself has,
+	/// which follow it in [`PeClassHierarchy::bases`].
+	pub contained_bases: u32,
+	/// Offset of the base class in the class.
+	pub mdisp: i32,
+	/// Offset of the virtual base table pointer, or `-1` if the base is not virtual.
+	pub pdisp: i32,
+	/// Offset of the displacement in the virtual base table.
+	pub vdisp: i32,
+	/// Attributes of the base class.
+	pub attributes: u32,
+}
+
+/// A PE image, such as a DLL, parsed from a byte slice
+/// to find MSVC VTables through their RTTI.
+/// 
+/// Both 64-bit images, whose RTTI uses image-relative offsets,
+/// and 32-bit images, whose RTTI uses absolute addresses, are supported.
+/// Absolute addresses are resolved against the preferred image base,
+/// which is what they are stored as in the file.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::PeFile;
+/// # let mut image = vec![0u8; 0x400];
+/// # let mut put = |offset: usize, bytes: &[u8]| image[offset..offset + bytes.len()].copy_from_slice(bytes);
+/// # put(0, b"MZ");
+/// # put(0x3c, &0x40u32.to_le_bytes());
+/// # put(0x40, b"PE\0\0\x64\x86\x02\0");
+/// # put(0x54, &0x20u16.to_le_bytes());
+/// # put(0x58, &0x20bu16.to_le_bytes());
+/// # put(0x70, &0x1_4000_0000u64.to_le_bytes());
+/// # for (index, (va, raw, flags)) in [(0x1000u32, 0x200u32, 0x6000_0020u32), (0x2000, 0x300, 0x4000_0040)].into_iter().enumerate() {
+/// # 	let header = 0x78 + index * 40;
+/// # 	put(header + 8, &0x100u32.to_le_bytes());
+/// # 	put(header + 12, &va.to_le_bytes());
+/// # 	put(header + 16, &0x100u32.to_le_bytes());
+/// # 	put(header + 20, &raw.to_le_bytes());
+/// # 	put(header + 36, &flags.to_le_bytes());
+/// # }
+/// # let rdata = |rva: usize| rva - 0x2000 + 0x300;
+/// # put(rdata(0x2010), b".?AVPet@zoo@@\0");
+/// # for (rva, value) in [(0x2020, 0), (0x2024, 0), (0x2028, 1), (0x202c, 0x2030), (0x2030, 0x2038)] {
+/// # 	put(rdata(rva), &u32::to_le_bytes(value));
+/// # }
+/// # for (rva, value) in [(0x2038, 0x2000), (0x203c, 0), (0x2040, 0), (0x2044, u32::MAX), (0x2048, 0), (0x204c, 0x40), (0x2050, 0x2020)] {
+/// # 	put(rdata(rva), &u32::to_le_bytes(value));
+/// # }
+/// # for (rva, value) in [(0x2058, 1), (0x205c, 0), (0x2060, 0), (0x2064, 0x2000), (0x2068, 0x2020), (0x206c, 0x2058)] {
+/// # 	put(rdata(rva), &u32::to_le_bytes(value));
+/// # }
+/// # for (rva, value) in [(0x2070, 0x1_4000_2058u64), (0x2078, 0x1_4000_1000), (0x2080, 0x1_4000_1010)] {
+/// # 	put(rdata(rva), &u64::to_le_bytes(value));
+/// # }
+/// // `image` is the contents of a 64-bit DLL with the VTable of `zoo::Pet`.
+/// let pe = PeFile::parse(&image).unwrap();
+/// let vtables = pe.vtables();
+/// assert_eq!(vtables.len(), 1);
+/// let pet = &vtables[0];
+/// assert_eq!(pet.locator.name, ".?AVPet@zoo@@");
+/// assert_eq!(pet.locator.hierarchy.bases[0].name, ".?AVPet@zoo@@");
+/// assert_eq!(pet.rva, 0x2078);
+/// assert_eq!(pet.slots, [0x1000, 0x1010]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PeFile<'a> {
+	data: &'a [u8],
+	is_64: bool,
+	machine: u16,
+	image_base: u64,
+	sections: Vec<Section>,
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], PeError> {
+	data.get(offset..offset.checked_add(N).ok_or(PeError::Truncated)?)
+		.map(|bytes| bytes.try_into().unwrap())
+		.ok_or(PeError::Truncated)
+}
+
+fn add(offset: usize, field: usize) -> Result<usize, PeError> {
+	offset.checked_add(field).ok_or(PeError::Truncated)
+}
+
+/// Return the RVA of the entry `index` of `size` bytes in a table at `rva`.
+fn entry_rva(rva: u32, index: u32, size: u32) -> Option<u32> {
+	rva.checked_add(index.checked_mul(size)?)
+}
+
+impl<'a> PeFile<'a> {
+	/// Parse the headers of a PE image.
+	/// 
+	/// # Errors
+	/// Returns an error if `data` is not a PE32 or PE32+ image,
+	/// or if its headers are truncated.
+	pub fn parse(data: &'a [u8]) -> Result<Self, PeError> {
+		let u16_at = |offset| read(data, offset).map(u16::from_le_bytes);
+		let u32_at = |offset| read(data, offset).map(u32::from_le_bytes);
+		if !data.starts_with(b"MZ") {
+			return Err(PeError::NotPe);
+		}
+		let header = u32_at(0x3c)? as usize;
+		if read::<4>(data, header)? != *b"PE\0\0" {
+			return Err(PeError::NotPe);
+		}
+		let machine = u16_at(add(header, 4)?)?;
+		let section_count = u16_at(add(header, 6)?)? as usize;
+		let optional_size = u16_at(add(header, 20)?)? as usize;
+		let optional = add(header, 24)?;
+		let (is_64, image_base) = match u16_at(optional)? {
+			0x10b => (false, u32_at(add(optional, 28)?)? as u64),
+			0x20b => (true, read(data, add(optional, 24)?).map(u64::from_le_bytes)?),
+			_ => return Err(PeError::Unsupported),
+		};
+		let mut sections = Vec::new();
+		for index in 0..section_count {
+			let section = add(add(optional, optional_size)?, index * 40)?;
+			sections.push(Section {
+				virtual_size: u32_at(add(section, 8)?)?,
+				virtual_address: u32_at(add(section, 12)?)?,
+				raw_size: u32_at(add(section, 16)?)?,
+				raw_offset: u32_at(add(section, 20)?)?,
+				characteristics: u32_at(add(section, 36)?)?,
+			});
+		}
+		Ok(Self { data, is_64, machine, image_base, sections })
+	}
+
+	/// Return `true` if the image is 64-bit (PE32+).
+	pub fn is_64(&self) -> bool {
+		self.is_64
+	}
+
+	/// Return the machine type of the image, such as `0x8664` for x64.
+	pub fn machine(&self) -> u16 {
+		self.machine
+	}
+
+	/// Return the preferred base address of the image.
+	pub fn image_base(&self) -> u64 {
+		self.image_base
+	}
+
+	fn section(&self, rva: u32) -> Option<&Section> {
+		self.sections.iter().find(|section| {
+			let size = section.virtual_size.max(section.raw_size);
+			section.virtual_address <= rva && rva - section.virtual_address < size
+		})
+	}
+
+	/// Return `true` if `rva` is in a section with executable code.
+	pub fn is_code(&self, rva: u32) -> bool {
+		self.section(rva).is_some_and(|section| {
+			section.characteristics & (IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE) != 0
+		})
+	}
+
+	/// Return the bytes of the image at `rva`, up to the end of the section's raw data.
+	pub fn bytes_at(&self, rva: u32) -> Option<&'a [u8]> {
+		let section = self.section(rva)?;
+		let offset = rva - section.virtual_address;
+		if offset >= section.raw_size {
+			return None;
+		}
+		let start = usize::try_from(section.raw_offset.checked_add(offset)?).ok()?;
+		let end = (section.raw_offset as usize).saturating_add(section.raw_size as usize).min(self.data.len());
+		self.data.get(start..end)
+	}
+
+	/// Read a 32-bit value at `rva`.
+	pub fn read_u32(&self, rva: u32) -> Option<u32> {
+		Some(u32::from_le_bytes(self.bytes_at(rva)?.get(..4)?.try_into().unwrap()))
+	}
+
+	/// Read a pointer at `rva` and convert it to an RVA,
+	/// or return [`None`] if it does not point into the image.
+	pub fn read_pointer(&self, rva: u32) -> Option<u32> {
+		let address = if self.is_64 {
+			u64::from_le_bytes(self.bytes_at(rva)?.get(..8)?.try_into().unwrap())
+		} else {
+			self.read_u32(rva)? as u64
+		};
+		self.section(u32::try_from(address.checked_sub(self.image_base)?).ok()?)?;
+		Some((address - self.image_base) as u32)
+	}
+
+	/// Read an RTTI reference at `rva`, which is an RVA in 64-bit images
+	/// and an absolute address in 32-bit images.
+	fn read_reference(&self, rva: u32) -> Option<u32> {
+		if self.is_64 {
+			let target = self.read_u32(rva)?;
+			self.section(target)?;
+			Some(target)
+		} else {
+			self.read_pointer(rva)
+		}
+	}
+
+	fn c_str_at(&self, rva: u32) -> Option<&'a str> {
+		let bytes = self.bytes_at(rva)?;
+		let len = bytes.iter().position(|&byte| byte == 0)?;
+		str::from_utf8(&bytes[..len]).ok()
+	}
+
+	/// Return the decorated name in the type descriptor at `rva`.
+	fn type_name(&self, rva: u32) -> Option<&'a str> {
+		let pointer_size = if self.is_64 { 8 } else { 4 };
+		self.c_str_at(rva.checked_add(2 * pointer_size)?).filter(|name| name.starts_with(".?A"))
+	}
+
+	/// Decode the Class Hierarchy Descriptor at `rva`.
+	pub fn class_hierarchy(&self, rva: u32) -> Option<PeClassHierarchy<'a>> {
+		if self.read_u32(rva)? != 0 {
+			return None;
+		}
+		let attributes = self.read_u32(rva.checked_add(4)?)?;
+		let count = self.read_u32(rva.checked_add(8)?)?;
+		let array = self.read_reference(rva.checked_add(12)?)?;
+		let mut bases = Vec::new();
+		for index in 0..count {
+			let base = self.read_reference(entry_rva(array, index, 4)?)?;
+			bases.push(PeBaseClass {
+				name: self.type_name(self.read_reference(base)?)?,
+				contained_bases: self.read_u32(base.checked_add(4)?)?,
+				mdisp: self.read_u32(base.checked_add(8)?)? as i32,
+				pdisp: self.read_u32(base.checked_add(12)?)? as i32,
+				vdisp: self.read_u32(base.checked_add(16)?)? as i32,
+				attributes: self.read_u32(base.checked_add(20)?)?,
+			});
+		}
+		Some(PeClassHierarchy { attributes, bases })
+	}
+
+	/// Decode the Complete Object Locator at `rva`,
+	/// or return [`None`] if there is not a valid one there.
+	pub fn object_locator(&self, rva: u32) -> Option<PeObjectLocator<'a>> {
+		let signature = self.read_u32(rva)?;
+		if signature != self.is_64 as u32 || (self.is_64 && self.read_u32(rva.checked_add(20)?)? != rva) {
+			return None;
+		}
+		let type_descriptor = self.read_reference(rva.checked_add(12)?)?;
+		Some(PeObjectLocator {
+			rva,
+			offset: self.read_u32(rva.checked_add(4)?)?,
+			cd_offset: self.read_u32(rva.checked_add(8)?)?,
+			type_descriptor,
+			name: self.type_name(type_descriptor)?,
+			hierarchy: self.class_hierarchy(self.read_reference(rva.checked_add(16)?)?)?,
+		})
+	}
+
+	/// Find every VTable in the image.
+	/// 
+	/// Complete Object Locators are found by scanning data sections,
+	/// and then VTables are found by scanning for pointers to them.
+	/// The slots of a VTable are the pointers to code that follow.
+	pub fn vtables(&self) -> Vec<PeVTable<'a>> {
+		let pointer_size = if self.is_64 { 8 } else { 4 };
+		let data_sections = || self.sections.iter().filter(|section| {
+			section.characteristics & (IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE) == 0
+		});
+
+		let mut locators = Vec::new();
+		for section in data_sections() {
+			for offset in (0..section.raw_size.saturating_sub(20)).step_by(4) {
+				let Some(rva) = section.virtual_address.checked_add(offset) else {
+					break;
+				};
+				if let Some(locator) = self.object_locator(rva) {
+					locators.push(locator);
+				}
+			}
+		}
+		locators.sort_by_key(|locator| locator.rva);
+
+		let mut vtables = Vec::new();
+		for section in data_sections() {
+			for offset in (0..section.raw_size.saturating_sub(pointer_size)).step_by(pointer_size as usize) {
+				let Some(rva) = section.virtual_address.checked_add(offset) else {
+					break;
+				};
+				let Some(target) = self.read_pointer(rva) else {
+					continue;
+				};
+				let Ok(index) = locators.binary_search_by_key(&target, |locator| locator.rva) else {
+					continue;
+				};
+				let Some(start) = rva.checked_add(pointer_size) else {
+					break;
+				};
+				let mut slots = Vec::new();
+				let slot = |index: usize| entry_rva(start, u32::try_from(index).ok()?, pointer_size);
+				while let Some(function) = slot(slots.len()).and_then(|rva| self.read_pointer(rva)) {
+					if !self.is_code(function) {
+						break;
+					}
+					slots.push(function);
+				}
+				vtables.push(PeVTable { rva: start, locator: locators[index].clone(), slots });
+			}
+		}
+		vtables
+	}
+}
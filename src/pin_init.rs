@@ -0,0 +1,188 @@
+use ::core::{
+	fmt,
+	mem::{
+		align_of, size_of, MaybeUninit,
+	},
+	pin::Pin,
+	ptr::NonNull,
+};
+#[cfg(feature = "alloc")]
+use ::alloc::boxed::Box;
+
+/// In-place initializer for a `T`.
+/// 
+/// Initializers write a `T` directly into its final location,
+/// which can then be pinned with [`pin_box`], [`pin_static`], [`pin_in_buffer`]
+/// or [`pin_init!`](crate::pin_init!),
+/// so that C++ can keep pointers to it.
+/// 
+/// # Safety
+/// [`Init::init`] must fully initialize `slot` if it returns.
+pub unsafe trait Init<T> {
+	/// Initialize `slot`.
+	/// 
+	/// # Safety
+	/// `slot` must be valid for writes and properly aligned.
+	unsafe fn init(self, slot: NonNull<T>);
+}
+
+/// Initializer that moves a value into place.
+/// 
+/// See [`init_value`].
+#[derive(Debug)]
+pub struct InitValue<T>(T);
+
+// SAFETY: `slot` is written to.
+unsafe impl<T> Init<T> for InitValue<T> {
+	unsafe fn init(self, slot: NonNull<T>) {
+		unsafe { slot.write(self.0) }
+	}
+}
+
+/// Return an initializer that moves `value` into place.
+/// 
+/// This is fine for values that are not yet referred to by address.
+pub const fn init_value<T>(value: T) -> InitValue<T> {
+	InitValue(value)
+}
+
+/// Initializer that calls a closure with the location to initialize.
+/// 
+/// See [`init_with`].
+#[derive(Debug)]
+pub struct InitWith<F>(F);
+
+// SAFETY: The caller of `init_with` promised that `F` initializes `slot`.
+unsafe impl<T, F: FnOnce(NonNull<T>)> Init<T> for InitWith<F> {
+	unsafe fn init(self, slot: NonNull<T>) {
+		(self.0)(slot)
+	}
+}
+
+/// Return an initializer that calls `f` with the location to initialize.
+/// 
+/// # Safety
+/// `f` must fully initialize the `T` it is given if it returns.
+pub const unsafe fn init_with<T, F: FnOnce(NonNull<T>)>(f: F) -> InitWith<F> {
+	InitWith(f)
+}
+
+/// Initialize a `T` on the heap and pin it.
+#[cfg(feature = "alloc")]
+pub fn pin_box<T>(init: impl Init<T>) -> Pin<Box<T>> {
+	let mut slot = Box::<T>::new_uninit();
+	// SAFETY: `slot` is valid for writes, and is initialized afterwards.
+	unsafe {
+		init.init(NonNull::from(&mut *slot).cast());
+		Box::into_pin(slot.assume_init())
+	}
+}
+
+/// Initialize a `T` in static memory and pin it.
+/// 
+/// The `T` is never dropped.
+pub fn pin_static<T>(slot: &'static mut MaybeUninit<T>, init: impl Init<T>) -> Pin<&'static mut T> {
+	// SAFETY: `slot` is valid for writes, and is initialized afterwards.
+	// Static memory is never reused, so the pinning guarantee is upheld.
+	unsafe {
+		init.init(NonNull::from(&mut *slot).cast());
+		Pin::new_unchecked(slot.assume_init_mut())
+	}
+}
+
+/// Error returned by [`pin_in_buffer`] when a `T` does not fit into the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferError {
+	/// The buffer is smaller than the `T`.
+	TooSmall {
+		/// Size of the buffer.
+		size: usize,
+		/// Size of the `T`.
+		required: usize,
+	},
+	/// The buffer is not aligned well enough for the `T`.
+	Misaligned {
+		/// Alignment of the buffer.
+		align: usize,
+		/// Alignment of the `T`.
+		required: usize,
+	},
+}
+
+impl fmt::Display for BufferError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::TooSmall { size, required } => {
+				write!(f, "buffer of {size} bytes is too small for {required} bytes")
+			}
+			Self::Misaligned { align, required } => {
+				write!(f, "buffer aligned to {align} bytes needs to be aligned to {required} bytes")
+			}
+		}
+	}
+}
+
+/// Initialize a `T` in a buffer, such as one provided by C++, and pin it.
+/// 
+/// `size` and `align` describe the buffer,
+/// for example as given by `sizeof` and `alignof` in C++.
+/// 
+/// # Safety
+/// - `buffer` must be valid for reads and writes of `size` bytes for the duration of `'a`,
+///   and must not be accessed through any other pointer during that time.
+/// - The `T` must be dropped in place before the buffer is reused or freed,
+///   for example with [`core::ptr::drop_in_place`].
+/// 
+/// # Errors
+/// Returns an error if the `T` does not fit into the buffer,
+/// or if `buffer` is not aligned well enough for the `T`.
+pub unsafe fn pin_in_buffer<'a, T>(
+	buffer: NonNull<u8>,
+	size: usize,
+	align: usize,
+	init: impl Init<T>,
+) -> Result<Pin<&'a mut T>, BufferError> {
+	if size < size_of::<T>() {
+		return Err(BufferError::TooSmall { size, required: size_of::<T>() });
+	}
+	let align = align.min(1 << buffer.as_ptr().addr().trailing_zeros().min(usize::BITS - 1));
+	if align < align_of::<T>() {
+		return Err(BufferError::Misaligned { align, required: align_of::<T>() });
+	}
+	let slot = buffer.cast::<T>();
+	// SAFETY: `slot` is valid for writes and properly aligned,
+	// and the caller upholds the pinning guarantee.
+	unsafe {
+		init.init(slot);
+		Ok(Pin::new_unchecked(&mut *slot.as_ptr()))
+	}
+}
+
+/// Implementation detail of [`pin_init!`](crate::pin_init!).
+/// 
+/// Drops the `T` in its slot when dropped.
+#[doc(hidden)]
+pub struct StackSlot<'a, T>(&'a mut MaybeUninit<T>);
+
+impl<'a, T> StackSlot<'a, T> {
+	/// # Safety
+	/// The returned value must not be leaked.
+	#[doc(hidden)]
+	pub unsafe fn new(slot: &'a mut MaybeUninit<T>, init: impl Init<T>) -> Self {
+		unsafe { init.init(NonNull::from(&mut *slot).cast()) };
+		Self(slot)
+	}
+
+	#[doc(hidden)]
+	pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+		// SAFETY: The slot is initialized, and `Self` is never leaked.
+		unsafe { Pin::new_unchecked(self.0.assume_init_mut()) }
+	}
+}
+
+impl<T> Drop for StackSlot<'_, T> {
+	fn drop(&mut self) {
+		// SAFETY: The slot is initialized.
+		unsafe { self.0.assume_init_drop() }
+	}
+}
@@ -0,0 +1,400 @@
+use ::core::{
+	ffi::{
+		c_char, c_void, CStr,
+	},
+	fmt,
+	marker::PhantomData,
+	mem::{
+		self, MaybeUninit,
+	},
+	ptr::NonNull,
+	slice,
+};
+
+#[cfg(any(unix, windows))]
+use super::{
+	Library, LibraryError,
+};
+use super::{
+	VtImpl, VtObject, VtObjectPtr,
+};
+
+/// Name of the entry point of plugins, which has the type [`PluginEntryFn`].
+pub const PLUGIN_ENTRY_SYMBOL: &CStr = c"cppdvt_plugin_entry";
+
+/// Version of the layout of [`PluginManifest`] and [`PluginExport`].
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Type of the entry point of plugins, which returns the manifest of the plugin.
+pub type PluginEntryFn = unsafe extern "C" fn() -> *const PluginManifest;
+
+/// Trait for VTables of interfaces between hosts and plugins,
+/// which identifies them by name and version.
+/// 
+/// Each version of an interface has its own VTable type with the same name.
+/// A new version must only append slots to the VTable of the previous one,
+/// so that a host can use the slots that an older plugin has,
+/// and a plugin can be used by an older host.
+/// 
+/// # Safety
+/// - `Self` must consist of [`PluginInterface::SLOTS`] pointers, such as a VTable
+///   declared with [`vtable!`](crate::vtable!).
+/// - Every VTable type with the same name must be a prefix of those with higher versions.
+pub unsafe trait PluginInterface: Sized + 'static {
+	/// Name of the interface, which is the same for all of its versions.
+	const NAME: &'static CStr;
+	/// Version of the interface that `Self` is the VTable of.
+	const VERSION: u32;
+	/// Oldest version of the interface that a host with `Self` accepts.
+	const MIN_VERSION: u32 = 1;
+	/// Number of slots in the VTable.
+	const SLOTS: usize = mem::size_of::<Self>() / mem::size_of::<*const ()>();
+}
+
+/// Manifest of a plugin, returned by its entry point.
+/// 
+/// Plugins written in Rust declare it with [`export_plugin!`](crate::export_plugin!),
+/// and plugins written in C++ define an equivalent structure.
+/// 
+/// The fields are private so that manifests created in Rust always point to
+/// their exports, which [`exports`](Self::exports) relies on.
+#[derive(Debug)]
+#[repr(C)]
+pub struct PluginManifest {
+	abi_version: u32,
+	len: usize,
+	exports: *const PluginExport,
+}
+
+// SAFETY: The manifest is immutable.
+unsafe impl Sync for PluginManifest {}
+
+impl PluginManifest {
+	/// Create the manifest of the plugin that exports `exports`.
+	pub const fn new(exports: &'static [PluginExport]) -> Self {
+		Self {
+			abi_version: PLUGIN_ABI_VERSION,
+			len: exports.len(),
+			exports: exports.as_ptr(),
+		}
+	}
+
+	/// Return the [`PLUGIN_ABI_VERSION`] of the plugin.
+	pub const fn abi_version(&self) -> u32 {
+		self.abi_version
+	}
+
+	/// Return the interfaces that the plugin exports.
+	pub fn exports(&self) -> &[PluginExport] {
+		if self.exports.is_null() {
+			return &[];
+		}
+		// SAFETY: `exports` points to `len` exports.
+		unsafe { slice::from_raw_parts(self.exports, self.len) }
+	}
+}
+
+/// Interface exported by a plugin, which is an object with the VTable of
+/// some version of the interface.
+/// 
+/// The fields are private so that exports created in Rust always point to
+/// a C string, which [`name`](Self::name) relies on.
+#[derive(Debug)]
+#[repr(C)]
+pub struct PluginExport {
+	name: *const c_char,
+	version: u32,
+	slots: usize,
+	object: *mut c_void,
+}
+
+// SAFETY: The export is immutable, and the object is shared with the host.
+unsafe impl Sync for PluginExport {}
+
+impl PluginExport {
+	/// Export `object`, which implements the interface `VTable`.
+	pub const fn new<VTable: PluginInterface, T: VtImpl<VTable> + Sync>(object: &'static T) -> Self {
+		// SAFETY: `T` upholds the contract of `VtImpl`, and can be shared with the host.
+		unsafe { Self::new_unchecked::<VTable, T>(object) }
+	}
+
+	/// Export `object` with the VTable of the interface `VTable`.
+	/// 
+	/// # Safety
+	/// `object` must be a C++ object with the VTable `VTable`,
+	/// which the host may use from any thread.
+	pub const unsafe fn new_unchecked<VTable: PluginInterface, T>(object: &'static T) -> Self {
+		Self {
+			name: VTable::NAME.as_ptr(),
+			version: VTable::VERSION,
+			slots: VTable::SLOTS,
+			object: (object as *const T).cast_mut().cast(),
+		}
+	}
+
+	/// Return the name of the interface.
+	pub fn name(&self) -> &CStr {
+		// SAFETY: `name` is a C string.
+		unsafe { CStr::from_ptr(self.name) }
+	}
+
+	/// Return the version of the interface.
+	pub const fn version(&self) -> u32 {
+		self.version
+	}
+
+	/// Return the number of slots in the VTable of the object.
+	pub const fn slots(&self) -> usize {
+		self.slots
+	}
+
+	/// Return the object, which starts with a pointer to its VTable.
+	pub const fn object(&self) -> *mut c_void {
+		self.object
+	}
+}
+
+/// Error returned when a plugin or one of its interfaces cannot be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginError {
+	/// The library could not be loaded.
+	#[cfg(any(unix, windows))]
+	Library(LibraryError),
+	/// The library does not export [`PLUGIN_ENTRY_SYMBOL`], or it returned no manifest.
+	NoEntry,
+	/// The manifest has a different [`PLUGIN_ABI_VERSION`].
+	AbiVersion(u32),
+	/// The plugin does not export the interface.
+	NoInterface,
+	/// The plugin only exports versions of the interface that the host does not accept,
+	/// the highest of which is given.
+	Version(u32),
+}
+
+impl fmt::Display for PluginError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			#[cfg(any(unix, windows))]
+			Self::Library(error) => write!(f, "plugin could not be loaded: {error}"),
+			Self::NoEntry => f.write_str("plugin has no entry point"),
+			Self::AbiVersion(version) => write!(f, "plugin has unsupported ABI version {version}"),
+			Self::NoInterface => f.write_str("plugin does not export the interface"),
+			Self::Version(version) => write!(f, "plugin exports unsupported interface version {version}"),
+		}
+	}
+}
+
+#[cfg(any(unix, windows))]
+impl From<LibraryError> for PluginError {
+	fn from(error: LibraryError) -> Self {
+		Self::Library(error)
+	}
+}
+
+/// Plugin loaded by a host, which negotiates the versions of its interfaces.
+/// 
+/// # Examples
+/// ```
+/// # use core::ffi::CStr;
+/// # use cppdvt::{Plugin, PluginError, PluginInterface, VTablePtr, VtImpl, VtObjectPtr, export_plugin, plugin_call, virtual_call, virtual_fn, vtable};
+/// // Version 1 of the interface, which an older plugin was built with.
+/// vtable! {
+/// 	GreeterV1Vt {
+/// 		pub fn greeting() -> &'static CStr;
+/// 	}
+/// }
+/// unsafe impl PluginInterface for GreeterV1Vt {
+/// 	const NAME: &'static CStr = c"Greeter";
+/// 	const VERSION: u32 = 1;
+/// }
+/// 
+/// // Version 2 appends a slot.
+/// vtable! {
+/// 	GreeterVt {
+/// 		pub fn greeting() -> &'static CStr;
+/// 		pub fn loudness() -> u32;
+/// 	}
+/// }
+/// unsafe impl PluginInterface for GreeterVt {
+/// 	const NAME: &'static CStr = c"Greeter";
+/// 	const VERSION: u32 = 2;
+/// }
+/// 
+/// // The older plugin.
+/// virtual_fn! {
+/// 	fn greeting(this: VtObjectPtr<GreeterV1Vt>) -> &'static CStr {
+/// 		c"hello"
+/// 	}
+/// }
+/// #[repr(C)]
+/// struct Greeter {
+/// 	vtable: VTablePtr<GreeterV1Vt>,
+/// }
+/// unsafe impl VtImpl<GreeterV1Vt> for Greeter {
+/// 	const VTABLE: &'static GreeterV1Vt = &GreeterV1Vt { greeting };
+/// }
+/// static GREETER: Greeter = Greeter { vtable: VTablePtr::from_ref(Greeter::VTABLE) };
+/// 
+/// export_plugin! {
+/// 	GreeterV1Vt => &GREETER,
+/// }
+/// 
+/// // A newer host, which would use `Plugin::open` instead.
+/// let plugin = unsafe { Plugin::from_entry(cppdvt_plugin_entry) }.unwrap();
+/// let greeter = plugin.get::<GreeterVt>().unwrap();
+/// assert_eq!((greeter.version(), greeter.slots()), (1, 1));
+/// assert!(greeter.object().is_none());
+/// 
+/// assert_eq!(unsafe { plugin_call!(greeter => greeting()) }, Some(c"hello"));
+/// assert_eq!(unsafe { plugin_call!(greeter => loudness()) }, None);
+/// 
+/// let old = plugin.get::<GreeterV1Vt>().unwrap().object().unwrap();
+/// assert_eq!(unsafe { virtual_call!(old => greeting()) }, c"hello");
+/// ```
+#[derive(Debug)]
+pub struct Plugin {
+	manifest: &'static PluginManifest,
+	#[cfg(any(unix, windows))]
+	_library: Option<Library>,
+}
+
+impl Plugin {
+	/// Load the plugin at `path`, and call its entry point.
+	/// 
+	/// # Safety
+	/// See [`Library::open`] and [`Plugin::from_library`].
+	/// 
+	/// # Errors
+	/// Returns the errors of [`Library::open`] and [`Plugin::from_library`].
+	#[cfg(any(unix, windows))]
+	pub unsafe fn open(path: &CStr) -> Result<Self, PluginError> {
+		unsafe { Self::from_library(Library::open(path)?) }
+	}
+
+	/// Call the entry point of the plugin `library`, which stays loaded as long as the plugin.
+	/// 
+	/// # Safety
+	/// [`PLUGIN_ENTRY_SYMBOL`] must be a [`PluginEntryFn`] that returns a valid manifest,
+	/// whose interfaces have the VTables of their names and versions.
+	/// 
+	/// # Errors
+	/// Returns [`PluginError::NoEntry`] and [`PluginError::AbiVersion`].
+	#[cfg(any(unix, windows))]
+	pub unsafe fn from_library(library: Library) -> Result<Self, PluginError> {
+		let entry = library.symbol(PLUGIN_ENTRY_SYMBOL).ok_or(PluginError::NoEntry)?;
+		// SAFETY: Guaranteed by the caller.
+		let entry = unsafe { mem::transmute::<*mut c_void, PluginEntryFn>(entry.as_ptr()) };
+		let manifest = unsafe { Self::manifest(entry) }?;
+		Ok(Self { manifest, _library: Some(library) })
+	}
+
+	/// Call the entry point `entry` of a plugin that is linked with the host.
+	/// 
+	/// # Safety
+	/// `entry` must return a valid manifest,
+	/// whose interfaces have the VTables of their names and versions.
+	/// 
+	/// # Errors
+	/// Returns [`PluginError::NoEntry`] and [`PluginError::AbiVersion`].
+	pub unsafe fn from_entry(entry: PluginEntryFn) -> Result<Self, PluginError> {
+		let manifest = unsafe { Self::manifest(entry) }?;
+		Ok(Self {
+			manifest,
+			#[cfg(any(unix, windows))]
+			_library: None,
+		})
+	}
+
+	unsafe fn manifest(entry: PluginEntryFn) -> Result<&'static PluginManifest, PluginError> {
+		// SAFETY: Guaranteed by the caller.
+		let manifest = unsafe { entry().as_ref() }.ok_or(PluginError::NoEntry)?;
+		if manifest.abi_version != PLUGIN_ABI_VERSION {
+			return Err(PluginError::AbiVersion(manifest.abi_version));
+		}
+		Ok(manifest)
+	}
+
+	/// Return the interfaces that the plugin exports.
+	pub fn exports(&self) -> &[PluginExport] {
+		self.manifest.exports()
+	}
+
+	/// Return the highest version of the interface `VTable` that the plugin exports,
+	/// if it is at least [`PluginInterface::MIN_VERSION`].
+	/// 
+	/// The version may be lower or higher than [`PluginInterface::VERSION`].
+	/// 
+	/// # Errors
+	/// Returns [`PluginError::NoInterface`] if the plugin does not export the interface,
+	/// and [`PluginError::Version`] if its versions are too old.
+	pub fn get<VTable: PluginInterface>(&self) -> Result<PluginObject<'_, VTable>, PluginError> {
+		let export = self.exports()
+			.iter()
+			.filter(|export| export.name() == VTable::NAME)
+			.max_by_key(|export| export.version)
+			.ok_or(PluginError::NoInterface)?;
+		if export.version < VTable::MIN_VERSION {
+			return Err(PluginError::Version(export.version));
+		}
+		let object = NonNull::new(export.object).ok_or(PluginError::NoInterface)?;
+		Ok(PluginObject {
+			object: object.cast(),
+			version: export.version,
+			// A higher version only has more slots.
+			slots: export.slots.min(VTable::SLOTS),
+			_plugin: PhantomData,
+		})
+	}
+}
+
+/// Object of an interface of a [`Plugin`], whose VTable may have fewer slots than `VTable`
+/// if the plugin exports an older version.
+/// 
+/// Methods are called with [`plugin_call!`](crate::plugin_call!),
+/// which checks that the slot is present.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginObject<'a, VTable> {
+	object: VtObjectPtr<VTable>,
+	version: u32,
+	slots: usize,
+	_plugin: PhantomData<&'a Plugin>,
+}
+
+impl<'a, VTable> PluginObject<'a, VTable> {
+	/// Return the version of the interface that the plugin exports.
+	pub const fn version(&self) -> u32 {
+		self.version
+	}
+
+	/// Return the number of slots of the VTable that can be used.
+	pub const fn slots(&self) -> usize {
+		self.slots
+	}
+
+	/// Return whether the slot at the byte offset `offset` of `VTable` can be used.
+	pub const fn has_slot(&self, offset: usize) -> bool {
+		offset / mem::size_of::<*const ()>() < self.slots
+	}
+
+	/// Return the byte offset of the slot that `field` projects `VTable` to.
+	/// 
+	/// `field` is called with a pointer to uninitialized memory,
+	/// so it must only project the pointer, as [`addr_of!`](::core::ptr::addr_of!) does.
+	pub fn slot_offset<T>(&self, field: impl FnOnce(*const VTable) -> *const T) -> usize {
+		let vtable = MaybeUninit::<VTable>::uninit();
+		field(vtable.as_ptr()) as usize - vtable.as_ptr() as usize
+	}
+
+	/// Return a pointer to the object, whose VTable has [`PluginObject::slots`] slots.
+	pub const fn as_ptr(&self) -> VtObjectPtr<VTable> {
+		self.object
+	}
+}
+
+impl<'a, VTable: PluginInterface> PluginObject<'a, VTable> {
+	/// Return the object if its VTable has all of the slots of `VTable`.
+	pub fn object(&self) -> Option<&'a VtObject<VTable>> {
+		// SAFETY: The plugin exports a valid object, which has all of the slots.
+		(self.slots >= VTable::SLOTS).then(|| unsafe { VtObject::from_ptr(self.object) })
+	}
+}
@@ -0,0 +1,320 @@
+use ::core::{
+	fmt,
+	mem::{
+		align_of, size_of,
+	},
+	ptr::NonNull,
+};
+use ::alloc::{
+	boxed::Box,
+	sync::Arc,
+	vec::Vec,
+};
+
+use super::{
+	VTablePtr, VtObject, VtObjectPtr,
+};
+
+/// Layout of the data that precedes the address point of a VTable,
+/// which is where [`VTablePtr`]s point to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VTablePrefix {
+	/// The offset-to-top and the RTTI pointer, as specified by the Itanium ABI.
+	Itanium,
+	/// The pointer to the RTTI Complete Object Locator, as used by MSVC.
+	Msvc,
+	/// Any number of pointer-sized words,
+	/// such as when an Itanium VTable has virtual call or virtual base offsets.
+	Words(usize),
+}
+
+impl VTablePrefix {
+	/// The prefix used by C++ on the target.
+	pub const NATIVE: Self = if cfg!(windows) { Self::Msvc } else { Self::Itanium };
+
+	/// Return the number of pointer-sized words in the prefix.
+	pub const fn words(self) -> usize {
+		match self {
+			Self::Itanium => 2,
+			Self::Msvc => 1,
+			Self::Words(words) => words,
+		}
+	}
+}
+
+struct Header<VTable> {
+	base: VTablePtr<VTable>,
+	prefix: usize,
+	/// The prefix, followed by the slots.
+	words: Box<[usize]>,
+}
+
+/// VTable that is copied from an existing VTable at runtime,
+/// with some of its slots replaced,
+/// which allows "deriving" from C++ classes that cannot be recompiled.
+/// 
+/// The copy includes the prefix of the VTable,
+/// so RTTI keeps working for objects that use it.
+/// Every object that the VTable is [installed](RuntimeVTable::install) into
+/// keeps the VTable alive until it is [uninstalled](RuntimeVTable::uninstall),
+/// and the original VTable stays available through [`RuntimeVTable::base`].
+/// 
+/// # Examples
+/// ```
+/// # use std::sync::OnceLock;
+/// # use cppdvt::{RuntimeVTable, VTablePrefix, VTablePtr, VtObject, VtObjectPtr, vtable, virtual_fn, virtual_call};
+/// vtable! {
+/// 	PetVt {
+/// 		pub fn sound() -> u32;
+/// 	}
+/// }
+/// 
+/// // Pretend that this is a VTable defined by C++, with an Itanium prefix.
+/// #[repr(C)]
+/// struct PetVtGroup {
+/// 	prefix: [usize; 2],
+/// 	vtable: PetVt,
+/// }
+/// virtual_fn! {
+/// 	fn pet_sound(this: VtObjectPtr<PetVt>) -> u32 {
+/// 		1
+/// 	}
+/// }
+/// static PET_VT: PetVtGroup = PetVtGroup {
+/// 	prefix: [0; 2],
+/// 	vtable: PetVt { sound: pet_sound },
+/// };
+/// 
+/// // Override `sound`, calling the base implementation.
+/// static LOUD_PET_VT: OnceLock<RuntimeVTable<PetVt>> = OnceLock::new();
+/// virtual_fn! {
+/// 	fn loud_sound(this: VtObjectPtr<PetVt>) -> u32 {
+/// 		(LOUD_PET_VT.get().unwrap().base().sound)(this) * 10
+/// 	}
+/// }
+/// let loud_pet_vt = LOUD_PET_VT.get_or_init(|| unsafe {
+/// 	let mut builder = RuntimeVTable::builder(
+/// 		VTablePtr::from_ref(&PET_VT.vtable),
+/// 		VTablePrefix::Itanium,
+/// 		1,
+/// 	);
+/// 	builder.vtable_mut().sound = loud_sound;
+/// 	builder.build()
+/// });
+/// 
+/// // Pretend that this object was created by the C++ constructor.
+/// let mut pet = VTablePtr::from_ref(&PET_VT.vtable);
+/// let pet = VtObjectPtr::from_mut(&mut pet);
+/// unsafe {
+/// 	assert!(loud_pet_vt.install(pet));
+/// 	assert!(!loud_pet_vt.install(pet));
+/// 	assert_eq!(virtual_call!(VtObject::from_ptr(pet) => sound()), 10);
+/// 	assert!(loud_pet_vt.uninstall(pet));
+/// 	assert_eq!(virtual_call!(VtObject::from_ptr(pet) => sound()), 1);
+/// }
+/// ```
+pub struct RuntimeVTable<VTable> {
+	header: Arc<Header<VTable>>,
+}
+
+impl<VTable> Clone for RuntimeVTable<VTable> {
+	fn clone(&self) -> Self {
+		Self { header: Arc::clone(&self.header) }
+	}
+}
+
+impl<VTable> fmt::Debug for RuntimeVTable<VTable> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RuntimeVTable")
+			.field("base", &self.header.base)
+			.field("vtable", &self.as_ptr())
+			.field("slots", &self.slots())
+			.finish()
+	}
+}
+
+/// Builder for a [`RuntimeVTable`].
+/// 
+/// See [`RuntimeVTable::builder`].
+pub struct RuntimeVTableBuilder<VTable> {
+	base: VTablePtr<VTable>,
+	prefix: usize,
+	words: Vec<usize>,
+}
+
+impl<VTable> fmt::Debug for RuntimeVTableBuilder<VTable> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RuntimeVTableBuilder")
+			.field("base", &self.base)
+			.field("prefix", &self.prefix)
+			.field("slots", &self.slots())
+			.finish()
+	}
+}
+
+impl<VTable> RuntimeVTable<VTable> {
+	/// Start copying the VTable at `base`,
+	/// together with its `prefix` and `slot_count` slots.
+	/// 
+	/// `slot_count` may be larger than the number of slots in `VTable`,
+	/// for example when `VTable` only declares the slots of a base class.
+	/// 
+	/// # Safety
+	/// `base` must point to the address point of a VTable with the specified prefix,
+	/// which must have at least `slot_count` pointer-sized slots.
+	/// 
+	/// # Panics
+	/// Panics if `slot_count` is smaller than the number of slots in `VTable`,
+	/// or if `VTable` is aligned more strictly than a pointer.
+	pub unsafe fn builder(
+		base: VTablePtr<VTable>,
+		prefix: VTablePrefix,
+		slot_count: usize,
+	) -> RuntimeVTableBuilder<VTable> {
+		assert!(
+			size_of::<VTable>() <= slot_count.saturating_mul(size_of::<usize>()),
+			"fewer slots would be copied than there are in the VTable",
+		);
+		assert!(align_of::<VTable>() <= align_of::<usize>(), "VTable is overaligned");
+		let prefix = prefix.words();
+		let start = base.as_ref() as *const VTable as *const usize;
+		let mut words = Vec::with_capacity(prefix + slot_count);
+		// SAFETY: The caller guarantees that the VTable spans these words.
+		unsafe {
+			let source = ::core::slice::from_raw_parts(start.sub(prefix), prefix + slot_count);
+			words.extend_from_slice(source);
+		}
+		RuntimeVTableBuilder { base, prefix, words }
+	}
+
+	/// Like [`RuntimeVTable::builder`],
+	/// but takes the size of the VTable's symbol in bytes, including its prefix,
+	/// as reported by the symbol table of the binary.
+	/// 
+	/// # Safety
+	/// See [`RuntimeVTable::builder`].
+	/// 
+	/// # Panics
+	/// Panics if the symbol has fewer slots than there are in `VTable`,
+	/// or if `VTable` is aligned more strictly than a pointer.
+	pub unsafe fn builder_from_symbol_size(
+		base: VTablePtr<VTable>,
+		prefix: VTablePrefix,
+		symbol_size: usize,
+	) -> RuntimeVTableBuilder<VTable> {
+		let slot_count = (symbol_size / size_of::<usize>()).saturating_sub(prefix.words());
+		unsafe { Self::builder(base, prefix, slot_count) }
+	}
+
+	/// Return the VTable pointer that objects using this VTable have.
+	pub fn as_ptr(&self) -> VTablePtr<VTable> {
+		let address_point = self.header.words[self.header.prefix..].as_ptr();
+		// SAFETY: `builder` checked that the slots are large and aligned enough for a `VTable`,
+		// and they are a copy of a valid one.
+		unsafe { VTablePtr::new(NonNull::new_unchecked(address_point as *mut VTable)) }
+	}
+
+	/// Return the VTable that this VTable was copied from,
+	/// which can be used to call the base implementations of replaced slots.
+	pub fn base(&self) -> &VTable {
+		self.header.base.as_ref()
+	}
+
+	/// Return the slots of this VTable, including any slots not declared in `VTable`.
+	pub fn slots(&self) -> &[usize] {
+		&self.header.words[self.header.prefix..]
+	}
+
+	/// Make `object` use this VTable, and keep this VTable alive
+	/// until it is uninstalled.
+	/// 
+	/// Returns `false` without doing anything if `object` already uses this VTable.
+	/// 
+	/// # Safety
+	/// `object` must point to a live C++ object that has a VTable compatible
+	/// with this VTable, and this VTable should be uninstalled from it
+	/// with [`RuntimeVTable::uninstall`] before it is destroyed,
+	/// for example from a replaced destructor slot;
+	/// otherwise, this VTable is leaked.
+	pub unsafe fn install(&self, object: VtObjectPtr<VTable>) -> bool {
+		unsafe {
+			if *object.as_ref() == self.as_ptr() {
+				return false;
+			}
+			// The reference is released by `uninstall`.
+			let _ = Arc::into_raw(Arc::clone(&self.header));
+			object.write(self.as_ptr());
+		}
+		true
+	}
+
+	/// Restore the original VTable of `object`, and release its reference to this VTable.
+	/// 
+	/// Returns `false` without doing anything if `object` does not use this VTable.
+	/// 
+	/// # Safety
+	/// `object` must point to a live C++ object.
+	pub unsafe fn uninstall(&self, object: VtObjectPtr<VTable>) -> bool {
+		unsafe {
+			if *object.as_ref() != self.as_ptr() {
+				return false;
+			}
+			object.write(VTablePtr::new(self.header.base.as_ptr()));
+			// SAFETY: `install` leaked a reference for this object.
+			Arc::decrement_strong_count(Arc::as_ptr(&self.header));
+		}
+		true
+	}
+
+	/// Return `true` if `object` uses this VTable.
+	pub fn is_installed(&self, object: &VtObject<VTable>) -> bool {
+		*object.vtable_ptr() == self.as_ptr()
+	}
+}
+
+impl<VTable> RuntimeVTableBuilder<VTable> {
+	/// Return the VTable that is being copied.
+	pub fn base(&self) -> &VTable {
+		self.base.as_ref()
+	}
+
+	/// Return the prefix of the copy, such as the RTTI pointer.
+	/// 
+	/// # Safety
+	/// Only values that C++ expects in the prefix may be written,
+	/// since `typeid` and `dynamic_cast` read the offset-to-top and the RTTI pointer.
+	pub unsafe fn prefix_mut(&mut self) -> &mut [usize] {
+		&mut self.words[..self.prefix]
+	}
+
+	/// Return the slots of the copy, including any slots not declared in `VTable`.
+	pub fn slots(&self) -> &[usize] {
+		&self.words[self.prefix..]
+	}
+
+	/// Return the slots of the copy as a mutable slice of raw pointers.
+	/// 
+	/// # Safety
+	/// Only pointers to functions with the signature that C++ expects
+	/// for the corresponding slot may be written.
+	pub unsafe fn slots_mut(&mut self) -> &mut [usize] {
+		&mut self.words[self.prefix..]
+	}
+
+	/// Return the copy as a mutable `VTable`, so that its slots can be replaced.
+	pub fn vtable_mut(&mut self) -> &mut VTable {
+		// SAFETY: `builder` checked that the slots are a copy of a valid `VTable`.
+		unsafe { &mut *self.words[self.prefix..].as_mut_ptr().cast::<VTable>() }
+	}
+
+	/// Finish building the VTable.
+	pub fn build(self) -> RuntimeVTable<VTable> {
+		RuntimeVTable {
+			header: Arc::new(Header {
+				base: self.base,
+				prefix: self.prefix,
+				words: self.words.into_boxed_slice(),
+			}),
+		}
+	}
+}
@@ -0,0 +1,914 @@
+use ::core::mem;
+use ::alloc::{
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+use crate::{ClassName, NamePart};
+
+const CONST: u8 = 1;
+const VOLATILE: u8 = 2;
+const RESTRICT: u8 = 4;
+
+/// Maximum nesting of names and types, to bound recursion on malformed symbols.
+const MAX_DEPTH: usize = 256;
+/// Maximum length of a name or parameter list, since back references can make them grow exponentially.
+const MAX_LEN: usize = 1 << 16;
+/// Number of names and parameter types that back references can refer to.
+const MAX_BACKREFS: usize = 10;
+
+const OPERATORS: &[(&str, &str)] = &[
+	("2", "operator new"), ("3", "operator delete"), ("4", "operator="), ("5", "operator>>"), ("6", "operator<<"),
+	("7", "operator!"), ("8", "operator=="), ("9", "operator!="), ("A", "operator[]"), ("C", "operator->"),
+	("D", "operator*"), ("E", "operator++"), ("F", "operator--"), ("G", "operator-"), ("H", "operator+"),
+	("I", "operator&"), ("J", "operator->*"), ("K", "operator/"), ("L", "operator%"), ("M", "operator<"),
+	("N", "operator<="), ("O", "operator>"), ("P", "operator>="), ("Q", "operator,"), ("R", "operator()"),
+	("S", "operator~"), ("T", "operator^"), ("U", "operator|"), ("V", "operator&&"), ("W", "operator||"),
+	("X", "operator*="), ("Y", "operator+="), ("Z", "operator-="),
+	("_0", "operator/="), ("_1", "operator%="), ("_2", "operator>>="), ("_3", "operator<<="),
+	("_4", "operator&="), ("_5", "operator|="), ("_6", "operator^="),
+	("_7", "`vftable'"), ("_8", "`vbtable'"), ("_D", "`vbase dtor'"),
+	("_E", "`vector deleting dtor'"), ("_F", "`default ctor closure'"), ("_G", "`scalar deleting dtor'"),
+	("_H", "`vector ctor iterator'"), ("_I", "`vector dtor iterator'"), ("_J", "`vector vbase ctor iterator'"),
+	("_K", "`virtual displacement map'"), ("_L", "`eh vector ctor iterator'"), ("_M", "`eh vector dtor iterator'"),
+	("_N", "`eh vector vbase ctor iterator'"), ("_O", "`copy ctor closure'"),
+	("_S", "`local vftable'"), ("_T", "`local vftable ctor closure'"),
+	("_U", "operator new[]"), ("_V", "operator delete[]"),
+	("_X", "`placement delete closure'"), ("_Y", "`placement delete[] closure'"),
+	("__L", "operator co_await"), ("__M", "operator<=>"),
+];
+
+/// Append qualifiers, separated by spaces except directly after a pointer or reference.
+fn push_quals(out: &mut String, quals: u8) {
+	for (qual, name) in [(CONST, "const"), (VOLATILE, "volatile"), (RESTRICT, "__restrict")] {
+		if quals & qual != 0 {
+			if !out.ends_with(['*', '&']) {
+				out.push(' ');
+			}
+			out.push_str(name);
+		}
+	}
+}
+
+/// Append a space before the next word, unless it directly follows a pointer or parenthesis.
+fn push_space(out: &mut String) {
+	if !out.is_empty() && !out.ends_with(['*', '&', '(', ' ']) {
+		out.push(' ');
+	}
+}
+
+#[derive(Debug, Clone)]
+enum Type {
+	/// A type printed as is, such as `int` or `class zoo::Lizard`.
+	Name { name: String, quals: u8 },
+	/// A pointer or reference, to a member of `class` if it is given.
+	Pointer { inner: Box<Type>, sigil: &'static str, quals: u8, class: Option<String> },
+	Function(Box<Function>),
+	Array { inner: Box<Type>, dims: String },
+}
+
+#[derive(Debug, Clone)]
+struct Function {
+	/// The return type, or `None` for constructors and destructors.
+	ret: Option<Type>,
+	convention: &'static str,
+	params: String,
+	/// Qualifiers of `this`, such as ` const &`.
+	quals: String,
+}
+
+impl Type {
+	fn qualify(&mut self, quals: u8) {
+		match self {
+			Type::Name { quals: old, .. } | Type::Pointer { quals: old, .. } => *old |= quals,
+			Type::Array { inner, .. } => inner.qualify(quals),
+			Type::Function(_) => {}
+		}
+	}
+
+	fn has_parens(&self) -> bool {
+		matches!(self, Type::Function(_) | Type::Array { .. })
+	}
+
+	/// Print the part of the type before the name of a declaration.
+	fn left(&self, out: &mut String) {
+		match self {
+			Type::Name { name, quals } => {
+				out.push_str(name);
+				push_quals(out, *quals);
+			}
+			Type::Pointer { inner, sigil, quals, class } => {
+				inner.left(out);
+				if inner.has_parens() {
+					match &**inner {
+						Type::Function(_) if !out.is_empty() => out.push(' '),
+						_ => push_space(out),
+					}
+					out.push('(');
+				} else {
+					push_space(out);
+				}
+				if let Type::Function(function) = &**inner {
+					out.push_str(function.convention);
+					out.push(' ');
+				}
+				if let Some(class) = class {
+					out.push_str(class);
+					out.push_str("::");
+				}
+				out.push_str(sigil);
+				push_quals(out, *quals);
+			}
+			Type::Function(function) => {
+				if let Some(ret) = &function.ret {
+					ret.left(out);
+				}
+			}
+			Type::Array { inner, .. } => inner.left(out),
+		}
+	}
+
+	/// Print the part of the type after the name of a declaration.
+	fn right(&self, out: &mut String) {
+		match self {
+			Type::Name { .. } => {}
+			Type::Pointer { inner, .. } => {
+				if inner.has_parens() {
+					out.push(')');
+				}
+				inner.right(out);
+			}
+			Type::Function(function) => {
+				out.push('(');
+				out.push_str(&function.params);
+				out.push(')');
+				out.push_str(&function.quals);
+				if let Some(ret) = &function.ret {
+					ret.right(out);
+				}
+			}
+			Type::Array { inner, dims } => {
+				out.push_str(dims);
+				inner.right(out);
+			}
+		}
+	}
+
+	/// Print the type on its own.
+	fn print(&self) -> String {
+		let mut out = String::new();
+		self.left(&mut out);
+		if let Type::Function(function) = self {
+			push_space(&mut out);
+			out.push_str(function.convention);
+		}
+		self.right(&mut out);
+		out
+	}
+
+	/// Print a declaration of `name` with the type.
+	fn declare(&self, name: &str) -> String {
+		let mut out = String::new();
+		self.left(&mut out);
+		push_space(&mut out);
+		out.push_str(name);
+		self.right(&mut out);
+		out
+	}
+}
+
+/// A name at the start of a symbol that is not an identifier.
+enum Special {
+	Name(String),
+	Constructor,
+	Destructor,
+	Conversion,
+}
+
+struct Parser<'a> {
+	input: &'a [u8],
+	pos: usize,
+	depth: usize,
+	/// The names that the back references `0` to `9` refer to.
+	names: Vec<NamePart>,
+	/// The parameter types that the back references `0` to `9` refer to.
+	params: Vec<Type>,
+}
+
+impl<'a> Parser<'a> {
+	fn new(input: &'a str) -> Self {
+		Self { input: input.as_bytes(), pos: 0, depth: 0, names: Vec::new(), params: Vec::new() }
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.input.get(self.pos).copied()
+	}
+
+	fn next(&mut self) -> Option<u8> {
+		let byte = self.peek()?;
+		self.pos += 1;
+		Some(byte)
+	}
+
+	fn eat(&mut self, byte: u8) -> bool {
+		let eaten = self.peek() == Some(byte);
+		if eaten {
+			self.pos += 1;
+		}
+		eaten
+	}
+
+	fn eat_str(&mut self, s: &str) -> bool {
+		let eaten = self.rest().starts_with(s.as_bytes());
+		if eaten {
+			self.pos += s.len();
+		}
+		eaten
+	}
+
+	fn expect(&mut self, byte: u8) -> Option<()> {
+		self.eat(byte).then_some(())
+	}
+
+	fn rest(&self) -> &'a [u8] {
+		&self.input[self.pos..]
+	}
+
+	fn enter(&mut self) -> Option<()> {
+		self.depth += 1;
+		(self.depth <= MAX_DEPTH).then_some(())
+	}
+
+	/// `<number> ::= [?] <digit> | [?] <hex digit A-P>+ @`, where a digit is one less than its value.
+	fn number(&mut self) -> Option<i64> {
+		let negative = self.eat(b'?');
+		let value = match self.next()? {
+			digit @ b'0'..=b'9' => u64::from(digit - b'0') + 1,
+			digit @ b'A'..=b'P' => {
+				let mut value = u64::from(digit - b'A');
+				loop {
+					match self.next()? {
+						b'@' => break value,
+						digit @ b'A'..=b'P' => value = value.checked_mul(16)? | u64::from(digit - b'A'),
+						_ => return None,
+					}
+				}
+			}
+			_ => return None,
+		};
+		let value = value as i64;
+		Some(if negative { value.wrapping_neg() } else { value })
+	}
+
+	fn identifier(&mut self) -> Option<String> {
+		let len = self.rest().iter().position(|&byte| byte == b'@')?;
+		if len == 0 {
+			return None;
+		}
+		let name = ::core::str::from_utf8(&self.rest()[..len]).ok()?.to_string();
+		self.pos += len + 1;
+		Some(name)
+	}
+
+	/// `const` and `volatile`, as `A` to `D`, or `Q` to `T` for members, which are followed by their class.
+	fn cv(&mut self) -> Option<u8> {
+		Some(match self.next()? {
+			b'A' | b'Q' => 0,
+			b'B' | b'R' => CONST,
+			b'C' | b'S' => VOLATILE,
+			b'D' | b'T' => CONST | VOLATILE,
+			_ => return None,
+		})
+	}
+
+	/// `__ptr64`, `__unaligned` and `__restrict`, of which only the last is printed.
+	fn ext_quals(&mut self) -> u8 {
+		let mut quals = 0;
+		loop {
+			match self.peek() {
+				Some(b'E' | b'F') => {}
+				Some(b'I') => quals |= RESTRICT,
+				_ => return quals,
+			}
+			self.pos += 1;
+		}
+	}
+
+	fn remember_name(&mut self, part: &NamePart) {
+		if self.names.len() < MAX_BACKREFS && !self.names.contains(part) {
+			self.names.push(part.clone());
+		}
+	}
+
+	fn operator_name(&mut self) -> Option<&'static str> {
+		let &(code, name) = OPERATORS.iter().find(|(code, _)| self.rest().starts_with(code.as_bytes()))?;
+		self.pos += code.len();
+		Some(name)
+	}
+
+	/// Parse the name of the scope or entity in a qualified name,
+	/// appending it to `parts`, which are ordered from the innermost name.
+	fn name_part(&mut self, parts: &mut Vec<NamePart>) -> Option<()> {
+		match self.peek()? {
+			digit @ b'0'..=b'9' => {
+				self.pos += 1;
+				parts.push(self.names.get(usize::from(digit - b'0'))?.clone());
+			}
+			b'?' if self.rest().starts_with(b"?$") => {
+				let part = self.template_name()?;
+				self.remember_name(&part);
+				parts.push(part);
+			}
+			b'?' if self.rest().starts_with(b"?A0x") || self.rest().starts_with(b"?A@") => {
+				self.pos += 2;
+				self.identifier().or_else(|| self.eat(b'@').then(String::new))?;
+				let part = NamePart { name: "`anonymous namespace'".to_string(), template_args: Vec::new() };
+				self.remember_name(&part);
+				parts.push(part);
+			}
+			b'?' => {
+				// A name declared within a function, numbered by its scope within the function.
+				self.pos += 1;
+				let scope = self.number()?;
+				self.expect(b'?')?;
+				let (function, _) = self.nested_symbol()?;
+				parts.push(NamePart { name: format!("`{scope}'"), template_args: Vec::new() });
+				parts.push(NamePart { name: format!("`{function}'"), template_args: Vec::new() });
+			}
+			_ => {
+				let part = NamePart { name: self.identifier()?, template_args: Vec::new() };
+				self.remember_name(&part);
+				parts.push(part);
+			}
+		}
+		Some(())
+	}
+
+	/// Parse the rest of a qualified name up to its terminating `@`.
+	fn scope(&mut self, parts: &mut Vec<NamePart>) -> Option<()> {
+		while !self.eat(b'@') {
+			self.name_part(parts)?;
+		}
+		Some(())
+	}
+
+	/// Parse a qualified name, such as `Lizard@zoo@@`, returning its parts from the outermost.
+	fn qualified_name(&mut self) -> Option<Vec<NamePart>> {
+		self.enter()?;
+		let mut parts = Vec::new();
+		let name = self.name_part(&mut parts).and_then(|()| self.scope(&mut parts));
+		self.depth -= 1;
+		name?;
+		parts.reverse();
+		Some(parts)
+	}
+
+	/// Parse a template name, such as `?$Pen@H@`, which has its own back references.
+	fn template_name(&mut self) -> Option<NamePart> {
+		self.enter()?;
+		self.pos += 2;
+		let names = mem::take(&mut self.names);
+		let params = mem::take(&mut self.params);
+		let part = self.template_name_inner();
+		self.names = names;
+		self.params = params;
+		self.depth -= 1;
+		part
+	}
+
+	fn template_name_inner(&mut self) -> Option<NamePart> {
+		let name = match self.eat(b'?') {
+			true => self.operator_name()?.to_string(),
+			false => self.identifier()?,
+		};
+		self.remember_name(&NamePart { name: name.clone(), template_args: Vec::new() });
+		let mut template_args = Vec::new();
+		let mut len = name.len();
+		while !self.eat(b'@') {
+			// Empty parameter packs.
+			if self.eat_str("$$V") || self.eat_str("$$$V") || self.eat_str("$$Z") {
+				continue;
+			}
+			let arg = if self.eat_str("$0") {
+				self.number()?.to_string()
+			} else if self.eat_str("$1") {
+				format!("&{}", ClassName { path: self.nested_symbol()?.1 })
+			} else if self.eat_str("$E") {
+				ClassName { path: self.nested_symbol()?.1 }.to_string()
+			} else {
+				self.type_()?.print()
+			};
+			len += arg.len();
+			if len > MAX_LEN {
+				return None;
+			}
+			template_args.push(arg);
+		}
+		Some(NamePart { name, template_args })
+	}
+
+	/// Parse a symbol within a symbol, which has its own back references.
+	fn nested_symbol(&mut self) -> Option<(String, Vec<NamePart>)> {
+		let names = mem::take(&mut self.names);
+		let params = mem::take(&mut self.params);
+		let symbol = self.symbol();
+		self.names = names;
+		self.params = params;
+		symbol
+	}
+
+	fn special_name(&mut self) -> Option<Special> {
+		let special = match self.peek()? {
+			b'0' => Special::Constructor,
+			b'1' => Special::Destructor,
+			b'B' => Special::Conversion,
+			_ if self.eat_str("_R1") => {
+				let mut numbers = [0; 4];
+				for number in &mut numbers {
+					*number = self.number()?;
+				}
+				let [a, b, c, d] = numbers;
+				return Some(Special::Name(format!("`RTTI Base Class Descriptor at ({a}, {b}, {c}, {d})'")));
+			}
+			_ if self.eat_str("_R2") => return Some(Special::Name("`RTTI Base Class Array'".to_string())),
+			_ if self.eat_str("_R3") => return Some(Special::Name("`RTTI Class Hierarchy Descriptor'".to_string())),
+			_ if self.eat_str("_R4") => return Some(Special::Name("`RTTI Complete Object Locator'".to_string())),
+			_ => return Some(Special::Name(self.operator_name()?.to_string())),
+		};
+		self.pos += 1;
+		Some(special)
+	}
+
+	/// Parse a symbol, returning its undecorated form and its qualified name.
+	fn symbol(&mut self) -> Option<(String, Vec<NamePart>)> {
+		self.enter()?;
+		let symbol = self.symbol_inner();
+		self.depth -= 1;
+		symbol
+	}
+
+	fn symbol_inner(&mut self) -> Option<(String, Vec<NamePart>)> {
+		self.expect(b'?')?;
+		if self.eat_str("?_R0") {
+			let ty = self.type_()?;
+			self.eat_str("@8").then_some(())?;
+			return Some((format!("{} `RTTI Type Descriptor'", ty.print()), Vec::new()));
+		}
+		if self.eat_str("?_C@_") {
+			// The contents of string literals are truncated and hashed, so they are not worth decoding.
+			self.pos = self.input.len();
+			return Some(("`string'".to_string(), Vec::new()));
+		}
+		let special = match self.rest().starts_with(b"?$") {
+			false if self.eat(b'?') => Some(self.special_name()?),
+			_ => None,
+		};
+		let mut parts = Vec::new();
+		if special.is_none() {
+			self.name_part(&mut parts)?;
+		}
+		self.scope(&mut parts)?;
+		parts.reverse();
+		let kind = self.next()?;
+		if let Some(special) = special {
+			let class = parts.last();
+			let name = match special {
+				Special::Name(name) => name,
+				Special::Constructor => return self.function(kind, parts.clone(), class?.clone()),
+				Special::Destructor => {
+					let class = class?;
+					format!("~{}", class.name)
+				}
+				Special::Conversion => "operator".to_string(),
+			};
+			let template_args = match name.starts_with('~') {
+				true => class?.template_args.clone(),
+				false => Vec::new(),
+			};
+			return self.special_symbol(kind, parts, NamePart { name, template_args });
+		}
+		self.entity(kind, parts)
+	}
+
+	/// Parse the rest of a symbol named by a special name, which is appended to its scope.
+	fn special_symbol(&mut self, kind: u8, parts: Vec<NamePart>, name: NamePart) -> Option<(String, Vec<NamePart>)> {
+		let mut path = parts;
+		path.push(name);
+		match kind {
+			b'6' | b'7' => {
+				let quals = self.cv()?;
+				let mut bases = Vec::new();
+				while !self.eat(b'@') {
+					bases.push(ClassName { path: self.qualified_name()? }.to_string());
+				}
+				let mut out = String::new();
+				push_quals(&mut out, quals);
+				push_space(&mut out);
+				out.push_str(&ClassName { path: path.clone() }.to_string());
+				if !bases.is_empty() {
+					out.push_str("{for `");
+					out.push_str(&bases.join("'s `"));
+					out.push_str("'}");
+				}
+				Some((out.trim_start().to_string(), path))
+			}
+			b'8' => Some((ClassName { path: path.clone() }.to_string(), path)),
+			_ => {
+				let last = path.pop()?;
+				self.function(kind, path, last)
+			}
+		}
+	}
+
+	/// Parse the rest of a symbol named by identifiers.
+	fn entity(&mut self, kind: u8, mut parts: Vec<NamePart>) -> Option<(String, Vec<NamePart>)> {
+		match kind {
+			b'0'..=b'4' => {
+				let access = ["private: static ", "protected: static ", "public: static ", "", ""][usize::from(kind - b'0')];
+				let mut ty = self.type_()?;
+				self.ext_quals();
+				let member = matches!(self.peek()?, b'Q'..=b'T');
+				let quals = self.cv()?;
+				if member {
+					self.qualified_name()?;
+				}
+				match &mut ty {
+					Type::Pointer { inner, .. } => inner.qualify(quals),
+					ty => ty.qualify(quals),
+				}
+				let name = ClassName { path: parts.clone() }.to_string();
+				Some((format!("{access}{}", ty.declare(&name)), parts))
+			}
+			_ => {
+				let last = parts.pop()?;
+				self.function(kind, parts, last)
+			}
+		}
+	}
+
+	/// Parse the rest of a function symbol, from its access and kind.
+	fn function(&mut self, kind: u8, scope: Vec<NamePart>, name: NamePart) -> Option<(String, Vec<NamePart>)> {
+		let mut prefix = String::new();
+		let mut adjustor = String::new();
+		let member = match kind {
+			b'A'..=b'X' => {
+				let index = usize::from(kind - b'A');
+				prefix.push_str(["private: ", "protected: ", "public: "][index / 8]);
+				match index % 8 / 2 {
+					0 => true,
+					1 => {
+						prefix.push_str("static ");
+						false
+					}
+					2 => {
+						prefix.push_str("virtual ");
+						true
+					}
+					_ => {
+						adjustor = format!("`adjustor{{{}}}'", self.number()?);
+						prefix.insert_str(0, "[thunk]: ");
+						prefix.push_str("virtual ");
+						true
+					}
+				}
+			}
+			b'Y' | b'Z' => false,
+			b'$' => {
+				let extended = self.eat(b'R');
+				let access = match self.next()? {
+					digit @ b'0'..=b'5' => usize::from(digit - b'0') / 2,
+					_ => return None,
+				};
+				let count = if extended { 4 } else { 2 };
+				let mut numbers = Vec::new();
+				for _ in 0..count {
+					numbers.push((self.number()? as i32).to_string());
+				}
+				let kind = if extended { "vtordispex" } else { "vtordisp" };
+				adjustor = format!("`{kind}{{{}}}'", numbers.join(", "));
+				prefix = format!("[thunk]: {}virtual ", ["private: ", "protected: ", "public: "][access]);
+				true
+			}
+			_ => return None,
+		};
+		let quals = match member {
+			true => self.this_quals()?,
+			false => String::new(),
+		};
+		let mut function = self.function_type()?;
+		function.quals = quals;
+		let mut path = scope;
+		path.push(name);
+		if path.last()?.name == "operator" {
+			let conversion = function.ret.as_ref()?.print();
+			path.last_mut()?.name = format!("operator {conversion}");
+		}
+		let mut name = ClassName { path: path.clone() }.to_string();
+		name.push_str(&adjustor);
+		let mut out = prefix;
+		if let Some(ret) = &function.ret {
+			ret.left(&mut out);
+			out.push(' ');
+		}
+		out.push_str(function.convention);
+		out.push(' ');
+		out.push_str(&name);
+		out.push('(');
+		out.push_str(&function.params);
+		out.push(')');
+		out.push_str(&function.quals);
+		if let Some(ret) = &function.ret {
+			ret.right(&mut out);
+		}
+		Some((out, path))
+	}
+
+	/// Parse the qualifiers of `this` in a member function.
+	fn this_quals(&mut self) -> Option<String> {
+		let mut quals = self.ext_quals();
+		let ref_qual = if self.eat(b'G') {
+			" &"
+		} else if self.eat(b'H') {
+			" &&"
+		} else {
+			""
+		};
+		quals |= self.cv()?;
+		let mut out = String::new();
+		push_quals(&mut out, quals);
+		out.push_str(ref_qual);
+		Some(out)
+	}
+
+	/// Parse a calling convention, return type and parameters.
+	fn function_type(&mut self) -> Option<Function> {
+		let convention = match self.next()? {
+			b'A' | b'B' => "__cdecl",
+			b'C' | b'D' => "__pascal",
+			b'E' | b'F' => "__thiscall",
+			b'G' | b'H' => "__stdcall",
+			b'I' | b'J' => "__fastcall",
+			b'M' | b'N' => "__clrcall",
+			b'O' | b'P' => "__eabi",
+			b'Q' => "__vectorcall",
+			_ => return None,
+		};
+		let ret = match self.peek()? {
+			b'@' => {
+				self.pos += 1;
+				None
+			}
+			b'?' => {
+				self.pos += 1;
+				let quals = self.cv()?;
+				let mut ret = self.type_()?;
+				ret.qualify(quals);
+				Some(ret)
+			}
+			_ => Some(self.type_()?),
+		};
+		let params = self.params()?;
+		self.expect(b'Z')?;
+		// `noexcept`, which is not printed.
+		self.eat_str("_E");
+		Some(Function { ret, convention, params, quals: String::new() })
+	}
+
+	fn params(&mut self) -> Option<String> {
+		if self.eat(b'X') {
+			return Some("void".to_string());
+		}
+		let mut params = String::new();
+		loop {
+			let param = match self.peek()? {
+				b'@' => {
+					self.pos += 1;
+					return Some(params);
+				}
+				b'Z' => {
+					self.pos += 1;
+					"...".to_string()
+				}
+				digit @ b'0'..=b'9' => {
+					self.pos += 1;
+					self.params.get(usize::from(digit - b'0'))?.print()
+				}
+				_ => {
+					let start = self.pos;
+					let ty = self.type_()?;
+					// Only types with encodings longer than one character can be referred back to.
+					if self.pos - start > 1 && self.params.len() < MAX_BACKREFS {
+						self.params.push(ty.clone());
+					}
+					ty.print()
+				}
+			};
+			if !params.is_empty() {
+				params.push_str(", ");
+			}
+			params.push_str(&param);
+			if params.len() > MAX_LEN {
+				return None;
+			}
+			if param == "..." {
+				return Some(params);
+			}
+		}
+	}
+
+	fn type_(&mut self) -> Option<Type> {
+		self.enter()?;
+		let ty = self.type_inner();
+		self.depth -= 1;
+		ty
+	}
+
+	fn type_inner(&mut self) -> Option<Type> {
+		let name = |name: &str| Some(Type::Name { name: name.to_string(), quals: 0 });
+		if self.eat(b'?') || self.eat_str("$$C") {
+			let quals = self.cv()?;
+			let mut ty = self.type_()?;
+			ty.qualify(quals);
+			return Some(ty);
+		}
+		if self.eat_str("$$Q") {
+			return self.pointer("&&", 0);
+		}
+		if self.eat_str("$$R") {
+			return self.pointer("&&", VOLATILE);
+		}
+		if self.eat_str("$$T") {
+			return name("std::nullptr_t");
+		}
+		if self.eat_str("$$A6") {
+			return Some(Type::Function(Box::new(self.function_type()?)));
+		}
+		if self.eat_str("$$B") {
+			return self.type_();
+		}
+		match self.next()? {
+			b'C' => name("signed char"),
+			b'D' => name("char"),
+			b'E' => name("unsigned char"),
+			b'F' => name("short"),
+			b'G' => name("unsigned short"),
+			b'H' => name("int"),
+			b'I' => name("unsigned int"),
+			b'J' => name("long"),
+			b'K' => name("unsigned long"),
+			b'M' => name("float"),
+			b'N' => name("double"),
+			b'O' => name("long double"),
+			b'X' => name("void"),
+			b'_' => match self.next()? {
+				b'N' => name("bool"),
+				b'J' => name("__int64"),
+				b'K' => name("unsigned __int64"),
+				b'W' => name("wchar_t"),
+				b'S' => name("char16_t"),
+				b'U' => name("char32_t"),
+				b'Q' => name("char8_t"),
+				_ => None,
+			},
+			tag @ (b'T' | b'U' | b'V' | b'W') => {
+				if tag == b'W' && !self.next()?.is_ascii_digit() {
+					return None;
+				}
+				let keyword = match tag {
+					b'T' => "union",
+					b'U' => "struct",
+					b'V' => "class",
+					_ => "enum",
+				};
+				name(&format!("{keyword} {}", ClassName { path: self.qualified_name()? }))
+			}
+			b'P' => self.pointer("*", 0),
+			b'Q' => self.pointer("*", CONST),
+			b'R' => self.pointer("*", VOLATILE),
+			b'S' => self.pointer("*", CONST | VOLATILE),
+			b'A' => self.pointer("&", 0),
+			b'B' => self.pointer("&", VOLATILE),
+			b'Y' => {
+				let count = self.number()?;
+				let mut dims = String::new();
+				for _ in 0..count {
+					dims.push_str(&format!("[{}]", self.number()?));
+				}
+				Some(Type::Array { inner: Box::new(self.type_()?), dims })
+			}
+			_ => None,
+		}
+	}
+
+	fn pointer(&mut self, sigil: &'static str, quals: u8) -> Option<Type> {
+		let pointer = |inner, quals, class| Some(Type::Pointer { inner: Box::new(inner), sigil, quals, class });
+		if self.eat(b'6') {
+			return pointer(Type::Function(Box::new(self.function_type()?)), quals, None);
+		}
+		if self.eat(b'8') {
+			let class = ClassName { path: self.qualified_name()? }.to_string();
+			let this = self.this_quals()?;
+			let mut function = self.function_type()?;
+			function.quals = this;
+			return pointer(Type::Function(Box::new(function)), quals, Some(class));
+		}
+		let quals = quals | self.ext_quals();
+		// Pointers to data members use `Q` to `T` instead of `A` to `D`.
+		let member = matches!(self.peek()?, b'Q'..=b'T');
+		let inner_quals = self.cv()?;
+		let class = match member {
+			true => Some(ClassName { path: self.qualified_name()? }.to_string()),
+			false => None,
+		};
+		let mut inner = self.type_()?;
+		inner.qualify(inner_quals);
+		pointer(inner, quals, class)
+	}
+
+	/// Parse a decorated class type, such as `?AVLizard@zoo@@`, returning its qualified name.
+	fn class_type(&mut self) -> Option<Vec<NamePart>> {
+		if self.eat(b'?') {
+			self.cv()?;
+		}
+		match self.next()? {
+			b'T' | b'U' | b'V' => {}
+			b'W' if self.next()?.is_ascii_digit() => {}
+			_ => return None,
+		}
+		self.qualified_name()
+	}
+}
+
+
+/// Undecorate a symbol decorated by MSVC, such as `?speak@Lizard@zoo@@UEAAXXZ`,
+/// in the format of `undname`.
+/// 
+/// RTTI type descriptor names, such as `.?AVLizard@zoo@@`, are also accepted.
+/// Returns `None` if the symbol is not decorated or uses unsupported features.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::undecorate;
+/// assert_eq!(undecorate("?speak@Lizard@zoo@@UEAAXXZ").as_deref(), Some("public: virtual void __cdecl zoo::Lizard::speak(void)"));
+/// assert_eq!(undecorate("?legs@Pet@zoo@@UEBAHXZ").as_deref(), Some("public: virtual int __cdecl zoo::Pet::legs(void) const"));
+/// assert_eq!(undecorate("??1Lizard@zoo@@UEAA@XZ").as_deref(), Some("public: virtual __cdecl zoo::Lizard::~Lizard(void)"));
+/// assert_eq!(undecorate("??_7CBaseEntity@@6B@").as_deref(), Some("const CBaseEntity::`vftable'"));
+/// assert_eq!(undecorate("??_7Lizard@zoo@@6BScaly@1@@").as_deref(), Some("const zoo::Lizard::`vftable'{for `zoo::Scaly'}"));
+/// assert_eq!(undecorate("?f@@YAXP6AHH@ZAEBV?$Pen@H@zoo@@@Z").as_deref(), Some("void __cdecl f(int (__cdecl *)(int), class zoo::Pen<int> const &)"));
+/// assert_eq!(undecorate(".?AVLizard@zoo@@").as_deref(), Some("class zoo::Lizard `RTTI Type Descriptor Name'"));
+/// assert_eq!(undecorate("_ZN3zoo6Lizard5speakEv"), None);
+/// ```
+pub fn undecorate(symbol: &str) -> Option<String> {
+	if let Some(name) = symbol.strip_prefix('.') {
+		return Some(format!("{} `RTTI Type Descriptor Name'", undecorate_type(name)?));
+	}
+	let mut parser = Parser::new(symbol);
+	let (out, _) = parser.symbol()?;
+	parser.rest().is_empty().then_some(out)
+}
+
+/// Undecorate a type decorated by MSVC, such as the name `.?AVLizard@zoo@@` of an RTTI type descriptor.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt::undecorate_type;
+/// assert_eq!(undecorate_type(".?AVLizard@zoo@@").as_deref(), Some("class zoo::Lizard"));
+/// assert_eq!(undecorate_type("PEBD").as_deref(), Some("char const *"));
+/// ```
+pub fn undecorate_type(decorated: &str) -> Option<String> {
+	let mut parser = Parser::new(decorated.strip_prefix('.').unwrap_or(decorated));
+	let ty = parser.type_()?;
+	parser.rest().is_empty().then(|| ty.print())
+}
+
+impl ClassName {
+	/// Parse a class name decorated by MSVC, such as the name `.?AVLizard@zoo@@`
+	/// of an RTTI type descriptor, or the VTable symbol `??_7Lizard@zoo@@6B@`.
+	/// 
+	/// Returns `None` if the name is not a class name.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::ClassName;
+	/// let name = ClassName::from_msvc(".?AV?$Pen@H@zoo@@").unwrap();
+	/// assert_eq!(name.name(), "Pen");
+	/// assert_eq!(name.path[1].template_args, ["int"]);
+	/// assert_eq!(name.to_string(), "zoo::Pen<int>");
+	/// assert_eq!(ClassName::from_msvc("??_7CBaseEntity@@6B@").unwrap().to_string(), "CBaseEntity");
+	/// assert_eq!(ClassName::from_msvc("?speak@Lizard@zoo@@UEAAXXZ"), None);
+	/// ```
+	pub fn from_msvc(decorated: &str) -> Option<Self> {
+		let decorated = decorated.strip_prefix('.').unwrap_or(decorated);
+		let mut parser = Parser::new(decorated);
+		let path = if decorated.starts_with("??_7") {
+			let (_, mut path) = parser.symbol()?;
+			path.pop();
+			path
+		} else {
+			parser.class_type()?
+		};
+		(parser.rest().is_empty() && !path.is_empty()).then_some(Self { path })
+	}
+}
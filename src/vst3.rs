@@ -0,0 +1,692 @@
+use ::core::{
+	ffi::{
+		c_char, c_void, CStr,
+	},
+	mem::MaybeUninit,
+	ptr::{
+		self, NonNull,
+	},
+	sync::atomic::{
+		AtomicPtr, Ordering,
+	},
+};
+
+use super::{
+	com_vtable, ComClass, ComObject, ComPtr, CreateObjectFn, GUID, HRESULT, IUnknownVt, Interface, VTablePtr, VtObjectPtr,
+};
+
+/// Result code of VST3 methods, `tresult`.
+/// 
+/// The codes are [`HRESULT`]s on Windows, and small integers elsewhere.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct TResult(pub i32);
+
+impl TResult {
+	/// Success, `kResultOk`.
+	pub const OK: Self = Self(0);
+	/// Success with a result of "true", `kResultTrue`.
+	pub const TRUE: Self = Self(0);
+	/// Success with a result of "false", `kResultFalse`.
+	pub const FALSE: Self = Self(1);
+	/// The object does not support the requested interface, `kNoInterface`.
+	#[cfg(windows)]
+	pub const NO_INTERFACE: Self = Self(HRESULT::E_NOINTERFACE.0);
+	/// The object does not support the requested interface, `kNoInterface`.
+	#[cfg(not(windows))]
+	pub const NO_INTERFACE: Self = Self(-1);
+	/// An argument is invalid, `kInvalidArgument`.
+	#[cfg(windows)]
+	pub const INVALID_ARGUMENT: Self = Self(HRESULT::E_INVALIDARG.0);
+	/// An argument is invalid, `kInvalidArgument`.
+	#[cfg(not(windows))]
+	pub const INVALID_ARGUMENT: Self = Self(2);
+	/// The method is not implemented, `kNotImplemented`.
+	#[cfg(windows)]
+	pub const NOT_IMPLEMENTED: Self = Self(HRESULT::E_NOTIMPL.0);
+	/// The method is not implemented, `kNotImplemented`.
+	#[cfg(not(windows))]
+	pub const NOT_IMPLEMENTED: Self = Self(3);
+	/// Unspecified failure, `kInternalError`.
+	#[cfg(windows)]
+	pub const INTERNAL_ERROR: Self = Self(HRESULT::E_FAIL.0);
+	/// Unspecified failure, `kInternalError`.
+	#[cfg(not(windows))]
+	pub const INTERNAL_ERROR: Self = Self(4);
+	/// The object is not initialized, `kNotInitialized`.
+	#[cfg(windows)]
+	pub const NOT_INITIALIZED: Self = Self(HRESULT::E_UNEXPECTED.0);
+	/// The object is not initialized, `kNotInitialized`.
+	#[cfg(not(windows))]
+	pub const NOT_INITIALIZED: Self = Self(5);
+	/// Memory could not be allocated, `kOutOfMemory`.
+	#[cfg(windows)]
+	pub const OUT_OF_MEMORY: Self = Self(HRESULT::E_OUTOFMEMORY.0);
+	/// Memory could not be allocated, `kOutOfMemory`.
+	#[cfg(not(windows))]
+	pub const OUT_OF_MEMORY: Self = Self(6);
+
+	/// Convert the code to a [`Result`].
+	/// 
+	/// # Errors
+	/// Returns `Err(self)` if the code is not [`TResult::OK`].
+	pub const fn ok(self) -> Result<(), Self> {
+		if self.0 == Self::OK.0 {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+
+	/// Return the code as an [`HRESULT`], which is how `FUnknown` returns it.
+	pub const fn as_hresult(self) -> HRESULT {
+		HRESULT(self.0)
+	}
+}
+
+impl From<HRESULT> for TResult {
+	fn from(value: HRESULT) -> Self {
+		Self(value.0)
+	}
+}
+
+impl GUID {
+	/// Create the GUID of a VST3 `TUID` declared with `INLINE_UID(l1, l2, l3, l4)`,
+	/// whose bytes are in the order of COM on Windows, and in big-endian order elsewhere.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::{GUID, IUnknownVt, Interface};
+	/// // `FUnknown` has the IID of `IUnknown` on every platform.
+	/// assert_eq!(GUID::from_vst3_uid(0x00000000, 0x00000000, 0xC0000000, 0x00000046), IUnknownVt::IID);
+	/// 
+	/// let uid = GUID::from_vst3_uid(0x7A4D811C, 0x52114A1F, 0xAED9D2EE, 0x0B43BF9F);
+	/// let first = if cfg!(windows) { 0x1C } else { 0x7A };
+	/// assert_eq!(uid.to_bytes()[0], first);
+	/// ```
+	pub const fn from_vst3_uid(l1: u32, l2: u32, l3: u32, l4: u32) -> Self {
+		let value = (l1 as u128) << 96 | (l2 as u128) << 64 | (l3 as u128) << 32 | l4 as u128;
+		if cfg!(windows) {
+			Self::from_u128(value)
+		} else {
+			Self::from_bytes(value.to_be_bytes())
+		}
+	}
+}
+
+/// VTable of `FUnknown`, which every VST3 interface starts with.
+/// 
+/// `FUnknown` has the layout and IID of `IUnknown`,
+/// but its `queryInterface` returns [`TResult`] codes,
+/// so Rust classes that implement VST3 interfaces set
+/// [`ComClass::NO_INTERFACE`] to [`TResult::NO_INTERFACE`].
+pub type FUnknownVt = IUnknownVt;
+
+/// Value of [`PClassInfo::cardinality`] for classes with any number of instances.
+pub const MANY_INSTANCES: i32 = 0x7FFFFFFF;
+
+/// Information about the vendor of a VST3 plugin factory, `PFactoryInfo`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct PFactoryInfo {
+	/// Name of the vendor.
+	pub vendor: [c_char; 64],
+	/// URL of the vendor.
+	pub url: [c_char; 256],
+	/// Contact e-mail address of the vendor.
+	pub email: [c_char; 128],
+	/// `PFactoryInfo::FactoryFlags`, such as `kUnicode` (16).
+	pub flags: i32,
+}
+
+impl PFactoryInfo {
+	/// Return the name of the vendor.
+	pub fn vendor(&self) -> &CStr {
+		c_str(&self.vendor)
+	}
+
+	/// Return the URL of the vendor.
+	pub fn url(&self) -> &CStr {
+		c_str(&self.url)
+	}
+
+	/// Return the contact e-mail address of the vendor.
+	pub fn email(&self) -> &CStr {
+		c_str(&self.email)
+	}
+}
+
+/// Basic information about a class of a VST3 plugin factory, `PClassInfo`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct PClassInfo {
+	/// Class ID, the `TUID` that creates the class.
+	pub cid: GUID,
+	/// Number of instances that can be created, usually [`MANY_INSTANCES`].
+	pub cardinality: i32,
+	/// Category of the class, such as `Audio Module Class`.
+	pub category: [c_char; 32],
+	/// Name of the class.
+	pub name: [c_char; 64],
+}
+
+impl PClassInfo {
+	/// Return the category of the class.
+	pub fn category(&self) -> &CStr {
+		c_str(&self.category)
+	}
+
+	/// Return the name of the class.
+	pub fn name(&self) -> &CStr {
+		c_str(&self.name)
+	}
+}
+
+/// Extended information about a class of a VST3 plugin factory, `PClassInfo2`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct PClassInfo2 {
+	/// Class ID, the `TUID` that creates the class.
+	pub cid: GUID,
+	/// Number of instances that can be created, usually [`MANY_INSTANCES`].
+	pub cardinality: i32,
+	/// Category of the class, such as `Audio Module Class`.
+	pub category: [c_char; 32],
+	/// Name of the class.
+	pub name: [c_char; 64],
+	/// Flags of the class, such as `kDistributable`.
+	pub class_flags: u32,
+	/// Subcategories separated by `|`, such as `Fx|Delay`.
+	pub sub_categories: [c_char; 128],
+	/// Name of the vendor of the class.
+	pub vendor: [c_char; 64],
+	/// Version of the class.
+	pub version: [c_char; 64],
+	/// Version of the SDK that the class was built with.
+	pub sdk_version: [c_char; 64],
+}
+
+impl PClassInfo2 {
+	/// Return the name of the class.
+	pub fn name(&self) -> &CStr {
+		c_str(&self.name)
+	}
+
+	/// Return the subcategories of the class.
+	pub fn sub_categories(&self) -> &CStr {
+		c_str(&self.sub_categories)
+	}
+
+	/// Return the version of the class.
+	pub fn version(&self) -> &CStr {
+		c_str(&self.version)
+	}
+}
+
+/// Extended information about a class of a VST3 plugin factory
+/// with UTF-16 strings, `PClassInfoW`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct PClassInfoW {
+	/// Class ID, the `TUID` that creates the class.
+	pub cid: GUID,
+	/// Number of instances that can be created, usually [`MANY_INSTANCES`].
+	pub cardinality: i32,
+	/// Category of the class, such as `Audio Module Class`.
+	pub category: [c_char; 32],
+	/// Name of the class, nul-terminated.
+	pub name: [u16; 64],
+	/// Flags of the class, such as `kDistributable`.
+	pub class_flags: u32,
+	/// Subcategories separated by `|`, such as `Fx|Delay`.
+	pub sub_categories: [c_char; 128],
+	/// Name of the vendor of the class, nul-terminated.
+	pub vendor: [u16; 64],
+	/// Version of the class, nul-terminated.
+	pub version: [u16; 64],
+	/// Version of the SDK that the class was built with, nul-terminated.
+	pub sdk_version: [u16; 64],
+}
+
+impl PClassInfoW {
+	/// Return the UTF-16 units of the name of the class, without the nul terminator.
+	pub fn name(&self) -> &[u16] {
+		let len = self.name.iter().position(|&c| c == 0).unwrap_or(self.name.len());
+		&self.name[..len]
+	}
+}
+
+fn c_str(chars: &[c_char]) -> &CStr {
+	// SAFETY: `c_char` has the layout of `u8`.
+	let bytes = unsafe { &*(chars as *const [c_char] as *const [u8]) };
+	CStr::from_bytes_until_nul(bytes).unwrap_or(c"")
+}
+
+/// Return a zeroed information structure, which is valid for all of them.
+fn zeroed<T>() -> T {
+	// SAFETY: Only called for the information structures, which are plain data.
+	unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+fn write_str(dst: &mut [c_char], s: &str) {
+	let mut len = s.len().min(dst.len() - 1);
+	while !s.is_char_boundary(len) {
+		len -= 1;
+	}
+	for (dst, &byte) in dst.iter_mut().zip(&s.as_bytes()[..len]) {
+		*dst = byte as c_char;
+	}
+	dst[len] = 0;
+}
+
+fn write_wide(dst: &mut [u16], s: &str) {
+	let max = dst.len() - 1;
+	let mut len = 0;
+	for c in s.chars() {
+		let mut units = [0; 2];
+		let units = c.encode_utf16(&mut units);
+		if len + units.len() > max {
+			break;
+		}
+		dst[len..len + units.len()].copy_from_slice(units);
+		len += units.len();
+	}
+	dst[len] = 0;
+}
+
+com_vtable! {
+	/// Methods of `IPluginFactory`.
+	pub IPluginFactoryVtExt for VtObjectPtr<IPluginFactoryVt> {
+		/// Write information about the vendor to `info`.
+		pub fn get_factory_info(info: *mut PFactoryInfo) -> TResult;
+		/// Return the number of classes.
+		pub fn count_classes() -> i32;
+		/// Write information about the class at `index` to `info`.
+		pub fn get_class_info(index: i32, info: *mut PClassInfo) -> TResult;
+		/// Create an object of the class `cid`,
+		/// and write a reference to its interface `iid` to `object`.
+		pub fn create_instance(cid: *const GUID, iid: *const GUID, object: *mut *mut c_void) -> TResult;
+	}
+}
+
+/// VTable of `IPluginFactory`, which is returned by the `GetPluginFactory`
+/// entry point of VST3 plugins.
+#[repr(C)]
+pub struct IPluginFactoryVt {
+	/// Methods of `FUnknown`.
+	pub base: FUnknownVt,
+	/// Methods of `IPluginFactory`.
+	pub factory: IPluginFactoryVtExt,
+}
+
+// SAFETY: `IPluginFactoryVt` is the VTable of `IPluginFactory`.
+unsafe impl Interface for IPluginFactoryVt {
+	const IID: GUID = GUID::from_vst3_uid(0x7A4D811C, 0x52114A1F, 0xAED9D2EE, 0x0B43BF9F);
+}
+
+com_vtable! {
+	/// Methods of `IPluginFactory2`.
+	pub IPluginFactory2VtExt for VtObjectPtr<IPluginFactory2Vt> {
+		/// Write extended information about the class at `index` to `info`.
+		pub fn get_class_info2(index: i32, info: *mut PClassInfo2) -> TResult;
+	}
+}
+
+/// VTable of `IPluginFactory2`.
+#[repr(C)]
+pub struct IPluginFactory2Vt {
+	/// Methods of `IPluginFactory`.
+	pub base: IPluginFactoryVt,
+	/// Methods of `IPluginFactory2`.
+	pub factory2: IPluginFactory2VtExt,
+}
+
+// SAFETY: `IPluginFactory2Vt` is the VTable of `IPluginFactory2`.
+unsafe impl Interface for IPluginFactory2Vt {
+	const IID: GUID = GUID::from_vst3_uid(0x0007B650, 0xF24B4C0B, 0xA464EDB9, 0xF00B2ABB);
+	const BASE_IIDS: &'static [GUID] = &[IPluginFactoryVt::IID];
+}
+
+com_vtable! {
+	/// Methods of `IPluginFactory3`.
+	pub IPluginFactory3VtExt for VtObjectPtr<IPluginFactory3Vt> {
+		/// Write extended information with UTF-16 strings about the class at `index` to `info`.
+		pub fn get_class_info_unicode(index: i32, info: *mut PClassInfoW) -> TResult;
+		/// Give the factory the context of the host.
+		pub fn set_host_context(context: Option<VtObjectPtr<FUnknownVt>>) -> TResult;
+	}
+}
+
+/// VTable of `IPluginFactory3`.
+#[repr(C)]
+pub struct IPluginFactory3Vt {
+	/// Methods of `IPluginFactory2`.
+	pub base: IPluginFactory2Vt,
+	/// Methods of `IPluginFactory3`.
+	pub factory3: IPluginFactory3VtExt,
+}
+
+// SAFETY: `IPluginFactory3Vt` is the VTable of `IPluginFactory3`.
+unsafe impl Interface for IPluginFactory3Vt {
+	const IID: GUID = GUID::from_vst3_uid(0x4555A2AB, 0xC1234E57, 0x9B122910, 0x36878931);
+	const BASE_IIDS: &'static [GUID] = &[IPluginFactoryVt::IID, IPluginFactory2Vt::IID];
+}
+
+/// Description of a class of a VST3 plugin, for [`PluginFactoryInfo`].
+/// 
+/// Fields that are not set with [`PluginClass::new`] are usually set with
+/// struct update syntax.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginClass {
+	/// Class ID, the `TUID` that creates the class.
+	pub cid: GUID,
+	/// Number of instances that can be created.
+	pub cardinality: i32,
+	/// Category of the class, such as `Audio Module Class`.
+	pub category: &'static str,
+	/// Name of the class.
+	pub name: &'static str,
+	/// Flags of the class, such as `kDistributable`.
+	pub class_flags: u32,
+	/// Subcategories separated by `|`, such as `Fx|Delay`.
+	pub sub_categories: &'static str,
+	/// Name of the vendor of the class.
+	pub vendor: &'static str,
+	/// Version of the class.
+	pub version: &'static str,
+	/// Version of the SDK that the class was built with.
+	pub sdk_version: &'static str,
+	/// Create a new object of the class.
+	pub create: CreateObjectFn,
+}
+
+impl PluginClass {
+	/// Describe a class with any number of instances and empty optional fields.
+	pub const fn new(cid: GUID, category: &'static str, name: &'static str, create: CreateObjectFn) -> Self {
+		Self {
+			cid,
+			cardinality: MANY_INSTANCES,
+			category,
+			name,
+			class_flags: 0,
+			sub_categories: "",
+			vendor: "",
+			version: "",
+			sdk_version: "",
+			create,
+		}
+	}
+}
+
+/// Description of a VST3 plugin factory and its classes,
+/// for [`plugin_factory`] and [`vst3_plugin!`](crate::vst3_plugin!).
+#[derive(Debug, Clone, Copy)]
+pub struct PluginFactoryInfo {
+	/// Name of the vendor.
+	pub vendor: &'static str,
+	/// URL of the vendor.
+	pub url: &'static str,
+	/// Contact e-mail address of the vendor.
+	pub email: &'static str,
+	/// The classes.
+	pub classes: &'static [PluginClass],
+}
+
+struct PluginFactory {
+	info: &'static PluginFactoryInfo,
+	host_context: AtomicPtr<c_void>,
+}
+
+impl PluginFactory {
+	/// `kUnicode`, as the strings of `PClassInfoW` are UTF-16.
+	const UNICODE: i32 = 16;
+
+	fn class<'a>(this: VtObjectPtr<impl Sized>, index: i32) -> Option<&'a PluginClass> {
+		let this = unsafe { ComObject::<Self>::from_this(this) };
+		this.info.classes.get(usize::try_from(index).ok()?)
+	}
+
+	unsafe extern "system" fn get_factory_info(this: VtObjectPtr<IPluginFactoryVt>, info: *mut PFactoryInfo) -> TResult {
+		if info.is_null() {
+			return TResult::INVALID_ARGUMENT;
+		}
+		let this = unsafe { ComObject::<Self>::from_this(this) };
+		let mut factory_info = zeroed::<PFactoryInfo>();
+		write_str(&mut factory_info.vendor, this.info.vendor);
+		write_str(&mut factory_info.url, this.info.url);
+		write_str(&mut factory_info.email, this.info.email);
+		factory_info.flags = Self::UNICODE;
+		unsafe { info.write(factory_info) };
+		TResult::OK
+	}
+
+	unsafe extern "system" fn count_classes(this: VtObjectPtr<IPluginFactoryVt>) -> i32 {
+		unsafe { ComObject::<Self>::from_this(this) }.info.classes.len() as i32
+	}
+
+	unsafe extern "system" fn get_class_info(
+		this: VtObjectPtr<IPluginFactoryVt>,
+		index: i32,
+		info: *mut PClassInfo,
+	) -> TResult {
+		let Some(class) = Self::class(this, index).filter(|_| !info.is_null()) else {
+			return TResult::INVALID_ARGUMENT;
+		};
+		let mut class_info = zeroed::<PClassInfo>();
+		class_info.cid = class.cid;
+		class_info.cardinality = class.cardinality;
+		write_str(&mut class_info.category, class.category);
+		write_str(&mut class_info.name, class.name);
+		unsafe { info.write(class_info) };
+		TResult::OK
+	}
+
+	unsafe extern "system" fn create_instance(
+		this: VtObjectPtr<IPluginFactoryVt>,
+		cid: *const GUID,
+		iid: *const GUID,
+		object: *mut *mut c_void,
+	) -> TResult {
+		if cid.is_null() || object.is_null() {
+			return TResult::INVALID_ARGUMENT;
+		}
+		unsafe { object.write(ptr::null_mut()) };
+		let this = unsafe { ComObject::<Self>::from_this(this) };
+		let cid = unsafe { cid.read_unaligned() };
+		let Some(class) = this.info.classes.iter().find(|class| class.cid == cid) else {
+			return TResult::NO_INTERFACE;
+		};
+		let unknown = (class.create)();
+		let result = unsafe { (unknown.vtable().query_interface)(ComPtr::as_raw(&unknown), iid, object) };
+		result.into()
+	}
+
+	unsafe extern "system" fn get_class_info2(
+		this: VtObjectPtr<IPluginFactory2Vt>,
+		index: i32,
+		info: *mut PClassInfo2,
+	) -> TResult {
+		let Some(class) = Self::class(this, index).filter(|_| !info.is_null()) else {
+			return TResult::INVALID_ARGUMENT;
+		};
+		let mut class_info = zeroed::<PClassInfo2>();
+		class_info.cid = class.cid;
+		class_info.cardinality = class.cardinality;
+		write_str(&mut class_info.category, class.category);
+		write_str(&mut class_info.name, class.name);
+		class_info.class_flags = class.class_flags;
+		write_str(&mut class_info.sub_categories, class.sub_categories);
+		write_str(&mut class_info.vendor, class.vendor);
+		write_str(&mut class_info.version, class.version);
+		write_str(&mut class_info.sdk_version, class.sdk_version);
+		unsafe { info.write(class_info) };
+		TResult::OK
+	}
+
+	unsafe extern "system" fn get_class_info_unicode(
+		this: VtObjectPtr<IPluginFactory3Vt>,
+		index: i32,
+		info: *mut PClassInfoW,
+	) -> TResult {
+		let Some(class) = Self::class(this, index).filter(|_| !info.is_null()) else {
+			return TResult::INVALID_ARGUMENT;
+		};
+		let mut class_info = zeroed::<PClassInfoW>();
+		class_info.cid = class.cid;
+		class_info.cardinality = class.cardinality;
+		write_str(&mut class_info.category, class.category);
+		write_wide(&mut class_info.name, class.name);
+		class_info.class_flags = class.class_flags;
+		write_str(&mut class_info.sub_categories, class.sub_categories);
+		write_wide(&mut class_info.vendor, class.vendor);
+		write_wide(&mut class_info.version, class.version);
+		write_wide(&mut class_info.sdk_version, class.sdk_version);
+		unsafe { info.write(class_info) };
+		TResult::OK
+	}
+
+	unsafe extern "system" fn set_host_context(
+		this: VtObjectPtr<IPluginFactory3Vt>,
+		context: Option<VtObjectPtr<FUnknownVt>>,
+	) -> TResult {
+		let this = unsafe { ComObject::<Self>::from_this(this) };
+		// The factory keeps a reference to the context.
+		let context = context.map(|context| unsafe { ComPtr::<FUnknownVt>::from_raw_borrowed(context) });
+		let new = context.map_or(ptr::null_mut(), |context| ComPtr::into_raw(context).as_ptr().cast());
+		let old = this.host_context.swap(new, Ordering::AcqRel);
+		if let Some(old) = NonNull::new(old) {
+			drop(unsafe { ComPtr::<FUnknownVt>::from_raw(old.cast()) });
+		}
+		TResult::OK
+	}
+}
+
+impl Drop for PluginFactory {
+	fn drop(&mut self) {
+		if let Some(context) = NonNull::new(*self.host_context.get_mut()) {
+			drop(unsafe { ComPtr::<FUnknownVt>::from_raw(context.cast()) });
+		}
+	}
+}
+
+// SAFETY: The only VTable starts with `ComObject::UNKNOWN`.
+unsafe impl ComClass for PluginFactory {
+	type VTables = [VTablePtr<()>; 1];
+
+	fn vtables() -> Self::VTables {
+		static VTABLE: IPluginFactory3Vt = IPluginFactory3Vt {
+			base: IPluginFactory2Vt {
+				base: IPluginFactoryVt {
+					base: ComObject::<PluginFactory>::UNKNOWN,
+					factory: IPluginFactoryVtExt {
+						get_factory_info: PluginFactory::get_factory_info,
+						count_classes: PluginFactory::count_classes,
+						get_class_info: PluginFactory::get_class_info,
+						create_instance: PluginFactory::create_instance,
+					},
+				},
+				factory2: IPluginFactory2VtExt {
+					get_class_info2: PluginFactory::get_class_info2,
+				},
+			},
+			factory3: IPluginFactory3VtExt {
+				get_class_info_unicode: PluginFactory::get_class_info_unicode,
+				set_host_context: PluginFactory::set_host_context,
+			},
+		};
+		// SAFETY: `VTABLE` is a valid VTable.
+		[unsafe { VTablePtr::new(NonNull::from(&VTABLE).cast()) }]
+	}
+
+	fn interface_index(iid: &GUID) -> Option<usize> {
+		IPluginFactory3Vt::matches(iid).then_some(0)
+	}
+
+	const NO_INTERFACE: HRESULT = TResult::NO_INTERFACE.as_hresult();
+}
+
+/// Return a new VST3 plugin factory with the classes in `info`,
+/// which implements `IPluginFactory`, `IPluginFactory2` and `IPluginFactory3`.
+pub fn plugin_factory(info: &'static PluginFactoryInfo) -> ComPtr<IPluginFactory3Vt> {
+	let unknown = ComObject::create(PluginFactory { info, host_context: AtomicPtr::new(ptr::null_mut()) });
+	// SAFETY: The first interface of `PluginFactory` is `IPluginFactory3`.
+	unsafe { ComPtr::from_raw(ComPtr::into_raw(unknown).cast()) }
+}
+
+impl ComPtr<IPluginFactoryVt> {
+	/// Return information about the vendor of the factory.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `getFactoryInfo`.
+	pub fn factory_info(&self) -> Result<PFactoryInfo, TResult> {
+		let mut info = zeroed();
+		unsafe { (self.vtable().factory.get_factory_info)(ComPtr::as_raw(self), &mut info) }.ok()?;
+		Ok(info)
+	}
+
+	/// Return the number of classes of the factory.
+	pub fn class_count(&self) -> usize {
+		let count = unsafe { (self.vtable().factory.count_classes)(ComPtr::as_raw(self)) };
+		count.max(0) as usize
+	}
+
+	/// Return information about the class at `index`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `getClassInfo`.
+	pub fn class_info(&self, index: usize) -> Result<PClassInfo, TResult> {
+		let index = i32::try_from(index).map_err(|_| TResult::INVALID_ARGUMENT)?;
+		let mut info = zeroed();
+		unsafe { (self.vtable().factory.get_class_info)(ComPtr::as_raw(self), index, &mut info) }.ok()?;
+		Ok(info)
+	}
+
+	/// Create an object of the class `cid`, returning its interface `I`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `createInstance`.
+	pub fn create_instance<I: Interface>(&self, cid: &GUID) -> Result<ComPtr<I>, TResult> {
+		let mut object = ptr::null_mut();
+		unsafe { (self.vtable().factory.create_instance)(ComPtr::as_raw(self), cid, &I::IID, &mut object) }.ok()?;
+		match NonNull::new(object) {
+			// SAFETY: `createInstance` returned a reference to `I`.
+			Some(object) => Ok(unsafe { ComPtr::from_raw(object.cast()) }),
+			None => Err(TResult::NO_INTERFACE),
+		}
+	}
+}
+
+impl ComPtr<IPluginFactory2Vt> {
+	/// Return extended information about the class at `index`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `getClassInfo2`.
+	pub fn class_info2(&self, index: usize) -> Result<PClassInfo2, TResult> {
+		let index = i32::try_from(index).map_err(|_| TResult::INVALID_ARGUMENT)?;
+		let mut info = zeroed();
+		unsafe { (self.vtable().factory2.get_class_info2)(ComPtr::as_raw(self), index, &mut info) }.ok()?;
+		Ok(info)
+	}
+}
+
+impl ComPtr<IPluginFactory3Vt> {
+	/// Return extended information with UTF-16 strings about the class at `index`.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `getClassInfoUnicode`.
+	pub fn class_info_unicode(&self, index: usize) -> Result<PClassInfoW, TResult> {
+		let index = i32::try_from(index).map_err(|_| TResult::INVALID_ARGUMENT)?;
+		let mut info = zeroed();
+		unsafe { (self.vtable().factory3.get_class_info_unicode)(ComPtr::as_raw(self), index, &mut info) }.ok()?;
+		Ok(info)
+	}
+
+	/// Give the factory the context of the host.
+	/// 
+	/// # Errors
+	/// Returns the failure code of `setHostContext`.
+	pub fn set_host_context(&self, context: Option<&ComPtr<FUnknownVt>>) -> Result<(), TResult> {
+		let context = context.map(ComPtr::as_raw);
+		unsafe { (self.vtable().factory3.set_host_context)(ComPtr::as_raw(self), context) }.ok()
+	}
+}
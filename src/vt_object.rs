@@ -1,5 +1,6 @@
 use ::core::{
 	fmt,
+	pin::Pin,
 	ptr::NonNull,
 };
 
@@ -13,6 +14,62 @@ use super::VTablePtr;
 /// has a non-null pointer to the VTable as the first field with `repr(C)`.
 pub type VtObjectPtr<VTable> = NonNull<VTablePtr<VTable>>;
 
+/// Trait for Rust types that implement a C++ class with a `VTable`,
+/// such as a `repr(C)` struct whose first field is a [`VTablePtr<VTable>`].
+/// 
+/// This allows a [`VtObject`] to be recognized as an instance of `Self`
+/// by comparing its VTable pointer against [`VtImpl::VTABLE`],
+/// which is what [`VtObject::downcast_ref`] and the devirtualizing form of
+/// [`virtual_call!`](crate::virtual_call!) do.
+/// 
+/// # Safety
+/// - `Self` must have a [`VTablePtr<VTable>`] at offset 0, and be a valid
+///   C++ object with that `VTable` (usually by being `repr(C)`).
+/// - Every instance of `Self` must have its VTable pointer set to
+///   [`VtImpl::VTABLE`] while it is reachable through a [`VtObject`].
+/// - [`VtImpl::VTABLE`] must not be used by objects of any other type.
+/// 
+/// Note that the address of a constant is not guaranteed to be unique.
+/// If the same VTable ends up at different addresses,
+/// the check merely fails and the call falls back to the indirect call.
+pub unsafe trait VtImpl<VTable: 'static>: Sized {
+	/// The `VTable` that every instance of `Self` points to.
+	const VTABLE: &'static VTable;
+
+	/// Write [`VtImpl::VTABLE`] to the VTable pointer of `slot`,
+	/// for use in an in-place initializer such as [`init_with`](crate::init_with).
+	/// 
+	/// # Safety
+	/// `slot` must be valid for writes and properly aligned.
+	unsafe fn write_vtable(slot: NonNull<Self>) {
+		unsafe { slot.cast::<VTablePtr<VTable>>().write(VTablePtr::from_ref(Self::VTABLE)) }
+	}
+
+	/// Return a reference that represents the pinned C++ object.
+	fn vt_object(self: Pin<&Self>) -> &VtObject<VTable> {
+		// SAFETY: `Self` upholds the contract of `VtImpl`.
+		unsafe { VtObject::from_ptr(NonNull::from(self.get_ref()).cast()) }
+	}
+
+	/// Return a mutable reference that represents the pinned C++ object.
+	/// 
+	/// # Safety
+	/// The VTable pointer of the object must not be replaced through the reference,
+	/// such as by swapping it with another [`VtObject`],
+	/// since the object must keep pointing to [`VtImpl::VTABLE`].
+	unsafe fn vt_object_mut(self: Pin<&mut Self>) -> &mut VtObject<VTable> {
+		// SAFETY: `Self` upholds the contract of `VtImpl`,
+		// and the object is not moved.
+		unsafe { VtObject::from_ptr_mut(NonNull::from(self.get_unchecked_mut()).cast()) }
+	}
+
+	/// Return a pointer to the pinned C++ object that can be used with C++.
+	fn vt_object_ptr(self: Pin<&mut Self>) -> VtObjectPtr<VTable> {
+		// SAFETY: The object is not moved.
+		NonNull::from(unsafe { self.get_unchecked_mut() }).cast()
+	}
+}
+
 /// Structure that imitates the layout of a C++ object with a `VTable`.
 #[repr(C)]
 pub struct VtObject<VTable> {
@@ -65,6 +122,11 @@ impl<VTable> VtObject<VTable> {
 		unsafe { VtObjectPtr::new_unchecked(self as *mut Self as *mut _) }
 	}
 
+	/// Return a reference to the object's VTable pointer.
+	pub const fn vtable_ptr(&self) -> &VTablePtr<VTable> {
+		&self.vtable
+	}
+
 	/// Return a reference to the object's `VTable`.
 	pub const fn vtable(&self) -> &VTable {
 		self.vtable.as_ref()
@@ -80,3 +142,75 @@ impl<VTable> VtObject<VTable> {
 		unsafe { self.vtable.as_mut() }
 	}
 }
+
+impl<VTable: 'static> VtObject<VTable> {
+	/// Return `true` if the object is an instance of the Rust type `T`,
+	/// based on its VTable pointer.
+	/// 
+	/// See the documentation of [`VtImpl`] for caveats.
+	pub fn is<T: VtImpl<VTable>>(&self) -> bool {
+		::core::ptr::eq(self.vtable.as_ref(), T::VTABLE)
+	}
+
+	/// Return a reference to the object as the Rust type `T`,
+	/// or [`None`] if it is not an instance of `T`.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::{VTablePtr, VtImpl, VtObject, VtObjectPtr, vtable, virtual_fn};
+	/// vtable! {
+	/// 	CounterVt {
+	/// 		pub fn get() -> u32;
+	/// 	}
+	/// }
+	/// 
+	/// #[repr(C)]
+	/// struct Counter {
+	/// 	vtable: VTablePtr<CounterVt>,
+	/// 	value: u32,
+	/// }
+	/// 
+	/// virtual_fn! {
+	/// 	fn get(this: VtObjectPtr<CounterVt>) -> u32 {
+	/// 		this.cast::<Counter>().as_ref().value
+	/// 	}
+	/// }
+	/// 
+	/// static COUNTER_VT: CounterVt = CounterVt { get };
+	/// 
+	/// unsafe impl VtImpl<CounterVt> for Counter {
+	/// 	const VTABLE: &'static CounterVt = &COUNTER_VT;
+	/// }
+	/// 
+	/// let counter = Counter {
+	/// 	vtable: VTablePtr::from_ref(&COUNTER_VT),
+	/// 	value: 3,
+	/// };
+	/// let object = unsafe { VtObject::from_ptr(VtObjectPtr::from_ref(&counter.vtable)) };
+	/// assert_eq!(object.downcast_ref::<Counter>().map(|c| c.value), Some(3));
+	/// ```
+	pub fn downcast_ref<T: VtImpl<VTable>>(&self) -> Option<&T> {
+		if self.is::<T>() {
+			// SAFETY: `T` upholds the contract of `VtImpl`.
+			Some(unsafe { &*(self as *const Self).cast::<T>() })
+		} else {
+			None
+		}
+	}
+
+	/// Return a mutable reference to the object as the Rust type `T`,
+	/// or [`None`] if it is not an instance of `T`.
+	/// 
+	/// `T` must be [`Unpin`], since the reference could otherwise be used to move
+	/// an object that is pinned, such as with [`mem::swap`](::core::mem::swap).
+	/// 
+	/// See [`VtObject::downcast_ref`].
+	pub fn downcast_mut<T: VtImpl<VTable> + Unpin>(&mut self) -> Option<&mut T> {
+		if self.is::<T>() {
+			// SAFETY: `T` upholds the contract of `VtImpl`.
+			Some(unsafe { &mut *(self as *mut Self).cast::<T>() })
+		} else {
+			None
+		}
+	}
+}
@@ -47,6 +47,11 @@ impl<VTable> VTablePtr<VTable> {
 		unsafe { Self::new(NonNull::new_unchecked(vtable as *mut _)) }
 	}
 
+	/// Returns the inner [`NonNull`].
+	pub const fn as_ptr(&self) -> NonNull<VTable> {
+		self.0
+	}
+
 	/// Consumes this pointer, converting it into the inner [`NonNull`].
 	pub const fn into_ptr(self) -> NonNull<VTable> {
 		self.0
@@ -96,3 +101,22 @@ impl<VTable> Hash for VTablePtr<VTable> {
 		self.0.hash(state)
 	}
 }
+
+/// Layout of a VTable symbol that has `PREFIX` pointer-sized words before the address point,
+/// such as the offset-to-top and the RTTI pointer of an Itanium VTable.
+/// 
+/// See [`extern_vtable!`](crate::extern_vtable!).
+#[repr(C)]
+pub struct PrefixedVTable<VTable, const PREFIX: usize> {
+	/// Words before the address point.
+	pub prefix: [usize; PREFIX],
+	/// VTable at the address point.
+	pub vtable: VTable,
+}
+
+impl<VTable, const PREFIX: usize> PrefixedVTable<VTable, PREFIX> {
+	/// Returns a [`VTablePtr`] to the address point.
+	pub const fn address_point(&'static self) -> VTablePtr<VTable> {
+		VTablePtr::from_ref(&self.vtable)
+	}
+}
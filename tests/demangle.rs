@@ -0,0 +1,113 @@
+#![cfg(feature = "demangle")]
+
+use cppdvt::demangle;
+
+/// Check symbols against the output of c++filt.
+fn check(cases: &[(&str, &str)]) {
+	for &(symbol, expected) in cases {
+		assert_eq!(demangle(symbol).as_deref(), Some(expected), "{symbol}");
+	}
+}
+
+#[test]
+fn pack_expansions() {
+	// Packs within the elements of an expanded pack are printed whole.
+	check(&[
+		(
+			"_ZNSt6vectorISt5tupleIJPN4llvm12LiveIntervalEmjEESaIS4_EE17_M_realloc_insertIJS4_EEEvN9__gnu_cxx17__normal_iteratorIPS4_S6_EEDpOT_",
+			"void std::vector<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int>, std::allocator<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int> > >::_M_realloc_insert<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int> >(__gnu_cxx::__normal_iterator<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int>*, std::vector<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int>, std::allocator<std::tuple<llvm::LiveInterval*, unsigned long, unsigned int> > > >, std::tuple<llvm::LiveInterval*, unsigned long, unsigned int>&&)",
+		),
+		(
+			"_ZN4llvm10make_errorINS_11StringErrorEJNS_14formatv_objectISt5tupleIJNS_6detail23provider_format_adapterImEES6_S6_S6_EEEESt10error_codeEEENS_5ErrorEDpOT0_",
+			"llvm::Error llvm::make_error<llvm::StringError, llvm::formatv_object<std::tuple<llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long> > >, std::error_code>(llvm::formatv_object<std::tuple<llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long>, llvm::detail::provider_format_adapter<unsigned long> > >&&, std::error_code&&)",
+		),
+		(
+			"_Z1fIJicEEvSt5tupleIJDpT_EE",
+			"void f<int, char>(std::tuple<int, char>)",
+		),
+		(
+			"_Z1fIJRiRKcEEvDpOT_",
+			"void f<int&, char const&>(int&, char const&)",
+		),
+		(
+			"_Z1fIJEEvDpT_",
+			"void f<>()",
+		),
+	]);
+}
+
+#[test]
+fn empty_packs() {
+	// c++filt does not separate `>`s after an empty pack.
+	check(&[
+		(
+			"_ZN4llvm11PassManagerINS_6ModuleENS_15AnalysisManagerIS1_JEEEJEEC1EOS4_",
+			"llvm::PassManager<llvm::Module, llvm::AnalysisManager<llvm::Module>>::PassManager(llvm::PassManager<llvm::Module, llvm::AnalysisManager<llvm::Module>>&&)",
+		),
+		(
+			"_ZTIN5clang4ento7CheckerINS0_5check7PreStmtINS_4StmtEEEJEEE",
+			"typeinfo for clang::ento::Checker<clang::ento::check::PreStmt<clang::Stmt>>",
+		),
+	]);
+}
+
+#[test]
+fn substitutions() {
+	check(&[
+		(
+			"_ZSt25__unguarded_linear_insertIPN4llvm3cfg6UpdateIPNS0_10BasicBlockEEEN9__gnu_cxx5__ops14_Val_comp_iterIZNS1_15LegalizeUpdatesIS4_EEvNS0_8ArrayRefINS2_IT_EEEERNS0_15SmallVectorImplISD_EEbbEUlRKS5_SJ_E_EEEvSC_T0_",
+			"void std::__unguarded_linear_insert<llvm::cfg::Update<llvm::BasicBlock*>*, __gnu_cxx::__ops::_Val_comp_iter<llvm::cfg::LegalizeUpdates<llvm::BasicBlock*>(llvm::ArrayRef<llvm::cfg::Update<llvm::BasicBlock*> >, llvm::SmallVectorImpl<llvm::cfg::Update<llvm::BasicBlock*> >&, bool, bool)::{lambda(llvm::cfg::Update<llvm::BasicBlock*> const&, llvm::cfg::Update<llvm::BasicBlock*> const&)#1}> >(llvm::cfg::Update<llvm::BasicBlock*>*, __gnu_cxx::__ops::_Val_comp_iter<llvm::cfg::LegalizeUpdates<llvm::BasicBlock*>(llvm::ArrayRef<llvm::cfg::Update<llvm::BasicBlock*> >, llvm::SmallVectorImpl<llvm::cfg::Update<llvm::BasicBlock*> >&, bool, bool)::{lambda(llvm::cfg::Update<llvm::BasicBlock*> const&, llvm::cfg::Update<llvm::BasicBlock*> const&)#1}>)",
+		),
+		(
+			"_ZZNSt9once_flag18_Prepare_executionC4IZSt9call_onceIRFvvEJEEvRS_OT_DpOT0_EUlvE_EERS6_ENUlvE_4_FUNEv",
+			"std::once_flag::_Prepare_execution::_Prepare_execution<std::call_once<void (&)()>(std::once_flag&, void (&)())::{lambda()#1}>(void (&)())::{lambda()#1}::_FUN()",
+		),
+		(
+			"_ZSt9__find_ifIPKSt10unique_ptrIN4llvm24ScheduleHazardRecognizerESt14default_deleteIS2_EEN9__gnu_cxx5__ops10_Iter_predISt7_Mem_fnIMS2_KFbvEEEEET_SG_SG_T0_St26random_access_iterator_tag",
+			"std::unique_ptr<llvm::ScheduleHazardRecognizer, std::default_delete<llvm::ScheduleHazardRecognizer> > const* std::__find_if<std::unique_ptr<llvm::ScheduleHazardRecognizer, std::default_delete<llvm::ScheduleHazardRecognizer> > const*, __gnu_cxx::__ops::_Iter_pred<std::_Mem_fn<bool (llvm::ScheduleHazardRecognizer::*)() const> > >(std::unique_ptr<llvm::ScheduleHazardRecognizer, std::default_delete<llvm::ScheduleHazardRecognizer> > const*, std::unique_ptr<llvm::ScheduleHazardRecognizer, std::default_delete<llvm::ScheduleHazardRecognizer> > const*, __gnu_cxx::__ops::_Iter_pred<std::_Mem_fn<bool (llvm::ScheduleHazardRecognizer::*)() const> >, std::random_access_iterator_tag)",
+		),
+		(
+			"_Z1fM1AKFvvES_S0_S1_",
+			"f(void (A::*)() const, A, void () const, void (A::*)() const)",
+		),
+		(
+			"_ZN4llvm22containsIrreducibleCFGIPKNS_10BasicBlockEKNS_25ReversePostOrderTraversalIPKNS_8FunctionENS_11GraphTraitsIS7_EEEEKNS_8LoopInfoENS8_IS3_EEEEbRT0_RKT1_",
+			"bool llvm::containsIrreducibleCFG<llvm::BasicBlock const*, llvm::ReversePostOrderTraversal<llvm::Function const*, llvm::GraphTraits<llvm::Function const*> > const, llvm::LoopInfo const, llvm::GraphTraits<llvm::BasicBlock const*> >(llvm::ReversePostOrderTraversal<llvm::Function const*, llvm::GraphTraits<llvm::Function const*> > const&, llvm::LoopInfo const&)",
+		),
+		(
+			"_ZN6icu_726number4impl10MicroPropsUt_D1Ev",
+			"icu_72::number::impl::MicroProps::{unnamed type#1}::~MicroProps()",
+		),
+	]);
+}
+
+#[test]
+fn expressions() {
+	// Operands are parenthesized unless they are names.
+	check(&[
+		(
+			"_ZN4llvmlsINS_18raw_string_ostreamEA2_cEENSt9enable_ifIXaantsr3std12is_referenceIT_EE5valuesr3std10is_base_ofINS_11raw_ostreamES4_EE5valueEOS4_E4typeES6_RKT0_",
+			"std::enable_if<(!std::is_reference<llvm::raw_string_ostream>::value)&&std::is_base_of<llvm::raw_ostream, llvm::raw_string_ostream>::value, llvm::raw_string_ostream&&>::type llvm::operator<< <llvm::raw_string_ostream, char [2]>(llvm::raw_string_ostream&&, char const (&) [2])",
+		),
+		(
+			"_ZN4llvm17make_filter_rangeIRNS_10BasicBlockESt8functionIFbRNS_11InstructionEEEEENS_14iterator_rangeINS_20filter_iterator_implIDTclsr3stdE5beginclsr3stdE7declvalIRT_EEEET0_NS_6detail15fwd_or_bidi_tagISC_E4typeEEEEEOSA_SD_",
+			"llvm::iterator_range<llvm::filter_iterator_impl<decltype (std::begin((std::declval<llvm::BasicBlock&>)())), std::function<bool (llvm::Instruction&)>, llvm::detail::fwd_or_bidi_tag<decltype (std::begin((std::declval<llvm::BasicBlock&>)()))>::type> > llvm::make_filter_range<llvm::BasicBlock&, std::function<bool (llvm::Instruction&)> >(llvm::BasicBlock&, std::function<bool (llvm::Instruction&)>)",
+		),
+		(
+			"_Z1fIiEDTcvT_plfp_Li1EET_",
+			"decltype ((int)({parm#1}+(1))) f<int>(int)",
+		),
+		(
+			"_Z1fIiEDTixdtfp_1xLi0EET_",
+			"decltype (({parm#1}.x)[0]) f<int>(int)",
+		),
+		(
+			"_Z1fIiEDTqugtfp_Li0Efp_ngfp_ET_",
+			"decltype ((({parm#1}>(0)))?{parm#1} : (-{parm#1})) f<int>(int)",
+		),
+		(
+			"_Z1fIiEDTszadfp_ET_",
+			"decltype (sizeof (&{parm#1})) f<int>(int)",
+		),
+	]);
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "elf")]
+
+use cppdvt::{ElfError, ElfFile, ElfPointer, ElfTypeInfoKind};
+
+/// Built from `fixtures/shapes.cpp` with g++ for x86-64.
+static SHAPES: &[u8] = include_bytes!("fixtures/shapes.elf");
+
+fn slot_names<'a>(elf: &ElfFile<'a>, name: &str, index: usize) -> Vec<&'a str> {
+	let vtable = elf.vtable(elf.symbol(name).unwrap()).unwrap();
+	vtable.address_points[index].slots.iter().map(|slot| slot.symbol.unwrap()).collect()
+}
+
+#[test]
+fn vtables() {
+	let elf = ElfFile::parse(SHAPES).unwrap();
+	let mut classes: Vec<_> = elf.vtables().map(|vtable| vtable.class_name()).collect();
+	classes.sort();
+	assert_eq!(classes, ["11NamedSquare", "5Error"]);
+
+	let vtable = elf.vtable(elf.symbol("_ZTV11NamedSquare").unwrap()).unwrap();
+	assert_eq!(vtable.address_points.len(), 2);
+	let primary = vtable.primary().unwrap();
+	assert_eq!(primary.address, vtable.symbol.value + 16);
+	assert_eq!(primary.offset_to_top, 0);
+	assert_eq!(slot_names(&elf, "_ZTV11NamedSquare", 0), [
+		"_ZN11NamedSquareD1Ev",
+		"_ZN11NamedSquareD0Ev",
+		"_ZNK6Square4areaEv",
+		"_ZNK11NamedSquare4nameEv",
+	]);
+
+	// The VTable of the `Named` base, whose slot is a thunk.
+	let secondary = &vtable.address_points[1];
+	assert_eq!(secondary.offset_to_top, -16);
+	assert_eq!(secondary.typeinfo, primary.typeinfo);
+	assert_eq!(slot_names(&elf, "_ZTV11NamedSquare", 1), ["_ZThn16_NK11NamedSquare4nameEv"]);
+}
+
+#[test]
+fn relocations() {
+	let elf = ElfFile::parse(SHAPES).unwrap();
+	let vtable = elf.vtable(elf.symbol("_ZTV5Error").unwrap()).unwrap();
+	let primary = vtable.primary().unwrap();
+
+	// `R_X86_64_RELATIVE` to a function in the file.
+	let destructor = elf.symbol("_ZN5ErrorD1Ev").unwrap();
+	assert_eq!(primary.slots[0].target, ElfPointer::Address(destructor.value));
+	assert_eq!(elf.read_pointer(primary.address), Some(ElfPointer::Address(destructor.value)));
+
+	// `R_X86_64_64` to a function in libstdc++.
+	assert_eq!(primary.slots[2].target, ElfPointer::Import { symbol: "_ZNKSt13runtime_error4whatEv", addend: 0 });
+
+	// The typeinfo pointer before the address point is `R_X86_64_RELATIVE` too.
+	let typeinfo = elf.symbol("_ZTI5Error").unwrap();
+	assert_eq!(primary.typeinfo, ElfPointer::Address(typeinfo.value));
+}
+
+#[test]
+fn symbols() {
+	let elf = ElfFile::parse(SHAPES).unwrap();
+
+	// Symbols in both the static and dynamic tables are listed once, sorted by address.
+	let symbols = elf.symbols();
+	assert!(symbols.windows(2).all(|pair| pair[0].value <= pair[1].value && pair[0] != pair[1]));
+	assert_eq!(symbols.iter().filter(|symbol| symbol.name == "_ZTV5Error").count(), 1);
+
+	// An address inside a VTable symbol is found by its symbol.
+	let vtable = elf.symbol("_ZTV11NamedSquare").unwrap();
+	assert_eq!(elf.symbol_at(vtable.value), Some(vtable));
+	assert_eq!(elf.symbol_at(vtable.value + vtable.size - 1), Some(vtable));
+	assert_ne!(elf.symbol_at(vtable.value + vtable.size), Some(vtable));
+}
+
+#[test]
+fn typeinfo() {
+	let elf = ElfFile::parse(SHAPES).unwrap();
+
+	let error = elf.typeinfo(ElfPointer::Address(elf.symbol("_ZTI5Error").unwrap().value)).unwrap();
+	assert_eq!(error.kind, ElfTypeInfoKind::SingleInheritance);
+	assert_eq!(error.name, Some("5Error"));
+	assert_eq!(error.bases.len(), 1);
+	assert_eq!(error.bases[0].typeinfo, ElfPointer::Import { symbol: "_ZTISt13runtime_error", addend: 0 });
+
+	let named_square = elf.typeinfo(ElfPointer::Address(elf.symbol("_ZTI11NamedSquare").unwrap().value)).unwrap();
+	assert_eq!(named_square.kind, ElfTypeInfoKind::VirtualMultipleInheritance);
+	let bases: Vec<_> = named_square.bases.iter()
+		.map(|base| {
+			let typeinfo = elf.typeinfo(base.typeinfo).unwrap();
+			(typeinfo.name.unwrap(), base.offset, base.is_virtual, base.is_public)
+		})
+		.collect();
+	assert_eq!(bases, [("6Square", 0, false, true), ("5Named", 16, false, true)]);
+
+	let square = elf.typeinfo(named_square.bases[0].typeinfo).unwrap();
+	assert_eq!(square.kind, ElfTypeInfoKind::SingleInheritance);
+	let shape = elf.typeinfo(square.bases[0].typeinfo).unwrap();
+	assert_eq!((shape.kind, shape.name), (ElfTypeInfoKind::Class, Some("5Shape")));
+}
+
+#[test]
+fn corrupted() {
+	assert_eq!(ElfFile::parse(&SHAPES[..100]).unwrap_err(), ElfError::Truncated);
+
+	let mut data = SHAPES.to_vec();
+	data[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+	assert_eq!(ElfFile::parse(&data).unwrap_err(), ElfError::Truncated);
+
+	// Overwrite each field of each section header with large values,
+	// which must be rejected or ignored without overflowing.
+	let shoff = u64::from_le_bytes(SHAPES[40..48].try_into().unwrap()) as usize;
+	let shnum = u16::from_le_bytes(SHAPES[60..62].try_into().unwrap()) as usize;
+	for section in 0..shnum {
+		for field in [16, 24, 32, 56] {
+			for value in [u64::MAX, u64::MAX - 8, 1 << 63] {
+				let mut data = SHAPES.to_vec();
+				let offset = shoff + section * 64 + field;
+				data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+				if let Ok(elf) = ElfFile::parse(&data) {
+					for vtable in elf.vtables() {
+						for point in &vtable.address_points {
+							elf.typeinfo(point.typeinfo);
+						}
+					}
+				}
+			}
+		}
+	}
+}
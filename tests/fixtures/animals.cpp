@@ -0,0 +1,26 @@
+// Fixture for the `itanium_rtti` tests, built with:
+// g++ -shared -fPIC -O1 -g0 -o animals.elf animals.cpp
+#include <typeinfo>
+
+struct Animal {
+	virtual unsigned legs() const { return 4; }
+};
+
+struct Swimmer {
+	virtual void swim() const {}
+};
+
+struct Dog : Animal {};
+
+struct Duck : Animal, Swimmer {
+	unsigned legs() const override { return 2; }
+};
+
+extern "C" {
+	const char* type_name(const Animal* animal) { return typeid(*animal).name(); }
+	unsigned legs(const Animal* animal) { return animal->legs(); }
+	const Duck* animal_to_duck(const Animal* animal) { return dynamic_cast<const Duck*>(animal); }
+	const Swimmer* animal_to_swimmer(const Animal* animal) { return dynamic_cast<const Swimmer*>(animal); }
+	const Duck* swimmer_to_duck(const Swimmer* swimmer) { return dynamic_cast<const Duck*>(swimmer); }
+	const Dog* animal_to_dog(const Animal* animal) { return dynamic_cast<const Dog*>(animal); }
+}
@@ -0,0 +1,30 @@
+// Fixture for the `elf` tests, built with:
+// g++ -shared -fPIC -O1 -g0 -o shapes.elf shapes.cpp
+#include <stdexcept>
+
+struct Shape {
+	virtual ~Shape() {}
+	virtual double area() const = 0;
+};
+
+struct Square : Shape {
+	double side;
+	Square(double side) : side(side) {}
+	double area() const override { return side * side; }
+};
+
+struct Named {
+	virtual const char* name() const { return "named"; }
+};
+
+struct NamedSquare : Square, Named {
+	NamedSquare() : Square(1) {}
+	const char* name() const override { return "square"; }
+};
+
+struct Error : std::runtime_error {
+	Error() : std::runtime_error("error") {}
+};
+
+Shape* make_square() { return new NamedSquare(); }
+void fail() { throw Error(); }
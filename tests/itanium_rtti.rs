@@ -0,0 +1,110 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", target_env = "gnu"))]
+
+use core::{
+	ffi::{c_char, c_void, CStr},
+	mem, ptr,
+};
+
+use cppdvt::{Library, VTablePtr, VtObjectPtr, itanium_type_info, itanium_vtable, vtable, virtual_fn};
+
+#[link(name = "stdc++")]
+unsafe extern "C" {}
+
+vtable! {
+	AnimalVt {
+		pub fn legs() -> u32;
+	}
+}
+vtable! {
+	SwimmerVt {
+		pub fn swim();
+	}
+}
+
+virtual_fn! {
+	fn duck_legs(_this: VtObjectPtr<AnimalVt>) -> u32 {
+		2
+	}
+}
+virtual_fn! {
+	fn duck_swim(_this: VtObjectPtr<SwimmerVt>) {}
+}
+
+// The names match the classes of the fixture, which libstdc++ compares by name.
+itanium_type_info! {
+	static ANIMAL_TYPE_INFO = "6Animal";
+	static SWIMMER_TYPE_INFO = "7Swimmer";
+	static DUCK_TYPE_INFO = "4Duck": ANIMAL_TYPE_INFO, SWIMMER_TYPE_INFO + 8;
+}
+
+itanium_vtable! {
+	#[type_info(DUCK_TYPE_INFO)]
+	static DUCK_VT: AnimalVt = AnimalVt { legs: duck_legs };
+	#[type_info(DUCK_TYPE_INFO, offset = 8)]
+	static DUCK_SWIMMER_VT: SwimmerVt = SwimmerVt { swim: duck_swim };
+}
+
+/// A `Duck` of the fixture, implemented in Rust.
+#[repr(C)]
+struct Duck {
+	animal: VTablePtr<AnimalVt>,
+	swimmer: VTablePtr<SwimmerVt>,
+}
+
+/// Built from `fixtures/animals.cpp` with g++ for x86-64.
+struct Animals {
+	library: Library,
+}
+
+impl Animals {
+	fn open() -> Self {
+		let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/animals.elf\0");
+		let path = CStr::from_bytes_with_nul(path.as_bytes()).unwrap();
+		Self { library: unsafe { Library::open(path) }.unwrap() }
+	}
+
+	/// Call the function `name`, which takes a pointer to an object.
+	fn call<R>(&self, name: &CStr, object: *const c_void) -> R {
+		let function = self.library.symbol(name).unwrap();
+		let function = unsafe {
+			mem::transmute::<*mut c_void, unsafe extern "C" fn(*const c_void) -> R>(function.as_ptr())
+		};
+		unsafe { function(object) }
+	}
+}
+
+fn duck() -> Duck {
+	unsafe {
+		Duck {
+			animal: VTablePtr::new(DUCK_VT.as_ptr()),
+			swimmer: VTablePtr::new(DUCK_SWIMMER_VT.as_ptr()),
+		}
+	}
+}
+
+#[test]
+fn typeid() {
+	let animals = Animals::open();
+	let duck = duck();
+	let animal = ptr::from_ref(&duck).cast::<c_void>();
+
+	let name = animals.call::<*const c_char>(c"type_name", animal);
+	assert_eq!(unsafe { CStr::from_ptr(name) }, c"4Duck");
+	assert_eq!(animals.call::<u32>(c"legs", animal), 2);
+}
+
+#[test]
+fn dynamic_cast() {
+	let animals = Animals::open();
+	let duck = duck();
+	let animal = ptr::from_ref(&duck).cast::<c_void>();
+	let swimmer = ptr::from_ref(&duck.swimmer).cast::<c_void>();
+
+	// Downcasts, including from the base at offset 8.
+	assert_eq!(animals.call::<*const c_void>(c"animal_to_duck", animal), animal);
+	assert_eq!(animals.call::<*const c_void>(c"swimmer_to_duck", swimmer), animal);
+	// Cross-cast.
+	assert_eq!(animals.call::<*const c_void>(c"animal_to_swimmer", animal), swimmer);
+	// A `Duck` is not a `Dog`.
+	assert!(animals.call::<*const c_void>(c"animal_to_dog", animal).is_null());
+}
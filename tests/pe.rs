@@ -0,0 +1,100 @@
+#![cfg(feature = "pe")]
+
+use cppdvt::PeFile;
+
+const CODE: u32 = 0x6000_0020;
+const DATA: u32 = 0x4000_0040;
+
+/// Return an x64 image, or an x86 one if `!is_64`,
+/// with one section per `(virtual_address, raw_offset, raw_size, characteristics)`.
+fn image(is_64: bool, sections: &[(u32, u32, u32, u32)]) -> Vec<u8> {
+	let mut image = vec![0u8; 0x400];
+	let mut put = |offset: usize, bytes: &[u8]| image[offset..offset + bytes.len()].copy_from_slice(bytes);
+	put(0, b"MZ");
+	put(0x3c, &0x40u32.to_le_bytes());
+	put(0x40, if is_64 { b"PE\0\0\x64\x86" } else { b"PE\0\0\x4c\x01" });
+	put(0x46, &(sections.len() as u16).to_le_bytes());
+	put(0x54, &0x20u16.to_le_bytes());
+	if is_64 {
+		put(0x58, &0x20bu16.to_le_bytes());
+		put(0x70, &0x1_4000_0000u64.to_le_bytes());
+	} else {
+		put(0x58, &0x10bu16.to_le_bytes());
+		put(0x74, &0x1000_0000u32.to_le_bytes());
+	}
+	for (index, &(va, raw, size, characteristics)) in sections.iter().enumerate() {
+		let header = 0x78 + index * 40;
+		put(header + 8, &size.to_le_bytes());
+		put(header + 12, &va.to_le_bytes());
+		put(header + 16, &size.to_le_bytes());
+		put(header + 20, &raw.to_le_bytes());
+		put(header + 36, &characteristics.to_le_bytes());
+	}
+	image
+}
+
+#[test]
+fn x86_rtti() {
+	let mut image = image(false, &[(0x1000, 0x200, 0x100, CODE), (0x2000, 0x300, 0x100, DATA)]);
+	let mut put = |rva: usize, bytes: &[u8]| {
+		let offset = rva - 0x2000 + 0x300;
+		image[offset..offset + bytes.len()].copy_from_slice(bytes);
+	};
+	// RTTI references are absolute addresses, relative to the preferred image base.
+	let va = |rva: u32| (0x1000_0000 + rva).to_le_bytes();
+	// Type descriptor, whose name follows the VTable pointer and the spare pointer.
+	put(0x2008, b".?AVPet@zoo@@\0");
+	// Class Hierarchy Descriptor, and its array of one Base Class Descriptor.
+	for (rva, value) in [(0x2020, 0), (0x2024, 0), (0x2028, 1)] {
+		put(rva, &u32::to_le_bytes(value));
+	}
+	put(0x202c, &va(0x2030));
+	put(0x2030, &va(0x2038));
+	put(0x2038, &va(0x2000));
+	for (rva, value) in [(0x203c, 0), (0x2040, 0), (0x2044, u32::MAX), (0x2048, 0), (0x204c, 0x40)] {
+		put(rva, &u32::to_le_bytes(value));
+	}
+	put(0x2050, &va(0x2020));
+	// Complete Object Locator, with signature 0 and without the RVA of itself.
+	put(0x2064, &va(0x2000));
+	put(0x2068, &va(0x2020));
+	// The VTable, preceded by a pointer to the locator.
+	put(0x2070, &va(0x2058));
+	put(0x2074, &va(0x1000));
+	put(0x2078, &va(0x1010));
+
+	let pe = PeFile::parse(&image).unwrap();
+	assert!(!pe.is_64());
+	assert_eq!((pe.machine(), pe.image_base()), (0x14c, 0x1000_0000));
+	assert_eq!(pe.read_pointer(0x2070), Some(0x2058));
+
+	let vtables = pe.vtables();
+	assert_eq!(vtables.len(), 1);
+	let pet = &vtables[0];
+	assert_eq!(pet.rva, 0x2074);
+	assert_eq!(pet.slots, [0x1000, 0x1010]);
+	assert_eq!((pet.locator.rva, pet.locator.type_descriptor), (0x2058, 0x2000));
+	assert_eq!(pet.locator.name, ".?AVPet@zoo@@");
+	let bases = &pet.locator.hierarchy.bases;
+	assert_eq!(bases.len(), 1);
+	assert_eq!((bases[0].name, bases[0].pdisp, bases[0].attributes), (".?AVPet@zoo@@", -1, 0x40));
+}
+
+#[test]
+fn overflowing_sections() {
+	let image = image(true, &[(0x1000, 0xffff_ff80, 0x100, DATA), (0xffff_ff00, 0x200, 0x100, DATA)]);
+	let pe = PeFile::parse(&image).unwrap();
+	assert_eq!(pe.bytes_at(0x10f0), None);
+	assert_eq!(pe.read_u32(0xffff_fffe), None);
+	assert!(pe.object_locator(0xffff_fff0).is_none());
+	assert!(pe.class_hierarchy(0xffff_fff0).is_none());
+	assert!(pe.vtables().is_empty());
+}
+
+#[test]
+fn truncated_headers() {
+	let mut image = image(true, &[(0x1000, 0x200, 0x100, DATA)]);
+	image[0x3c..0x40].copy_from_slice(&u32::MAX.to_le_bytes());
+	assert!(PeFile::parse(&image).is_err());
+	assert!(PeFile::parse(&image[..0x80]).is_err());
+}
@@ -0,0 +1,82 @@
+//! Generate `vtable!` declarations from clang's JSON AST dump.
+//! 
+//! ```text
+//! clang++ -Xclang -ast-dump=json -fsyntax-only pet.hpp > pet.json
+//! cppdvt-ast2vtable [--msvc] pet.json Pet Lizard > pet.rs
+//! ```
+//! 
+//! The JSON can also be read from standard input by passing `-` as the file.
+//! Without class names, every class with a VTable is generated.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use cppdvt_tools::clang_ast::{Abi, Ast};
+use cppdvt_tools::json;
+
+const USAGE: &str = "usage: cppdvt-ast2vtable [--msvc] <ast.json | -> [class...]";
+
+fn main() -> ExitCode {
+	let mut abi = Abi::Itanium;
+	let mut path = None;
+	let mut names = Vec::new();
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--msvc" => abi = Abi::Msvc,
+			"--itanium" => abi = Abi::Itanium,
+			"-h" | "--help" => {
+				println!("{USAGE}");
+				return ExitCode::SUCCESS;
+			}
+			_ if path.is_none() => path = Some(arg),
+			_ => names.push(arg),
+		}
+	}
+	let Some(path) = path else {
+		eprintln!("{USAGE}");
+		return ExitCode::FAILURE;
+	};
+
+	let mut input = String::new();
+	let read = if path == "-" {
+		std::io::stdin().read_to_string(&mut input).map(drop)
+	} else {
+		std::fs::read_to_string(&path).map(|contents| input = contents)
+	};
+	if let Err(error) = read {
+		eprintln!("error: cannot read {path}: {error}");
+		return ExitCode::FAILURE;
+	}
+	let root = match json::parse(&input) {
+		Ok(root) => root,
+		Err(error) => {
+			eprintln!("error: cannot parse {path}: {error}");
+			return ExitCode::FAILURE;
+		}
+	};
+	let ast = Ast::from_json(&root);
+
+	let classes = if names.is_empty() {
+		ast.classes.iter().filter(|class| ast.is_dynamic(class)).collect::<Vec<_>>()
+	} else {
+		let mut classes = Vec::new();
+		for name in &names {
+			match ast.class(name) {
+				Some(class) => classes.push(class),
+				None => {
+					eprintln!("error: class `{name}` is not defined in {path}");
+					return ExitCode::FAILURE;
+				}
+			}
+		}
+		classes
+	};
+
+	let types = ast.type_map(abi, &classes);
+	println!("use cppdvt::vtable;");
+	for class in classes {
+		println!();
+		print!("{}", ast.vtable(class, abi, &types));
+	}
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,87 @@
+//! Compare the VTables of two builds of an ELF or PE file,
+//! and print how their slots moved as JSON.
+//! 
+//! ```text
+//! cppdvt-diff server_old.so server_new.so > migration.json
+//! cppdvt-diff --all --regex '^zoo::' server_old.dll server_new.dll
+//! ```
+//! 
+//! Classes are matched by their RTTI names,
+//! and slots by their function symbols or function body hashes.
+//! The output is a JSON array with one object per class,
+//! as described by `cppdvt_tools::diff::ClassDiff::to_json`.
+//! Unchanged classes are only included with `--all`.
+
+use std::process::ExitCode;
+
+use cppdvt_tools::diff::{self, ClassStatus};
+use cppdvt_tools::regex::Regex;
+use cppdvt_tools::scan;
+
+const USAGE: &str = "usage: cppdvt-diff [--all] [--regex <pattern>] <old> <new>";
+
+fn main() -> ExitCode {
+	let mut all = false;
+	let mut regex = None;
+	let mut paths = Vec::new();
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--all" => all = true,
+			"-r" | "--regex" => {
+				let Some(pattern) = args.next() else {
+					eprintln!("{USAGE}");
+					return ExitCode::FAILURE;
+				};
+				match Regex::new(&pattern) {
+					Ok(compiled) => regex = Some(compiled),
+					Err(error) => {
+						eprintln!("error: invalid regex `{pattern}`: {error}");
+						return ExitCode::FAILURE;
+					}
+				}
+			}
+			"-h" | "--help" => {
+				println!("{USAGE}");
+				return ExitCode::SUCCESS;
+			}
+			_ => paths.push(arg),
+		}
+	}
+	let [old_path, new_path] = paths.as_slice() else {
+		eprintln!("{USAGE}");
+		return ExitCode::FAILURE;
+	};
+
+	let mut builds = Vec::new();
+	for path in [old_path, new_path] {
+		let data = match std::fs::read(path) {
+			Ok(data) => data,
+			Err(error) => {
+				eprintln!("error: cannot read {path}: {error}");
+				return ExitCode::FAILURE;
+			}
+		};
+		match scan::scan(&data) {
+			Ok((_, classes)) => builds.push(classes),
+			Err(error) => {
+				eprintln!("error: cannot scan {path}: {error}");
+				return ExitCode::FAILURE;
+			}
+		}
+	}
+
+	let diffs = diff::diff(&builds[0], &builds[1]);
+	let json: Vec<_> = diffs.iter()
+		.filter(|diff| all || diff.status != ClassStatus::Unchanged)
+		.filter(|diff| regex.as_ref().is_none_or(|regex| regex.is_match(&diff.class)))
+		.map(|diff| diff.to_json().to_string())
+		.collect();
+	println!("[");
+	for (index, class) in json.iter().enumerate() {
+		let comma = if index + 1 == json.len() { "" } else { "," };
+		println!("\t{class}{comma}");
+	}
+	println!("]");
+	ExitCode::SUCCESS
+}
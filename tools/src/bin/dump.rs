@@ -0,0 +1,111 @@
+//! Print the VTables of classes in an ELF or PE file.
+//! 
+//! ```text
+//! cppdvt-dump libserver.so zoo::Lizard
+//! cppdvt-dump --regex 'zoo::(Lizard|Snake)' server.dll
+//! cppdvt-dump --emit-rust libserver.so Lizard > lizard.rs
+//! ```
+//! 
+//! Classes are selected by their qualified or unqualified name,
+//! or with `--regex`, by a regular expression that matches anywhere in the qualified name.
+//! Without a class, every class is printed.
+//! ELF addresses are virtual addresses, and PE addresses are RVAs.
+
+use std::process::ExitCode;
+
+use cppdvt_tools::regex::Regex;
+use cppdvt_tools::scan::{self, ClassVTables};
+
+const USAGE: &str = "usage: cppdvt-dump [--regex] [--emit-rust] <file> [class]";
+
+fn main() -> ExitCode {
+	let mut regex = false;
+	let mut emit_rust = false;
+	let mut positional = Vec::new();
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"-r" | "--regex" => regex = true,
+			"--emit-rust" => emit_rust = true,
+			"-h" | "--help" => {
+				println!("{USAGE}");
+				return ExitCode::SUCCESS;
+			}
+			_ => positional.push(arg),
+		}
+	}
+	let (path, pattern) = match positional.as_slice() {
+		[path] => (path, None),
+		[path, pattern] => (path, Some(pattern.as_str())),
+		_ => {
+			eprintln!("{USAGE}");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	let filter: Box<dyn Fn(&ClassVTables) -> bool> = match (pattern, regex) {
+		(None, _) => Box::new(|_| true),
+		(Some(pattern), true) => match Regex::new(pattern) {
+			Ok(regex) => Box::new(move |class| regex.is_match(&class.class)),
+			Err(error) => {
+				eprintln!("error: invalid regex `{pattern}`: {error}");
+				return ExitCode::FAILURE;
+			}
+		},
+		(Some(pattern), false) => Box::new(move |class| class.class == pattern || class.short_name() == pattern),
+	};
+
+	let data = match std::fs::read(path) {
+		Ok(data) => data,
+		Err(error) => {
+			eprintln!("error: cannot read {path}: {error}");
+			return ExitCode::FAILURE;
+		}
+	};
+	let classes = match scan::scan(&data) {
+		Ok((_, classes)) => classes,
+		Err(error) => {
+			eprintln!("error: cannot scan {path}: {error}");
+			return ExitCode::FAILURE;
+		}
+	};
+	let selected: Vec<&ClassVTables> = classes.iter().filter(|class| filter(class)).collect();
+	if selected.is_empty() {
+		eprintln!("error: no matching classes with VTables in {path}");
+		return ExitCode::FAILURE;
+	}
+
+	if emit_rust {
+		println!("use cppdvt::vtable;");
+		for class in selected {
+			for decl in class.vtable_decls() {
+				println!();
+				print!("{decl}");
+			}
+		}
+		return ExitCode::SUCCESS;
+	}
+
+	for (index, class) in selected.into_iter().enumerate() {
+		if index != 0 {
+			println!();
+		}
+		println!("{} ({})", class.class, class.symbol);
+		for vtable in &class.vtables {
+			match &vtable.base {
+				None => println!("  vtable at {:#x}", vtable.address),
+				Some(base) => println!("  vtable at {:#x} for {base} at offset {}", vtable.address, vtable.offset),
+			}
+			for (slot_index, slot) in vtable.slots.iter().enumerate() {
+				let target = slot.target.map_or_else(|| "external".to_string(), |target| format!("{target:#x}"));
+				match (slot.function_name(), &slot.symbol) {
+					(Some(name), Some(symbol)) if name != *symbol => {
+						println!("    [{slot_index:>3}] {target:>12}  {name}  ({symbol})");
+					}
+					(Some(name), _) => println!("    [{slot_index:>3}] {target:>12}  {name}"),
+					(None, _) => println!("    [{slot_index:>3}] {target:>12}"),
+				}
+			}
+		}
+	}
+	ExitCode::SUCCESS
+}
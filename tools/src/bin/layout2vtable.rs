@@ -0,0 +1,83 @@
+//! Generate `vtable!` declarations from VTable layouts printed by a compiler.
+//! 
+//! ```text
+//! clang++ -Xclang -fdump-vtable-layouts -c pet.cpp > pet.txt
+//! cl /d1reportAllClassLayout /c pet.cpp > pet.txt
+//! cppdvt-layout2vtable [--msvc] pet.txt Pet Lizard > pet.rs
+//! ```
+//! 
+//! Both formats are detected automatically.
+//! `--msvc` only affects how types such as `wchar_t` are mapped.
+//! The layouts can also be read from standard input by passing `-` as the file.
+//! Without class names, every class in the dump is generated.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use cppdvt_tools::layout_dump;
+use cppdvt_tools::types::TypeMap;
+
+const USAGE: &str = "usage: cppdvt-layout2vtable [--msvc] <layouts.txt | -> [class...]";
+
+fn main() -> ExitCode {
+	let mut msvc = false;
+	let mut path = None;
+	let mut names = Vec::new();
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--msvc" => msvc = true,
+			"-h" | "--help" => {
+				println!("{USAGE}");
+				return ExitCode::SUCCESS;
+			}
+			_ if path.is_none() => path = Some(arg),
+			_ => names.push(arg),
+		}
+	}
+	let Some(path) = path else {
+		eprintln!("{USAGE}");
+		return ExitCode::FAILURE;
+	};
+
+	let mut input = String::new();
+	let read = if path == "-" {
+		std::io::stdin().read_to_string(&mut input).map(drop)
+	} else {
+		std::fs::read_to_string(&path).map(|contents| input = contents)
+	};
+	if let Err(error) = read {
+		eprintln!("error: cannot read {path}: {error}");
+		return ExitCode::FAILURE;
+	}
+	let layouts = layout_dump::parse(&input);
+
+	let selected = if names.is_empty() {
+		layouts.iter().collect::<Vec<_>>()
+	} else {
+		let mut selected = Vec::new();
+		for name in &names {
+			match layouts.iter().find(|layout| layout.class == *name || layout.short_name() == name) {
+				Some(layout) => selected.push(layout),
+				None => {
+					eprintln!("error: no VTable layout for `{name}` in {path}");
+					return ExitCode::FAILURE;
+				}
+			}
+		}
+		selected
+	};
+
+	let mut types = TypeMap::new(msvc);
+	for layout in &selected {
+		types.add_class(layout.class.clone(), format!("{}Vt", layout.short_name()));
+		types.add_class(layout.short_name(), format!("{}Vt", layout.short_name()));
+	}
+	println!("use cppdvt::vtable;");
+	for layout in selected {
+		for decl in layout.vtable_decls(&types) {
+			println!();
+			print!("{decl}");
+		}
+	}
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,477 @@
+//! Reading classes from clang's JSON AST dump,
+//! as produced by `clang -Xclang -ast-dump=json -fsyntax-only`,
+//! and laying out their VTables.
+
+use crate::emit::{SlotDecl, VTableDecl};
+use crate::json::Value;
+use crate::types::TypeMap;
+
+/// C++ ABI whose VTable layout rules to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Abi {
+	/// The Itanium C++ ABI, used by GCC and Clang outside of Windows.
+	Itanium,
+	/// The MSVC C++ ABI.
+	Msvc,
+}
+
+/// A type as spelled by clang.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Type {
+	/// The type as written.
+	pub written: String,
+	/// The type with typedefs resolved, if it differs.
+	pub desugared: Option<String>,
+}
+
+/// A member function of a class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Method {
+	/// Name of the function, or `~Class` for destructors.
+	pub name: String,
+	/// Names (possibly empty) and types of the parameters.
+	pub params: Vec<(String, Type)>,
+	/// `true` if the function takes variadic arguments.
+	pub variadic: bool,
+	/// Return type.
+	pub ret: Type,
+	/// `true` if the function is `const`-qualified.
+	pub is_const: bool,
+	/// `true` if the function is a destructor.
+	pub is_destructor: bool,
+	/// `true` if the function is declared `virtual`, `override` or `final`.
+	pub is_virtual: bool,
+}
+
+impl Method {
+	/// Return `true` if `self` would override `other` if they were virtual.
+	pub fn overrides(&self, other: &Method) -> bool {
+		if self.is_destructor || other.is_destructor {
+			return self.is_destructor && other.is_destructor;
+		}
+		self.name == other.name
+			&& self.is_const == other.is_const
+			&& self.variadic == other.variadic
+			&& self.params.len() == other.params.len()
+			&& self.params.iter().zip(&other.params).all(|((_, a), (_, b))| {
+				a.written == b.written || (a.desugared.is_some() && a.desugared == b.desugared)
+			})
+	}
+
+	/// Return the C++ signature of the function, as used in documentation.
+	pub fn signature(&self, class: &str) -> String {
+		let params: Vec<&str> = self.params.iter().map(|(_, ty)| ty.written.as_str()).collect();
+		let mut signature = format!("{class}::{}({}", self.name, params.join(", "));
+		if self.variadic {
+			signature.push_str(if params.is_empty() { "..." } else { ", ..." });
+		}
+		signature.push(')');
+		if self.is_const {
+			signature.push_str(" const");
+		}
+		signature
+	}
+}
+
+/// A base class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base {
+	/// Qualified name of the base class, if it could be resolved.
+	pub name: String,
+	/// `true` if the base class is virtual.
+	pub is_virtual: bool,
+}
+
+/// A class definition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Class {
+	/// Qualified name of the class.
+	pub name: String,
+	/// Base classes, in declaration order.
+	pub bases: Vec<Base>,
+	/// Member functions, in declaration order.
+	pub methods: Vec<Method>,
+}
+
+impl Class {
+	/// Return the unqualified name of the class.
+	pub fn short_name(&self) -> &str {
+		self.name.rsplit("::").next().unwrap_or(&self.name)
+	}
+}
+
+/// A VTable slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+	/// Qualified name of the class that introduced the slot.
+	pub class: String,
+	/// The function that introduced the slot.
+	pub method: Method,
+	/// Which part of the function the slot is for,
+	/// for functions with more than one slot.
+	pub kind: SlotKind,
+}
+
+/// Kind of a VTable slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+	/// An ordinary virtual function.
+	Function,
+	/// The Itanium complete object destructor.
+	CompleteDestructor,
+	/// The Itanium deleting destructor.
+	DeletingDestructor,
+	/// The MSVC scalar deleting destructor.
+	ScalarDeletingDestructor,
+}
+
+/// Classes and enumerations read from an AST dump.
+#[derive(Debug, Clone, Default)]
+pub struct Ast {
+	/// Class definitions, in order of appearance.
+	pub classes: Vec<Class>,
+	/// Qualified names and underlying types of enumerations.
+	pub enums: Vec<(String, String)>,
+}
+
+impl Ast {
+	/// Read the classes and enumerations of an AST dump.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::{clang_ast::Ast, json};
+	/// let ast = Ast::from_json(&json::parse(r#"{"kind": "TranslationUnitDecl", "inner": [
+	/// 	{"kind": "NamespaceDecl", "name": "zoo", "inner": [
+	/// 		{"kind": "CXXRecordDecl", "name": "Pet", "tagUsed": "class", "completeDefinition": true, "inner": [
+	/// 			{"kind": "CXXMethodDecl", "name": "speak", "type": {"qualType": "void ()"}, "virtual": true}
+	/// 		]}
+	/// 	]}
+	/// ]}"#).unwrap());
+	/// let pet = ast.class("Pet").unwrap();
+	/// assert_eq!(pet.name, "zoo::Pet");
+	/// assert!(pet.methods[0].is_virtual);
+	/// ```
+	pub fn from_json(root: &Value) -> Self {
+		let mut ast = Self::default();
+		ast.visit(root, "");
+		for index in 0..ast.classes.len() {
+			let scope = ast.classes[index].name.rsplit_once("::").map_or("", |(scope, _)| scope).to_string();
+			for base_index in 0..ast.classes[index].bases.len() {
+				let name = ast.resolve(&scope, &ast.classes[index].bases[base_index].name);
+				ast.classes[index].bases[base_index].name = name;
+			}
+		}
+		ast
+	}
+
+	fn visit(&mut self, node: &Value, scope: &str) {
+		let qualify = |name: &str| if scope.is_empty() { name.to_string() } else { format!("{scope}::{name}") };
+		match node.str("kind") {
+			Some("TranslationUnitDecl" | "LinkageSpecDecl") => {
+				for child in node.get("inner").map_or(&[][..], Value::elements) {
+					self.visit(child, scope);
+				}
+			}
+			Some("NamespaceDecl") => {
+				let scope = match node.str("name") {
+					Some(name) => qualify(name),
+					None => scope.to_string(),
+				};
+				for child in node.get("inner").map_or(&[][..], Value::elements) {
+					self.visit(child, &scope);
+				}
+			}
+			Some("EnumDecl") => {
+				if let Some(name) = node.str("name") {
+					let underlying = node.get("fixedUnderlyingType").and_then(|ty| ty.str("qualType")).unwrap_or("int");
+					self.enums.push((qualify(name), underlying.to_string()));
+				}
+			}
+			Some("CXXRecordDecl") if !node.flag("isImplicit") && node.flag("completeDefinition") => {
+				let Some(name) = node.str("name") else {
+					return;
+				};
+				let mut class = Class { name: qualify(name), ..Class::default() };
+				for base in node.get("bases").map_or(&[][..], Value::elements) {
+					if let Some(ty) = base.get("type").and_then(|ty| ty.str("qualType")) {
+						class.bases.push(Base { name: ty.to_string(), is_virtual: base.flag("isVirtual") });
+					}
+				}
+				let inner = node.get("inner").map_or(&[][..], Value::elements);
+				for child in inner {
+					if let Some(method) = method(child) {
+						class.methods.push(method);
+					}
+				}
+				let scope = class.name.clone();
+				self.classes.push(class);
+				for child in inner {
+					self.visit(child, &scope);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Resolve `name` as written in `scope` to the qualified name of a class.
+	fn resolve(&self, scope: &str, name: &str) -> String {
+		let name = name.trim_start_matches("class ").trim_start_matches("struct ").trim_start_matches("::");
+		let mut scope = scope;
+		loop {
+			let candidate = if scope.is_empty() { name.to_string() } else { format!("{scope}::{name}") };
+			if self.classes.iter().any(|class| class.name == candidate) {
+				return candidate;
+			}
+			if scope.is_empty() {
+				return name.to_string();
+			}
+			scope = scope.rsplit_once("::").map_or("", |(scope, _)| scope);
+		}
+	}
+
+	/// Find a class by its qualified or unqualified name.
+	/// 
+	/// If several classes have the same unqualified name, the first is returned.
+	pub fn class(&self, name: &str) -> Option<&Class> {
+		self.classes.iter().find(|class| class.name == name)
+			.or_else(|| self.classes.iter().find(|class| class.short_name() == name))
+	}
+
+	/// Return the base classes of `class` that were found in the dump.
+	fn bases<'a>(&'a self, class: &'a Class) -> impl Iterator<Item = (&'a Base, &'a Class)> + 'a {
+		class.bases.iter().filter_map(|base| Some((base, self.class(&base.name)?)))
+	}
+
+	/// Return `true` if the class has a VTable.
+	pub fn is_dynamic(&self, class: &Class) -> bool {
+		class.methods.iter().any(|method| method.is_virtual)
+			|| self.bases(class).any(|(base, class)| base.is_virtual || self.is_dynamic(class))
+	}
+
+	/// Return the primary base of `class`, whose VTable the VTable of `class` extends.
+	pub fn primary_base<'a>(&'a self, class: &'a Class) -> Option<&'a Class> {
+		self.bases(class).find(|(base, class)| !base.is_virtual && self.is_dynamic(class)).map(|(_, class)| class)
+	}
+
+	fn overrides_any(&self, class: &Class, method: &Method) -> bool {
+		self.bases(class).any(|(_, base)| {
+			base.methods.iter().any(|other| self.is_virtual(base, other) && method.overrides(other))
+				|| self.overrides_any(base, method)
+		})
+	}
+
+	/// Return `true` if `method` of `class` is virtual,
+	/// either by declaration or by overriding a virtual function of a base.
+	pub fn is_virtual(&self, class: &Class, method: &Method) -> bool {
+		method.is_virtual || self.overrides_any(class, method)
+	}
+
+	/// Lay out the VTable of `class`, not including the slots
+	/// of VTables of secondary bases.
+	/// 
+	/// Slots of the primary base come first,
+	/// followed by functions that do not override a function already in the VTable.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::{clang_ast::{Abi, Ast}, json};
+	/// let ast = Ast::from_json(&json::parse(r#"{"kind": "TranslationUnitDecl", "inner": [
+	/// 	{"kind": "CXXRecordDecl", "name": "Pet", "completeDefinition": true, "inner": [
+	/// 		{"kind": "CXXDestructorDecl", "name": "~Pet", "type": {"qualType": "void () noexcept"}, "virtual": true},
+	/// 		{"kind": "CXXMethodDecl", "name": "speak", "type": {"qualType": "void ()"}, "virtual": true}
+	/// 	]},
+	/// 	{"kind": "CXXRecordDecl", "name": "Lizard", "completeDefinition": true,
+	/// 		"bases": [{"type": {"qualType": "Pet"}}],
+	/// 		"inner": [
+	/// 			{"kind": "CXXMethodDecl", "name": "speak", "type": {"qualType": "void ()"}},
+	/// 			{"kind": "CXXMethodDecl", "name": "derp", "type": {"qualType": "void ()"}, "virtual": true}
+	/// 		]
+	/// 	}
+	/// ]}"#).unwrap());
+	/// let lizard = ast.class("Lizard").unwrap();
+	/// 
+	/// let names = |abi| ast.layout(lizard, abi).iter().map(|slot| slot.method.name.clone()).collect::<Vec<_>>();
+	/// assert_eq!(names(Abi::Itanium), ["~Pet", "~Pet", "speak", "derp"]);
+	/// assert_eq!(names(Abi::Msvc), ["~Pet", "speak", "derp"]);
+	/// ```
+	pub fn layout(&self, class: &Class, abi: Abi) -> Vec<Slot> {
+		let mut slots = self.primary_base(class).map_or_else(Vec::new, |base| self.layout(base, abi));
+		let inherited = slots.len();
+		let mut new = Vec::new();
+		for method in &class.methods {
+			if !self.is_virtual(class, method) {
+				continue;
+			}
+			let overrides = match abi {
+				Abi::Itanium => slots[..inherited].iter().any(|slot| method.overrides(&slot.method)),
+				Abi::Msvc => self.overrides_any(class, method),
+			};
+			if !overrides {
+				new.push(method);
+			}
+		}
+		if abi == Abi::Msvc {
+			// Overloads are grouped at the first declaration, in reverse order.
+			let mut grouped: Vec<&Method> = Vec::new();
+			for method in &new {
+				if grouped.iter().any(|other| other.name == method.name) {
+					continue;
+				}
+				grouped.extend(new.iter().rev().filter(|other| other.name == method.name));
+			}
+			new = grouped;
+		}
+		for method in new {
+			let mut push = |kind| slots.push(Slot { class: class.name.clone(), method: method.clone(), kind });
+			match (method.is_destructor, abi) {
+				(false, _) => push(SlotKind::Function),
+				(true, Abi::Itanium) => {
+					push(SlotKind::CompleteDestructor);
+					push(SlotKind::DeletingDestructor);
+				}
+				(true, Abi::Msvc) => push(SlotKind::ScalarDeletingDestructor),
+			}
+		}
+		slots
+	}
+
+	/// Create a [`TypeMap`] that knows about the enumerations in the dump,
+	/// and maps pointers to `classes` to pointers to their VTables.
+	pub fn type_map(&self, abi: Abi, classes: &[&Class]) -> TypeMap {
+		let mut map = TypeMap::new(abi == Abi::Msvc);
+		for (name, underlying) in &self.enums {
+			let underlying = map.map(underlying).rust;
+			let short = name.rsplit("::").next().unwrap_or(name);
+			map.add_alias(short, underlying.clone());
+			map.add_alias(name.clone(), underlying);
+		}
+		for class in classes {
+			map.add_class(class.name.clone(), vtable_name(class));
+			map.add_class(class.short_name(), vtable_name(class));
+		}
+		map
+	}
+
+	/// Generate a `vtable!` declaration for `class`.
+	/// 
+	/// Types that cannot be mapped to Rust are replaced with opaque pointers,
+	/// and the slot's documentation notes the original type.
+	pub fn vtable(&self, class: &Class, abi: Abi, types: &TypeMap) -> VTableDecl {
+		let map = |ty: &Type| {
+			let mapped = types.map(&ty.written);
+			match &ty.desugared {
+				Some(desugared) if mapped.opaque => types.map(desugared),
+				_ => mapped,
+			}
+		};
+		let mut vtable = VTableDecl {
+			name: vtable_name(class),
+			docs: vec![format!("VTable for `{}`.", class.name)],
+			slots: Vec::new(),
+		};
+		for slot in self.layout(class, abi) {
+			let method = &slot.method;
+			let mut decl = SlotDecl {
+				docs: vec![format!("`{}`", method.signature(&slot.class))],
+				variadic: method.variadic,
+				..SlotDecl::default()
+			};
+			match slot.kind {
+				SlotKind::Function => {
+					decl.name = method.name.clone();
+					for (name, ty) in &method.params {
+						let mapped = map(ty);
+						if mapped.opaque {
+							decl.docs.push(format!("FIXME: opaque parameter `{name}`: `{}`", ty.written));
+						}
+						decl.params.push((name.clone(), mapped.rust));
+					}
+					if ty_is_void(&method.ret) {
+						decl.ret = None;
+					} else {
+						let mapped = map(&method.ret);
+						if mapped.opaque {
+							decl.docs.push(format!("FIXME: opaque return type `{}`", method.ret.written));
+						}
+						decl.ret = Some(mapped.rust);
+					}
+				}
+				SlotKind::CompleteDestructor => decl.name = "complete_destructor".into(),
+				SlotKind::DeletingDestructor => decl.name = "deleting_destructor".into(),
+				SlotKind::ScalarDeletingDestructor => {
+					decl.name = "scalar_deleting_destructor".into();
+					decl.params.push(("flags".into(), "::core::ffi::c_uint".into()));
+					decl.ret = Some("*mut ::core::ffi::c_void".into());
+				}
+			}
+			vtable.slots.push(decl);
+		}
+		vtable.fix_names();
+		vtable
+	}
+}
+
+/// Return the name of the generated VTable struct for `class`.
+pub fn vtable_name(class: &Class) -> String {
+	format!("{}Vt", class.short_name())
+}
+
+fn ty_is_void(ty: &Type) -> bool {
+	ty.written.trim() == "void" || ty.desugared.as_deref().is_some_and(|ty| ty.trim() == "void")
+}
+
+/// Split a function type such as `int (char, ...) const noexcept`
+/// into its return type, parameter list and trailing qualifiers.
+pub(crate) fn split_function_type(ty: &str) -> Option<(&str, &str, &str)> {
+	let close = ty.rfind(')')?;
+	let mut depth = 0;
+	for (index, c) in ty[..=close].char_indices().rev() {
+		match c {
+			')' => depth += 1,
+			'(' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some((ty[..index].trim(), &ty[index + 1..close], &ty[close + 1..]));
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+fn method(node: &Value) -> Option<Method> {
+	let is_destructor = match node.str("kind")? {
+		"CXXMethodDecl" => false,
+		"CXXDestructorDecl" => true,
+		_ => return None,
+	};
+	if node.flag("isImplicit") && !is_destructor {
+		return None;
+	}
+	let ty = node.get("type")?;
+	let (ret, params, qualifiers) = split_function_type(ty.str("qualType")?)?;
+	let desugared_ret = ty.str("desugaredQualType")
+		.and_then(split_function_type)
+		.map(|(ret, ..)| ret.to_string())
+		.filter(|desugared| desugared != ret);
+	let inner = node.get("inner").map_or(&[][..], Value::elements);
+	let mut method = Method {
+		name: node.str("name")?.to_string(),
+		variadic: params.trim_end().ends_with("..."),
+		ret: Type { written: ret.to_string(), desugared: desugared_ret },
+		is_const: qualifiers.split_whitespace().any(|qualifier| qualifier == "const"),
+		is_destructor,
+		is_virtual: node.flag("virtual")
+			|| inner.iter().any(|child| matches!(child.str("kind"), Some("OverrideAttr" | "FinalAttr"))),
+		..Method::default()
+	};
+	for child in inner.iter().filter(|child| child.str("kind") == Some("ParmVarDecl")) {
+		let ty = child.get("type");
+		method.params.push((child.str("name").unwrap_or_default().to_string(), Type {
+			written: ty.and_then(|ty| ty.str("qualType")).unwrap_or_default().to_string(),
+			desugared: ty.and_then(|ty| ty.str("desugaredQualType")).map(str::to_string),
+		}));
+	}
+	Some(method)
+}
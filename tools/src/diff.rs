@@ -0,0 +1,304 @@
+//! Comparing the VTables of two builds of a binary.
+
+use crate::json::Value;
+use crate::scan::{ClassVTables, ScannedSlot, ScannedVTable};
+
+/// How a slot changed between two builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotChange {
+	/// The slot has the same index in both builds.
+	Same,
+	/// The slot was found at a different index.
+	Moved,
+	/// The slot only exists in the old build.
+	Removed,
+	/// The slot only exists in the new build.
+	Inserted,
+}
+
+impl SlotChange {
+	/// Return the name of the change, as written in JSON.
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Same => "same",
+			Self::Moved => "moved",
+			Self::Removed => "removed",
+			Self::Inserted => "inserted",
+		}
+	}
+}
+
+/// A slot in a [`VTableDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotDiff {
+	/// Index of the slot in the old build.
+	pub old: Option<usize>,
+	/// Index of the slot in the new build.
+	pub new: Option<usize>,
+	/// How the slot changed.
+	pub change: SlotChange,
+	/// Readable name of the function, if known.
+	pub name: Option<String>,
+}
+
+/// Differences between a VTable of a class in two builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VTableDiff {
+	/// Base class of the VTable, or `None` for the primary VTable.
+	pub base: Option<String>,
+	/// Number of slots in the old build.
+	pub old_len: usize,
+	/// Number of slots in the new build.
+	pub new_len: usize,
+	/// For each slot index in the old build, the index in the new build.
+	pub mapping: Vec<Option<usize>>,
+	/// Every slot, ordered by its index in the new build,
+	/// with removed slots placed after their predecessor.
+	pub slots: Vec<SlotDiff>,
+}
+
+impl VTableDiff {
+	/// Return `true` if no slot changed.
+	pub fn is_unchanged(&self) -> bool {
+		self.old_len == self.new_len && self.slots.iter().all(|slot| slot.change == SlotChange::Same)
+	}
+}
+
+/// Status of a class in a [`ClassDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassStatus {
+	/// The class has the same VTables in both builds.
+	Unchanged,
+	/// The VTables of the class changed.
+	Changed,
+	/// The class only exists in the new build.
+	Added,
+	/// The class only exists in the old build.
+	Removed,
+}
+
+impl ClassStatus {
+	/// Return the name of the status, as written in JSON.
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Unchanged => "unchanged",
+			Self::Changed => "changed",
+			Self::Added => "added",
+			Self::Removed => "removed",
+		}
+	}
+}
+
+/// Differences between the VTables of a class in two builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassDiff {
+	/// Readable name of the class.
+	pub class: String,
+	/// The RTTI name the class was matched by.
+	pub symbol: String,
+	/// Status of the class.
+	pub status: ClassStatus,
+	/// Differences of each VTable, for classes in both builds.
+	pub vtables: Vec<VTableDiff>,
+}
+
+/// Return the key that identifies the function in a slot across builds.
+fn key(slot: &ScannedSlot) -> Option<String> {
+	match (&slot.symbol, slot.hash) {
+		(Some(symbol), _) => Some(symbol.clone()),
+		(None, Some(hash)) => Some(format!("#{hash:016x}")),
+		(None, None) => None,
+	}
+}
+
+/// Align the slots of two VTables.
+/// 
+/// Slots are matched by the longest common subsequence of their keys,
+/// which are function symbols, or function body hashes for slots without one.
+/// Remaining slots with equal keys are considered moved.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::{diff::{diff_vtable, SlotChange}, scan::{ScannedSlot, ScannedVTable}};
+/// let vtable = |names: &[&str]| ScannedVTable {
+/// 	base: None,
+/// 	offset: 0,
+/// 	address: 0,
+/// 	slots: names.iter().map(|name| ScannedSlot {
+/// 		target: None,
+/// 		symbol: Some(name.to_string()),
+/// 		hash: None,
+/// 	}).collect(),
+/// };
+/// let diff = diff_vtable(&vtable(&["a", "b", "c", "d"]), &vtable(&["a", "x", "b", "d", "c"]));
+/// assert_eq!(diff.mapping, [Some(0), Some(2), Some(4), Some(3)]);
+/// let inserted: Vec<_> = diff.slots.iter().filter(|slot| slot.change == SlotChange::Inserted).collect();
+/// assert_eq!(inserted[0].new, Some(1));
+/// ```
+pub fn diff_vtable(old: &ScannedVTable, new: &ScannedVTable) -> VTableDiff {
+	let old_keys: Vec<Option<String>> = old.slots.iter().map(key).collect();
+	let new_keys: Vec<Option<String>> = new.slots.iter().map(key).collect();
+	let (n, m) = (old_keys.len(), new_keys.len());
+	let equal = |i: usize, j: usize| old_keys[i].is_some() && old_keys[i] == new_keys[j];
+
+	// Longest common subsequence, computed from the end.
+	let mut table = vec![vec![0usize; m + 1]; n + 1];
+	for i in (0..n).rev() {
+		for j in (0..m).rev() {
+			table[i][j] = if equal(i, j) {
+				table[i + 1][j + 1] + 1
+			} else {
+				table[i + 1][j].max(table[i][j + 1])
+			};
+		}
+	}
+	let mut mapping = vec![None; n];
+	let mut matched_new = vec![None; m];
+	let (mut i, mut j) = (0, 0);
+	while i < n && j < m {
+		if equal(i, j) {
+			mapping[i] = Some(j);
+			matched_new[j] = Some(i);
+			i += 1;
+			j += 1;
+		} else if table[i + 1][j] >= table[i][j + 1] {
+			i += 1;
+		} else {
+			j += 1;
+		}
+	}
+	// Slots that were reordered.
+	let mut moved = vec![false; n];
+	for i in 0..n {
+		if mapping[i].is_some() {
+			continue;
+		}
+		if let Some(j) = (0..m).find(|&j| matched_new[j].is_none() && equal(i, j)) {
+			mapping[i] = Some(j);
+			matched_new[j] = Some(i);
+			moved[i] = true;
+		}
+	}
+
+	// Removed slots are placed after the new position of the slot that preceded them.
+	let mut removed_after = vec![Vec::new(); m + 1];
+	for i in (0..n).filter(|&i| mapping[i].is_none()) {
+		let anchor = (0..i).rev().find_map(|k| mapping[k]).map_or(0, |j| j + 1);
+		removed_after[anchor].push(i);
+	}
+	let removed = |i: usize| SlotDiff {
+		old: Some(i),
+		new: None,
+		change: SlotChange::Removed,
+		name: old.slots[i].function_name(),
+	};
+	let mut slots: Vec<SlotDiff> = removed_after[0].iter().map(|&i| removed(i)).collect();
+	for j in 0..m {
+		let change = match matched_new[j] {
+			None => SlotChange::Inserted,
+			Some(i) if i == j && !moved[i] => SlotChange::Same,
+			Some(_) => SlotChange::Moved,
+		};
+		slots.push(SlotDiff { old: matched_new[j], new: Some(j), change, name: new.slots[j].function_name() });
+		slots.extend(removed_after[j + 1].iter().map(|&i| removed(i)));
+	}
+
+	VTableDiff { base: new.base.clone(), old_len: n, new_len: m, mapping, slots }
+}
+
+/// Match classes between two builds by their RTTI names and compare their VTables.
+/// 
+/// VTables of a class are matched by the base class they are for.
+pub fn diff(old: &[ClassVTables], new: &[ClassVTables]) -> Vec<ClassDiff> {
+	let mut diffs = Vec::new();
+	for new_class in new {
+		let Some(old_class) = old.iter().find(|class| class.symbol == new_class.symbol) else {
+			diffs.push(ClassDiff {
+				class: new_class.class.clone(),
+				symbol: new_class.symbol.clone(),
+				status: ClassStatus::Added,
+				vtables: Vec::new(),
+			});
+			continue;
+		};
+		let empty = ScannedVTable { base: None, offset: 0, address: 0, slots: Vec::new() };
+		let mut vtables = Vec::new();
+		for new_vtable in &new_class.vtables {
+			let old_vtable = old_class.vtables.iter().find(|vtable| vtable.base == new_vtable.base);
+			vtables.push(diff_vtable(old_vtable.unwrap_or(&empty), new_vtable));
+		}
+		for old_vtable in &old_class.vtables {
+			if !new_class.vtables.iter().any(|vtable| vtable.base == old_vtable.base) {
+				let mut diff = diff_vtable(old_vtable, &empty);
+				diff.base = old_vtable.base.clone();
+				vtables.push(diff);
+			}
+		}
+		let status = if vtables.iter().all(VTableDiff::is_unchanged) {
+			ClassStatus::Unchanged
+		} else {
+			ClassStatus::Changed
+		};
+		diffs.push(ClassDiff { class: new_class.class.clone(), symbol: new_class.symbol.clone(), status, vtables });
+	}
+	for old_class in old {
+		if !new.iter().any(|class| class.symbol == old_class.symbol) {
+			diffs.push(ClassDiff {
+				class: old_class.class.clone(),
+				symbol: old_class.symbol.clone(),
+				status: ClassStatus::Removed,
+				vtables: Vec::new(),
+			});
+		}
+	}
+	diffs
+}
+
+fn index(index: Option<usize>) -> Value {
+	index.map_or(Value::Null, |index| Value::Number(index as f64))
+}
+
+fn string(s: Option<&str>) -> Value {
+	s.map_or(Value::Null, |s| Value::String(s.to_string()))
+}
+
+impl ClassDiff {
+	/// Convert the differences to JSON.
+	/// 
+	/// ```json
+	/// {
+	/// 	"class": "zoo::Lizard",
+	/// 	"symbol": "_ZTVN3zoo6LizardE",
+	/// 	"status": "changed",
+	/// 	"vtables": [{
+	/// 		"base": null,
+	/// 		"old_len": 6,
+	/// 		"new_len": 7,
+	/// 		"mapping": [0, 1, 3, null, 4, 5],
+	/// 		"slots": [{"old": 2, "new": 3, "change": "moved", "name": "zoo::Lizard::speak()"}]
+	/// 	}]
+	/// }
+	/// ```
+	/// 
+	/// `mapping` maps each old slot index to its new index, or `null` if it was removed.
+	pub fn to_json(&self) -> Value {
+		let vtables = self.vtables.iter().map(|vtable| Value::Object(vec![
+			("base".into(), string(vtable.base.as_deref())),
+			("old_len".into(), Value::Number(vtable.old_len as f64)),
+			("new_len".into(), Value::Number(vtable.new_len as f64)),
+			("mapping".into(), Value::Array(vtable.mapping.iter().copied().map(index).collect())),
+			("slots".into(), Value::Array(vtable.slots.iter().map(|slot| Value::Object(vec![
+				("old".into(), index(slot.old)),
+				("new".into(), index(slot.new)),
+				("change".into(), Value::String(slot.change.as_str().into())),
+				("name".into(), string(slot.name.as_deref())),
+			])).collect())),
+		])).collect();
+		Value::Object(vec![
+			("class".into(), Value::String(self.class.clone())),
+			("symbol".into(), Value::String(self.symbol.clone())),
+			("status".into(), Value::String(self.status.as_str().into())),
+			("vtables".into(), Value::Array(vtables)),
+		])
+	}
+}
@@ -0,0 +1,171 @@
+//! Emission of `vtable!` declarations.
+
+use std::fmt;
+
+/// A VTable to be declared with `vtable!`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VTableDecl {
+	/// Name of the VTable struct.
+	pub name: String,
+	/// Lines of documentation for the VTable struct.
+	pub docs: Vec<String>,
+	/// Slots of the VTable, in order.
+	pub slots: Vec<SlotDecl>,
+}
+
+/// A slot of a VTable to be declared with `vtable!`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotDecl {
+	/// Name of the function pointer field.
+	pub name: String,
+	/// Lines of documentation for the field.
+	pub docs: Vec<String>,
+	/// Names and Rust types of the parameters, not including `this`.
+	pub params: Vec<(String, String)>,
+	/// `true` if the function takes variadic arguments.
+	pub variadic: bool,
+	/// Rust return type, if not `()`.
+	pub ret: Option<String>,
+}
+
+impl VTableDecl {
+	/// Make the names of all slots valid and distinct identifiers.
+	/// 
+	/// Names are converted to `snake_case`, and repeated names,
+	/// such as those of overloaded functions, get a numeric suffix.
+	pub fn fix_names(&mut self) {
+		let mut seen: Vec<String> = Vec::new();
+		for slot in &mut self.slots {
+			let base = rust_ident(&slot.name);
+			let mut name = base.clone();
+			let mut index = 1;
+			while seen.contains(&name) {
+				name = format!("{}_{index}", base.trim_start_matches("r#"));
+				index += 1;
+			}
+			seen.push(name.clone());
+			slot.name = name;
+			for (param_index, (param, _)) in slot.params.iter_mut().enumerate() {
+				*param = if param.is_empty() {
+					format!("arg{param_index}")
+				} else {
+					rust_ident(param)
+				};
+			}
+		}
+	}
+}
+
+impl fmt::Display for VTableDecl {
+	/// Write the `vtable!` invocation.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::emit::{SlotDecl, VTableDecl};
+	/// let mut vtable = VTableDecl {
+	/// 	name: "PetVt".into(),
+	/// 	docs: vec!["VTable for `Pet`.".into()],
+	/// 	slots: vec![
+	/// 		SlotDecl { name: "Speak".into(), ..SlotDecl::default() },
+	/// 		SlotDecl {
+	/// 			name: "Speak".into(),
+	/// 			params: vec![("times".into(), "u32".into())],
+	/// 			ret: Some("bool".into()),
+	/// 			..SlotDecl::default()
+	/// 		},
+	/// 	],
+	/// };
+	/// vtable.fix_names();
+	/// assert_eq!(vtable.to_string(), "\
+	/// vtable! {
+	/// 	/// VTable for `Pet`.
+	/// 	pub PetVt {
+	/// 		pub fn speak();
+	/// 		pub fn speak_1(times: u32) -> bool;
+	/// 	}
+	/// }
+	/// ");
+	/// ```
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "vtable! {{")?;
+		for doc in &self.docs {
+			writeln!(f, "\t/// {doc}")?;
+		}
+		writeln!(f, "\tpub {} {{", self.name)?;
+		for slot in &self.slots {
+			for doc in &slot.docs {
+				writeln!(f, "\t\t/// {doc}")?;
+			}
+			write!(f, "\t\tpub fn {}(", slot.name)?;
+			for (index, (name, ty)) in slot.params.iter().enumerate() {
+				if index != 0 {
+					write!(f, ", ")?;
+				}
+				write!(f, "{name}: {ty}")?;
+			}
+			if slot.variadic {
+				if !slot.params.is_empty() {
+					write!(f, ", ")?;
+				}
+				write!(f, "...")?;
+			}
+			write!(f, ")")?;
+			if let Some(ret) = &slot.ret {
+				write!(f, " -> {ret}")?;
+			}
+			writeln!(f, ";")?;
+		}
+		writeln!(f, "\t}}")?;
+		writeln!(f, "}}")
+	}
+}
+
+/// Convert a C++ identifier to a `snake_case` Rust identifier.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::emit::rust_ident;
+/// assert_eq!(rust_ident("GetHTTPHeader"), "get_http_header");
+/// assert_eq!(rust_ident("type"), "r#type");
+/// assert_eq!(rust_ident("operator=="), "operator_eq");
+/// ```
+pub fn rust_ident(name: &str) -> String {
+	let name = name
+		.replace("operator==", "operator_eq")
+		.replace("operator=", "operator_assign")
+		.replace("operator()", "operator_call")
+		.replace("operator[]", "operator_index");
+	let chars: Vec<char> = name.chars().collect();
+	let mut ident = String::new();
+	for (index, &c) in chars.iter().enumerate() {
+		if c.is_ascii_uppercase() {
+			let prev = index.checked_sub(1).map(|index| chars[index]);
+			let next = chars.get(index + 1);
+			let boundary = prev.is_some_and(|prev| {
+				prev.is_ascii_lowercase() || prev.is_ascii_digit()
+					|| (prev.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase))
+			});
+			if boundary && !ident.ends_with('_') {
+				ident.push('_');
+			}
+			ident.push(c.to_ascii_lowercase());
+		} else if c.is_ascii_alphanumeric() || c == '_' {
+			ident.push(c);
+		} else if !ident.ends_with('_') {
+			ident.push('_');
+		}
+	}
+	let ident = ident.trim_end_matches('_').to_string();
+	if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+		return format!("_{ident}");
+	}
+	match ident.as_str() {
+		"self" | "super" | "crate" | "Self" | "this" => format!("{ident}_"),
+		"as" | "async" | "await" | "box" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+		| "extern" | "false" | "fn" | "for" | "gen" | "if" | "impl" | "in" | "let" | "loop" | "match" | "mod"
+		| "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait" | "true" | "try"
+		| "type" | "unsafe" | "use" | "where" | "while" | "yield" | "abstract" | "become" | "do"
+		| "final" | "macro" | "override" | "priv" | "typeof" | "unsized" | "virtual" => format!("r#{ident}"),
+		_ => ident,
+	}
+}
@@ -0,0 +1,326 @@
+//! Minimal JSON parser, sufficient for reading compiler output.
+
+use std::fmt;
+
+/// Parsed JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	/// `null`.
+	Null,
+	/// `true` or `false`.
+	Bool(bool),
+	/// Any number.
+	Number(f64),
+	/// A string.
+	String(String),
+	/// An array.
+	Array(Vec<Value>),
+	/// An object, with its members in order.
+	Object(Vec<(String, Value)>),
+}
+
+impl Value {
+	/// Return the member `key` if this is an object that has it.
+	pub fn get(&self, key: &str) -> Option<&Value> {
+		match self {
+			Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+			_ => None,
+		}
+	}
+
+	/// Return the string if this is a string.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(s) => Some(s),
+			_ => None,
+		}
+	}
+
+	/// Return the boolean if this is a boolean.
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Self::Bool(b) => Some(*b),
+			_ => None,
+		}
+	}
+
+	/// Return the elements if this is an array, or an empty slice otherwise.
+	pub fn elements(&self) -> &[Value] {
+		match self {
+			Self::Array(elements) => elements,
+			_ => &[],
+		}
+	}
+
+	/// Return the string member `key`, if any.
+	pub fn str(&self, key: &str) -> Option<&str> {
+		self.get(key).and_then(Value::as_str)
+	}
+
+	/// Return `true` if the member `key` is `true`.
+	pub fn flag(&self, key: &str) -> bool {
+		self.get(key).and_then(Value::as_bool).unwrap_or(false)
+	}
+}
+
+impl fmt::Display for Value {
+	/// Write the value as compact JSON.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::json::Value;
+	/// let value = Value::Object(vec![
+	/// 	("name".into(), Value::String("say \"hi\"".into())),
+	/// 	("slots".into(), Value::Array(vec![Value::Number(0.0), Value::Null])),
+	/// ]);
+	/// assert_eq!(value.to_string(), r#"{"name":"say \"hi\"","slots":[0,null]}"#);
+	/// ```
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Null => f.write_str("null"),
+			Self::Bool(b) => write!(f, "{b}"),
+			Self::Number(n) => write!(f, "{n}"),
+			Self::String(s) => write_string(f, s),
+			Self::Array(elements) => {
+				f.write_str("[")?;
+				for (index, element) in elements.iter().enumerate() {
+					if index != 0 {
+						f.write_str(",")?;
+					}
+					write!(f, "{element}")?;
+				}
+				f.write_str("]")
+			}
+			Self::Object(members) => {
+				f.write_str("{")?;
+				for (index, (key, value)) in members.iter().enumerate() {
+					if index != 0 {
+						f.write_str(",")?;
+					}
+					write_string(f, key)?;
+					write!(f, ":{value}")?;
+				}
+				f.write_str("}")
+			}
+		}
+	}
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+	f.write_str("\"")?;
+	for c in s.chars() {
+		match c {
+			'"' => f.write_str("\\\"")?,
+			'\\' => f.write_str("\\\\")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+			c => write!(f, "{c}")?,
+		}
+	}
+	f.write_str("\"")
+}
+
+/// Error returned when JSON cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+	/// Byte offset of the error in the input.
+	pub offset: usize,
+	/// Description of the error.
+	pub message: &'static str,
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at byte {}", self.message, self.offset)
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Parse a JSON document.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::json::{parse, Value};
+/// let value = parse(r#"{"kind": "CXXRecordDecl", "inner": [1, true, null]}"#).unwrap();
+/// assert_eq!(value.str("kind"), Some("CXXRecordDecl"));
+/// assert_eq!(value.get("inner").unwrap().elements()[1], Value::Bool(true));
+/// ```
+/// 
+/// # Errors
+/// Returns an error if `input` is not valid JSON.
+pub fn parse(input: &str) -> Result<Value, Error> {
+	let mut parser = Parser { input: input.as_bytes(), offset: 0 };
+	let value = parser.value()?;
+	parser.whitespace();
+	if parser.offset != parser.input.len() {
+		return Err(parser.error("trailing characters"));
+	}
+	Ok(value)
+}
+
+struct Parser<'a> {
+	input: &'a [u8],
+	offset: usize,
+}
+
+impl Parser<'_> {
+	fn error(&self, message: &'static str) -> Error {
+		Error { offset: self.offset, message }
+	}
+
+	fn whitespace(&mut self) {
+		while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.offset) {
+			self.offset += 1;
+		}
+	}
+
+	fn peek(&mut self) -> Option<u8> {
+		self.whitespace();
+		self.input.get(self.offset).copied()
+	}
+
+	fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), Error> {
+		if self.peek() == Some(byte) {
+			self.offset += 1;
+			Ok(())
+		} else {
+			Err(self.error(message))
+		}
+	}
+
+	fn keyword(&mut self, keyword: &[u8], value: Value) -> Result<Value, Error> {
+		if self.input[self.offset..].starts_with(keyword) {
+			self.offset += keyword.len();
+			Ok(value)
+		} else {
+			Err(self.error("invalid literal"))
+		}
+	}
+
+	fn value(&mut self) -> Result<Value, Error> {
+		match self.peek() {
+			Some(b'{') => self.object(),
+			Some(b'[') => self.array(),
+			Some(b'"') => self.string().map(Value::String),
+			Some(b't') => self.keyword(b"true", Value::Bool(true)),
+			Some(b'f') => self.keyword(b"false", Value::Bool(false)),
+			Some(b'n') => self.keyword(b"null", Value::Null),
+			Some(b'-' | b'0'..=b'9') => self.number(),
+			Some(_) => Err(self.error("unexpected character")),
+			None => Err(self.error("unexpected end of input")),
+		}
+	}
+
+	fn object(&mut self) -> Result<Value, Error> {
+		self.offset += 1;
+		let mut members = Vec::new();
+		if self.peek() == Some(b'}') {
+			self.offset += 1;
+			return Ok(Value::Object(members));
+		}
+		loop {
+			if self.peek() != Some(b'"') {
+				return Err(self.error("expected string key"));
+			}
+			let key = self.string()?;
+			self.expect(b':', "expected `:`")?;
+			members.push((key, self.value()?));
+			match self.peek() {
+				Some(b',') => self.offset += 1,
+				Some(b'}') => {
+					self.offset += 1;
+					return Ok(Value::Object(members));
+				}
+				_ => return Err(self.error("expected `,` or `}`")),
+			}
+		}
+	}
+
+	fn array(&mut self) -> Result<Value, Error> {
+		self.offset += 1;
+		let mut elements = Vec::new();
+		if self.peek() == Some(b']') {
+			self.offset += 1;
+			return Ok(Value::Array(elements));
+		}
+		loop {
+			elements.push(self.value()?);
+			match self.peek() {
+				Some(b',') => self.offset += 1,
+				Some(b']') => {
+					self.offset += 1;
+					return Ok(Value::Array(elements));
+				}
+				_ => return Err(self.error("expected `,` or `]`")),
+			}
+		}
+	}
+
+	fn number(&mut self) -> Result<Value, Error> {
+		let start = self.offset;
+		while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.input.get(self.offset) {
+			self.offset += 1;
+		}
+		std::str::from_utf8(&self.input[start..self.offset])
+			.ok()
+			.and_then(|number| number.parse().ok())
+			.map(Value::Number)
+			.ok_or_else(|| self.error("invalid number"))
+	}
+
+	fn hex4(&mut self) -> Result<u32, Error> {
+		let digits = self.input.get(self.offset..self.offset + 4)
+			.and_then(|digits| std::str::from_utf8(digits).ok())
+			.and_then(|digits| u32::from_str_radix(digits, 16).ok())
+			.ok_or_else(|| self.error("invalid unicode escape"))?;
+		self.offset += 4;
+		Ok(digits)
+	}
+
+	fn string(&mut self) -> Result<String, Error> {
+		self.offset += 1;
+		let mut bytes = Vec::new();
+		loop {
+			let Some(&byte) = self.input.get(self.offset) else {
+				return Err(self.error("unterminated string"));
+			};
+			self.offset += 1;
+			match byte {
+				b'"' => break,
+				b'\\' => {
+					let Some(&escape) = self.input.get(self.offset) else {
+						return Err(self.error("unterminated string"));
+					};
+					self.offset += 1;
+					let c = match escape {
+						b'"' => '"',
+						b'\\' => '\\',
+						b'/' => '/',
+						b'b' => '\u{8}',
+						b'f' => '\u{c}',
+						b'n' => '\n',
+						b'r' => '\r',
+						b't' => '\t',
+						b'u' => {
+							let mut code = self.hex4()?;
+							if (0xd800..0xdc00).contains(&code) && self.input[self.offset..].starts_with(b"\\u") {
+								self.offset += 2;
+								let low = self.hex4()?;
+								code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+							}
+							char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+						}
+						_ => return Err(self.error("invalid escape")),
+					};
+					let mut buffer = [0; 4];
+					bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+				}
+				_ => bytes.push(byte),
+			}
+		}
+		String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"))
+	}
+}
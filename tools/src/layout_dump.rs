@@ -0,0 +1,533 @@
+//! Reading VTable layouts printed by compilers,
+//! with clang's `-Xclang -fdump-vtable-layouts`
+//! or MSVC's `/d1reportAllClassLayout`.
+
+use std::fmt;
+
+use cppdvt::SlotInfo;
+
+use crate::clang_ast::{split_function_type, SlotKind};
+use crate::emit::{rust_ident, SlotDecl, VTableDecl};
+use crate::types::TypeMap;
+
+/// The VTables of a class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+	/// Qualified name of the class.
+	pub class: String,
+	/// The VTables of the class, starting with the primary VTable.
+	pub vtables: Vec<DumpVTable>,
+}
+
+/// A single VTable of a class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DumpVTable {
+	/// Name of the base class this VTable is for,
+	/// or `None` for the primary VTable.
+	pub base: Option<String>,
+	/// Offset of the VTable pointer in the class.
+	pub offset: i64,
+	/// Slots, starting at the address point.
+	pub slots: Vec<DumpSlot>,
+}
+
+/// A slot of a VTable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpSlot {
+	/// Qualified name of the class that defines the function.
+	pub class: String,
+	/// Unqualified name of the function.
+	pub name: String,
+	/// Which part of the function the slot is for.
+	pub kind: SlotKind,
+	/// The signature of the function, if the dump includes it.
+	pub signature: Option<Signature>,
+	/// `true` if the function is pure virtual.
+	pub pure: bool,
+	/// `true` if the slot holds a thunk that adjusts `this` or the return value.
+	pub thunk: bool,
+	/// The entry as printed by the compiler.
+	pub text: String,
+}
+
+/// The signature of a function in a VTable dump.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signature {
+	/// The return type.
+	pub ret: String,
+	/// The parameter types.
+	pub params: Vec<String>,
+	/// `true` if the function takes variadic arguments.
+	pub variadic: bool,
+	/// `true` if the function is `const`-qualified.
+	pub is_const: bool,
+}
+
+/// Parse every layout in compiler output, in either format.
+/// 
+/// Classes whose VTables are printed more than once are only returned once.
+pub fn parse(text: &str) -> Vec<Layout> {
+	let mut layouts = parse_clang(text);
+	layouts.extend(parse_msvc(text));
+	layouts
+}
+
+fn push_layout(layouts: &mut Vec<Layout>, layout: Layout) {
+	if !layout.vtables.is_empty() && !layouts.iter().any(|other| other.class == layout.class) {
+		layouts.push(layout);
+	}
+}
+
+/// Parse the output of clang's `-Xclang -fdump-vtable-layouts`.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::layout_dump::parse_clang;
+/// let layouts = parse_clang("\
+/// Vtable for 'Lizard' (6 entries).
+///    0 | offset_to_top (0)
+///    1 | Lizard RTTI
+///        -- (Lizard, 0) vtable address --
+///        -- (Pet, 0) vtable address --
+///    2 | Lizard::~Lizard() [complete]
+///    3 | Lizard::~Lizard() [deleting]
+///    4 | void Pet::speak() [pure]
+///    5 | void Lizard::derp(unsigned int)
+/// ");
+/// let slots = &layouts[0].vtables[0].slots;
+/// assert_eq!(layouts[0].class, "Lizard");
+/// assert_eq!(slots.len(), 4);
+/// assert_eq!((slots[2].class.as_str(), slots[2].name.as_str(), slots[2].pure), ("Pet", "speak", true));
+/// assert_eq!(slots[3].signature.as_ref().unwrap().params, ["unsigned int"]);
+/// ```
+pub fn parse_clang(text: &str) -> Vec<Layout> {
+	let mut layouts = Vec::new();
+	let mut current: Option<Layout> = None;
+	let mut named = false;
+	for line in text.lines() {
+		if let Some(rest) = line.strip_prefix("Vtable for '") {
+			if let Some(layout) = current.take() {
+				push_layout(&mut layouts, layout);
+			}
+			if let Some((class, _)) = rest.split_once("' (") {
+				current = Some(Layout { class: class.to_string(), vtables: Vec::new() });
+			}
+			continue;
+		}
+		let Some(layout) = &mut current else {
+			continue;
+		};
+		let trimmed = line.trim();
+		if trimmed.is_empty() || !line.starts_with(' ') {
+			push_layout(&mut layouts, current.take().unwrap());
+			continue;
+		}
+		if let Some(point) = trimmed.strip_prefix("-- (").and_then(|point| point.strip_suffix(") vtable address --")) {
+			// The primary VTable has an address point for the class itself,
+			// and other VTables are named after their first address point.
+			if let (Some(vtable), Some((base, offset))) = (layout.vtables.last_mut(), point.rsplit_once(", ")) {
+				if base == layout.class {
+					vtable.base = None;
+					named = true;
+				} else if !named {
+					vtable.base = Some(base.to_string());
+					vtable.offset = offset.trim().parse().unwrap_or(0);
+					named = true;
+				}
+			}
+			continue;
+		}
+		if trimmed.starts_with('[') {
+			if let Some(slot) = layout.vtables.last_mut().and_then(|vtable| vtable.slots.last_mut()) {
+				slot.thunk |= trimmed.contains("adjustment");
+			}
+			continue;
+		}
+		let Some((_, entry)) = trimmed.split_once(" | ") else {
+			continue;
+		};
+		if entry.starts_with("offset_to_top") {
+			layout.vtables.push(DumpVTable::default());
+			named = false;
+		} else if entry.starts_with("vcall_offset") || entry.starts_with("vbase_offset") || entry.ends_with(" RTTI") {
+			// Part of the VTable prefix.
+		} else if let Some(vtable) = layout.vtables.last_mut() {
+			if let Some(slot) = clang_slot(entry) {
+				vtable.slots.push(slot);
+			}
+		}
+	}
+	if let Some(layout) = current {
+		push_layout(&mut layouts, layout);
+	}
+	layouts
+}
+
+fn clang_slot(entry: &str) -> Option<DumpSlot> {
+	let mut text = entry.trim();
+	let mut kind = SlotKind::Function;
+	let mut pure = false;
+	while let Some((rest, annotation)) = text.strip_suffix(']').and_then(|text| text.rsplit_once(" [")) {
+		match annotation {
+			"complete" => kind = SlotKind::CompleteDestructor,
+			"deleting" => kind = SlotKind::DeletingDestructor,
+			"scalar deleting" | "vector deleting" => kind = SlotKind::ScalarDeletingDestructor,
+			"pure" => pure = true,
+			_ => {}
+		}
+		text = rest.trim_end();
+	}
+	let (head, params, qualifiers) = split_function_type(text)?;
+	// The qualified name starts after the return type, which ends with a space, `*` or `&`.
+	let mut depth = 0;
+	let mut name_start = 0;
+	for (index, c) in head.char_indices() {
+		match c {
+			'<' | '(' => depth += 1,
+			'>' | ')' => depth -= 1,
+			' ' | '*' | '&' if depth == 0 && !head[index + 1..].starts_with(['*', '&', ' ']) => name_start = index + 1,
+			_ => {}
+		}
+	}
+	let (ret, qualified) = (head[..name_start].trim(), &head[name_start..]);
+	let (class, name) = qualified.rsplit_once("::").unwrap_or(("", qualified));
+	if name.starts_with('~') && kind == SlotKind::Function {
+		kind = SlotKind::DeletingDestructor;
+	}
+	let mut params = split_params(params);
+	let variadic = params.last().is_some_and(|param| param == "...");
+	if variadic {
+		params.pop();
+	}
+	if params.len() == 1 && params[0] == "void" {
+		params.clear();
+	}
+	Some(DumpSlot {
+		class: class.to_string(),
+		name: name.to_string(),
+		kind,
+		signature: Some(Signature {
+			ret: if ret.is_empty() { "void".into() } else { ret.to_string() },
+			params,
+			variadic,
+			is_const: qualifiers.split_whitespace().any(|qualifier| qualifier == "const"),
+		}),
+		pure,
+		thunk: false,
+		text: entry.trim().to_string(),
+	})
+}
+
+/// Split a parameter list on top-level commas.
+fn split_params(params: &str) -> Vec<String> {
+	let mut result = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+	for (index, c) in params.char_indices() {
+		match c {
+			'<' | '(' | '[' => depth += 1,
+			'>' | ')' | ']' => depth -= 1,
+			',' if depth == 0 => {
+				result.push(params[start..index].trim().to_string());
+				start = index + 1;
+			}
+			_ => {}
+		}
+	}
+	let last = params[start..].trim();
+	if !last.is_empty() {
+		result.push(last.to_string());
+	}
+	result
+}
+
+/// Parse the output of MSVC's `/d1reportAllClassLayout`
+/// or `/d1reportSingleClassLayout`.
+/// 
+/// MSVC does not print signatures, so [`DumpSlot::signature`] is always `None`.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::{clang_ast::SlotKind, layout_dump::parse_msvc};
+/// let layouts = parse_msvc("\
+/// Lizard::$vftable@:
+/// 	| &Lizard_meta
+/// 	|  0
+///  0	| &Lizard::{dtor}
+///  1	| &Pet::speak
+///  2	| &Lizard::derp
+/// 
+/// Lizard::$vftable@Scaly@zoo@:
+/// 	| -8
+///  0	| &thunk: this-=8; goto Lizard::shed
+/// ");
+/// let lizard = &layouts[0];
+/// assert_eq!(lizard.vtables[0].slots[0].kind, SlotKind::ScalarDeletingDestructor);
+/// assert_eq!(lizard.vtables[0].slots[1].name, "speak");
+/// assert_eq!(lizard.vtables[1].base.as_deref(), Some("zoo::Scaly"));
+/// assert_eq!(lizard.vtables[1].offset, 8);
+/// assert!(lizard.vtables[1].slots[0].thunk);
+/// ```
+pub fn parse_msvc(text: &str) -> Vec<Layout> {
+	let mut layouts: Vec<Layout> = Vec::new();
+	let mut current: Option<(String, DumpVTable)> = None;
+	let mut seen_offset = false;
+	let finish = |layouts: &mut Vec<Layout>, current: Option<(String, DumpVTable)>| {
+		let Some((class, vtable)) = current else {
+			return;
+		};
+		match layouts.iter_mut().find(|layout| layout.class == class) {
+			Some(layout) => {
+				if !layout.vtables.iter().any(|other| other.base == vtable.base) {
+					layout.vtables.push(vtable);
+				}
+			}
+			None => layouts.push(Layout { class, vtables: vec![vtable] }),
+		}
+	};
+	for line in text.lines() {
+		if let Some((class, suffix)) = line.trim().strip_suffix(':').and_then(|line| line.split_once("::$vftable@")) {
+			finish(&mut layouts, current.take());
+			let base = suffix.split('@').filter(|part| !part.is_empty()).rev().collect::<Vec<_>>().join("::");
+			let vtable = DumpVTable { base: (!base.is_empty()).then_some(base), ..DumpVTable::default() };
+			current = Some((class.to_string(), vtable));
+			seen_offset = false;
+			continue;
+		}
+		let Some((_, vtable)) = &mut current else {
+			continue;
+		};
+		let Some((index, entry)) = line.split_once('|') else {
+			finish(&mut layouts, current.take());
+			continue;
+		};
+		let entry = entry.trim();
+		if index.trim().is_empty() {
+			if !seen_offset {
+				if let Ok(offset) = entry.parse::<i64>() {
+					vtable.offset = -offset;
+					seen_offset = true;
+				}
+			}
+			continue;
+		}
+		let target = entry.trim_start_matches('&');
+		let (thunk, target) = match target.split_once("goto ") {
+			Some((_, target)) => (true, target.trim()),
+			None => (target.starts_with("thunk"), target),
+		};
+		let (class, name) = target.rsplit_once("::").unwrap_or(("", target));
+		let kind = if name == "{dtor}" || name.starts_with('~') || name.contains("deleting destructor") {
+			SlotKind::ScalarDeletingDestructor
+		} else {
+			SlotKind::Function
+		};
+		vtable.slots.push(DumpSlot {
+			class: class.to_string(),
+			name: name.to_string(),
+			kind,
+			signature: None,
+			pure: name == "_purecall",
+			thunk,
+			text: entry.to_string(),
+		});
+	}
+	finish(&mut layouts, current);
+	layouts
+}
+
+impl Layout {
+	/// Return the primary VTable.
+	pub fn primary(&self) -> Option<&DumpVTable> {
+		self.vtables.iter().find(|vtable| vtable.base.is_none())
+	}
+
+	/// Return the VTable for the base class `base`.
+	pub fn secondary(&self, base: &str) -> Option<&DumpVTable> {
+		self.vtables.iter().find(|vtable| {
+			vtable.base.as_deref().is_some_and(|name| name == base || name.rsplit("::").next() == Some(base))
+		})
+	}
+
+	/// Return the unqualified name of the class.
+	pub fn short_name(&self) -> &str {
+		self.class.rsplit("::").next().unwrap_or(&self.class)
+	}
+
+	/// Generate `vtable!` declarations for each VTable of the class.
+	/// 
+	/// The primary VTable is named `ClassVt`,
+	/// and the VTable for base `Base` is named `ClassAsBaseVt`.
+	/// Functions without a known signature are declared without parameters,
+	/// and are documented as such.
+	pub fn vtable_decls(&self, types: &TypeMap) -> Vec<VTableDecl> {
+		self.vtables.iter().map(|vtable| {
+			let (name, doc) = match &vtable.base {
+				None => (format!("{}Vt", self.short_name()), format!("VTable for `{}`.", self.class)),
+				Some(base) => (
+					format!("{}As{}Vt", self.short_name(), base.rsplit("::").next().unwrap_or(base)),
+					format!("VTable for `{}` in `{}`, at offset {}.", base, self.class, vtable.offset),
+				),
+			};
+			let mut decl = VTableDecl { name, docs: vec![doc], slots: Vec::new() };
+			for slot in &vtable.slots {
+				decl.slots.push(slot.decl(types));
+			}
+			decl.fix_names();
+			decl
+		}).collect()
+	}
+}
+
+impl DumpSlot {
+	fn decl(&self, types: &TypeMap) -> SlotDecl {
+		let mut decl = SlotDecl { docs: vec![format!("`{}`", self.text)], ..SlotDecl::default() };
+		match self.kind {
+			SlotKind::CompleteDestructor => decl.name = "complete_destructor".into(),
+			SlotKind::DeletingDestructor => decl.name = "deleting_destructor".into(),
+			SlotKind::ScalarDeletingDestructor => {
+				decl.name = "scalar_deleting_destructor".into();
+				decl.params.push(("flags".into(), "::core::ffi::c_uint".into()));
+				decl.ret = Some("*mut ::core::ffi::c_void".into());
+			}
+			SlotKind::Function => {
+				decl.name = self.name.clone();
+				let Some(signature) = &self.signature else {
+					decl.docs.push("FIXME: the signature of this function is unknown".into());
+					return decl;
+				};
+				for (index, param) in signature.params.iter().enumerate() {
+					let mapped = types.map(param);
+					if mapped.opaque {
+						decl.docs.push(format!("FIXME: opaque parameter `{param}`"));
+					}
+					decl.params.push((format!("arg{index}"), mapped.rust));
+				}
+				decl.variadic = signature.variadic;
+				if let Some(mapped) = types.map_return(&signature.ret) {
+					if mapped.opaque {
+						decl.docs.push(format!("FIXME: opaque return type `{}`", signature.ret));
+					}
+					decl.ret = Some(mapped.rust);
+				}
+			}
+		}
+		decl
+	}
+}
+
+/// Difference between a Rust VTable and a dumped VTable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+	/// Index of the first slot that differs.
+	pub index: usize,
+	/// The slot in the dump, if any.
+	pub expected: Option<String>,
+	/// The slot in the Rust VTable, if any.
+	pub found: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match (&self.expected, &self.found) {
+			(Some(expected), Some(found)) => write!(f, "slot {} is `{found}`, expected `{expected}`", self.index),
+			(Some(expected), None) => write!(f, "missing slot {}, expected `{expected}`", self.index),
+			(None, Some(found)) => write!(f, "extra slot {}: `{found}`", self.index),
+			(None, None) => write!(f, "slot {} differs", self.index),
+		}
+	}
+}
+
+impl std::error::Error for Mismatch {}
+
+fn is_destructor_name(name: &str) -> bool {
+	matches!(name, "destructor" | "complete_destructor" | "deleting_destructor" | "scalar_deleting_destructor")
+}
+
+impl DumpVTable {
+	/// Check that the slots of a Rust VTable are in the same order as in the dump.
+	/// 
+	/// Slot names are compared after conversion to `snake_case`.
+	/// A Rust slot may add a numeric suffix, such as `speak_1` for an overload of `Speak`,
+	/// as [`VTableDecl::fix_names`] does.
+	/// Destructor slots match any slot named `destructor`,
+	/// `complete_destructor`, `deleting_destructor` or `scalar_deleting_destructor`.
+	/// If the dump includes signatures, the numbers of parameters must also match.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt::{vtable, VTableInfo};
+	/// # use cppdvt_tools::layout_dump::parse_clang;
+	/// vtable! {
+	/// 	PetVt {
+	/// 		pub fn destructor();
+	/// 		pub fn deleting_destructor();
+	/// 		pub fn speak();
+	/// 		pub fn speak_1(times: u32);
+	/// 	}
+	/// }
+	/// vtable! {
+	/// 	NamedPetVt {
+	/// 		pub fn destructor();
+	/// 		pub fn deleting_destructor();
+	/// 		pub fn speak();
+	/// 		pub fn speak_name(name: u32);
+	/// 	}
+	/// }
+	/// 
+	/// let layouts = parse_clang("\
+	/// Vtable for 'Pet' (6 entries).
+	///    0 | offset_to_top (0)
+	///    1 | Pet RTTI
+	///        -- (Pet, 0) vtable address --
+	///    2 | Pet::~Pet() [complete]
+	///    3 | Pet::~Pet() [deleting]
+	///    4 | void Pet::Speak()
+	///    5 | void Pet::Speak(unsigned int)
+	/// ");
+	/// let dump = layouts[0].primary().unwrap();
+	/// assert_eq!(dump.check(PetVt::SLOTS), Ok(()));
+	/// assert_eq!(dump.check(&PetVt::SLOTS[..3]).unwrap_err().index, 3);
+	/// // `speak_name` is another function, not an overload of `Speak`.
+	/// assert_eq!(dump.check(NamedPetVt::SLOTS).unwrap_err().index, 3);
+	/// ```
+	/// 
+	/// # Errors
+	/// Returns the first slot that differs.
+	pub fn check(&self, slots: &[SlotInfo]) -> Result<(), Mismatch> {
+		for index in 0..self.slots.len().max(slots.len()) {
+			let expected = self.slots.get(index);
+			let found = slots.get(index);
+			let matches = match (expected, found) {
+				(Some(expected), Some(found)) => {
+					let name_matches = if expected.kind == SlotKind::Function {
+						let name = rust_ident(&expected.name);
+						let name = name.trim_start_matches("r#");
+						let found_name = found.name.trim_start_matches("r#");
+						found_name == name || found_name.strip_prefix(name)
+							.and_then(|suffix| suffix.strip_prefix('_'))
+							.is_some_and(|index| !index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit()))
+					} else {
+						is_destructor_name(found.name)
+					};
+					let params_match = match (&expected.signature, expected.kind) {
+						(Some(signature), SlotKind::Function) => {
+							found.params().filter(|(name, _)| *name != "...").count() == signature.params.len()
+						}
+						_ => true,
+					};
+					name_matches && params_match
+				}
+				_ => false,
+			};
+			if !matches {
+				return Err(Mismatch {
+					index,
+					expected: expected.map(|slot| slot.text.clone()),
+					found: found.map(|slot| slot.name.to_string()),
+				});
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,13 @@
+//! Code generators for declaring C++ virtual function tables with [`cppdvt`].
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+pub mod clang_ast;
+pub mod diff;
+pub mod emit;
+pub mod json;
+pub mod layout_dump;
+pub mod names;
+pub mod regex;
+pub mod scan;
+pub mod types;
@@ -0,0 +1,97 @@
+//! Readable names for classes and functions found in binaries.
+
+/// Return the readable name of an Itanium mangled class name,
+/// such as `N3zoo6LizardE` in `_ZTVN3zoo6LizardE`.
+/// 
+/// Names that cannot be demangled are returned unchanged.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::names::itanium_class_name;
+/// assert_eq!(itanium_class_name("N3zoo6LizardE"), "zoo::Lizard");
+/// assert_eq!(itanium_class_name("3Pet"), "Pet");
+/// assert_eq!(itanium_class_name("N3zoo3PenIiEE"), "zoo::Pen<int>");
+/// ```
+pub fn itanium_class_name(mangled: &str) -> String {
+	cppdvt::demangle_type(mangled).unwrap_or_else(|| mangled.to_string())
+}
+
+/// Return the readable name of an MSVC type descriptor name,
+/// such as `.?AVLizard@zoo@@`.
+/// 
+/// Names that cannot be undecorated are returned unchanged.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::names::msvc_class_name;
+/// assert_eq!(msvc_class_name(".?AVLizard@zoo@@"), "zoo::Lizard");
+/// assert_eq!(msvc_class_name(".?AUPet@@"), "Pet");
+/// assert_eq!(msvc_class_name(".?AV?$Pen@H@zoo@@"), "zoo::Pen<int>");
+/// ```
+pub fn msvc_class_name(decorated: &str) -> String {
+	cppdvt::ClassName::from_msvc(decorated).map_or_else(|| decorated.to_string(), |name| name.to_string())
+}
+
+/// Return the readable name of a function symbol,
+/// or the symbol itself if it cannot be demangled.
+/// 
+/// Itanium destructors are followed by their kind, as they share a name.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::names::function_name;
+/// assert_eq!(function_name("_ZN3zoo6Lizard5speakEv"), "zoo::Lizard::speak()");
+/// assert_eq!(function_name("_ZN3zoo6LizardD1Ev"), "zoo::Lizard::~Lizard() [complete]");
+/// assert_eq!(function_name("?speak@Lizard@zoo@@UEAAXXZ"), "public: virtual void __cdecl zoo::Lizard::speak(void)");
+/// assert_eq!(function_name("__cxa_pure_virtual"), "__cxa_pure_virtual");
+/// ```
+pub fn function_name(symbol: &str) -> String {
+	if symbol.starts_with('?') {
+		return cppdvt::undecorate(symbol).unwrap_or_else(|| symbol.to_string());
+	}
+	let Some(name) = cppdvt::demangle(symbol) else {
+		return symbol.to_string();
+	};
+	match slot_name(symbol) {
+		Some(slot) if slot.ends_with("_destructor") => format!("{name} [{}]", slot.trim_end_matches("_destructor")),
+		_ => name,
+	}
+}
+
+/// Return the name of the VTable slot for the function `symbol`,
+/// if it is a plain member function.
+/// 
+/// Itanium destructors are named `complete_destructor` or `deleting_destructor`.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::names::slot_name;
+/// assert_eq!(slot_name("_ZN3zoo6Lizard5speakEv").as_deref(), Some("speak"));
+/// assert_eq!(slot_name("_ZThn8_N3zoo6Lizard4shedEv").as_deref(), Some("shed"));
+/// assert_eq!(slot_name("_ZN3zoo6LizardD0Ev").as_deref(), Some("deleting_destructor"));
+/// assert_eq!(slot_name("_ZNK3zoo3PeneqERKS0_").as_deref(), Some("operator=="));
+/// assert_eq!(
+/// 	slot_name("_ZNSt13basic_filebufIcSt11char_traitsIcEE7seekoffElSt12_Ios_SeekdirSt13_Ios_Openmode").as_deref(),
+/// 	Some("seekoff"),
+/// );
+/// assert_eq!(slot_name("_ZNSt13basic_filebufIcSt11char_traitsIcEED1Ev").as_deref(), Some("complete_destructor"));
+/// assert_eq!(slot_name("__cxa_pure_virtual"), None);
+/// ```
+pub fn slot_name(symbol: &str) -> Option<String> {
+	let name = cppdvt::ClassName::from_itanium_function(symbol)?;
+	if name.scope().is_empty() {
+		return None;
+	}
+	let name = name.name();
+	if name.starts_with('~') {
+		// Destructors take no parameters, so the kind is right before `Ev`.
+		let encoding = symbol.split('.').next()?.strip_suffix("Ev")?;
+		return match encoding.as_bytes()[encoding.len().checked_sub(2)?..] {
+			[b'D', b'0'] => Some("deleting_destructor".to_string()),
+			[b'D', b'1' | b'2'] => Some("complete_destructor".to_string()),
+			_ => None,
+		};
+	}
+	// Drop ABI tags, such as `[abi:cxx11]`.
+	Some(name.split('[').next().unwrap_or(name).to_string())
+}
@@ -0,0 +1,224 @@
+//! Small backtracking regular expression matcher for selecting classes by name.
+//! 
+//! Supported syntax: literals, `.`, `[...]` and `[^...]` classes with ranges,
+//! `*`, `+` and `?`, `(...)` groups, `|` alternation, `^` and `$` anchors,
+//! and `\` to escape a metacharacter.
+
+use std::fmt;
+
+#[derive(Debug, Clone)]
+enum Node {
+	Char(char),
+	Any,
+	Class { negated: bool, ranges: Vec<(char, char)> },
+	Start,
+	End,
+	Group(Vec<Vec<Node>>),
+	Repeat { node: Box<Node>, min: usize, max: Option<usize> },
+}
+
+/// A compiled regular expression.
+#[derive(Debug, Clone)]
+pub struct Regex {
+	alternatives: Vec<Vec<Node>>,
+}
+
+/// Error returned when a regular expression cannot be compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+	/// Character offset of the error in the pattern.
+	pub offset: usize,
+	/// Description of the error.
+	pub message: &'static str,
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at character {}", self.message, self.offset)
+	}
+}
+
+impl std::error::Error for Error {}
+
+struct Parser {
+	chars: Vec<char>,
+	offset: usize,
+}
+
+impl Parser {
+	fn error(&self, message: &'static str) -> Error {
+		Error { offset: self.offset, message }
+	}
+
+	fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, Error> {
+		let mut alternatives = vec![self.sequence()?];
+		while self.chars.get(self.offset) == Some(&'|') {
+			self.offset += 1;
+			alternatives.push(self.sequence()?);
+		}
+		Ok(alternatives)
+	}
+
+	fn sequence(&mut self) -> Result<Vec<Node>, Error> {
+		let mut nodes = Vec::new();
+		while let Some(&c) = self.chars.get(self.offset) {
+			let node = match c {
+				'|' | ')' => break,
+				'*' | '+' | '?' => {
+					let Some(node) = nodes.pop() else {
+						return Err(self.error("nothing to repeat"));
+					};
+					self.offset += 1;
+					let (min, max) = match c {
+						'*' => (0, None),
+						'+' => (1, None),
+						_ => (0, Some(1)),
+					};
+					Node::Repeat { node: Box::new(node), min, max }
+				}
+				'(' => {
+					self.offset += 1;
+					let alternatives = self.alternatives()?;
+					if self.chars.get(self.offset) != Some(&')') {
+						return Err(self.error("unclosed group"));
+					}
+					self.offset += 1;
+					Node::Group(alternatives)
+				}
+				'[' => {
+					self.offset += 1;
+					self.class()?
+				}
+				'.' => {
+					self.offset += 1;
+					Node::Any
+				}
+				'^' => {
+					self.offset += 1;
+					Node::Start
+				}
+				'$' => {
+					self.offset += 1;
+					Node::End
+				}
+				'\\' => {
+					self.offset += 1;
+					let c = *self.chars.get(self.offset).ok_or_else(|| self.error("trailing backslash"))?;
+					self.offset += 1;
+					Node::Char(c)
+				}
+				_ => {
+					self.offset += 1;
+					Node::Char(c)
+				}
+			};
+			nodes.push(node);
+		}
+		Ok(nodes)
+	}
+
+	fn class(&mut self) -> Result<Node, Error> {
+		let negated = self.chars.get(self.offset) == Some(&'^');
+		if negated {
+			self.offset += 1;
+		}
+		let mut ranges = Vec::new();
+		let mut first = true;
+		loop {
+			let mut c = *self.chars.get(self.offset).ok_or_else(|| self.error("unclosed class"))?;
+			self.offset += 1;
+			if c == ']' && !first {
+				break;
+			}
+			first = false;
+			if c == '\\' {
+				c = *self.chars.get(self.offset).ok_or_else(|| self.error("trailing backslash"))?;
+				self.offset += 1;
+			}
+			let end = match (self.chars.get(self.offset), self.chars.get(self.offset + 1)) {
+				(Some('-'), Some(&end)) if end != ']' => {
+					self.offset += 2;
+					end
+				}
+				_ => c,
+			};
+			ranges.push((c, end));
+		}
+		Ok(Node::Class { negated, ranges })
+	}
+}
+
+impl Regex {
+	/// Compile a regular expression.
+	/// 
+	/// # Errors
+	/// Returns an error if the pattern is not valid.
+	pub fn new(pattern: &str) -> Result<Self, Error> {
+		let mut parser = Parser { chars: pattern.chars().collect(), offset: 0 };
+		let alternatives = parser.alternatives()?;
+		if parser.offset != parser.chars.len() {
+			return Err(parser.error("unmatched `)`"));
+		}
+		Ok(Self { alternatives })
+	}
+
+	/// Return `true` if the expression matches anywhere in `text`.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::regex::Regex;
+	/// let regex = Regex::new(r"^zoo::(Lizard|Snake)[0-9]*$").unwrap();
+	/// assert!(regex.is_match("zoo::Lizard"));
+	/// assert!(regex.is_match("zoo::Snake2"));
+	/// assert!(!regex.is_match("zoo::Pet"));
+	/// assert!(Regex::new("Pet").unwrap().is_match("zoo::Pet"));
+	/// ```
+	pub fn is_match(&self, text: &str) -> bool {
+		let chars: Vec<char> = text.chars().collect();
+		(0..=chars.len()).any(|start| {
+			let group = Node::Group(self.alternatives.clone());
+			matches(&chars, start, &[&group], &mut |_| true)
+		})
+	}
+}
+
+/// Match `nodes` against `chars` at `at`, calling `rest` with each end position.
+fn matches(chars: &[char], at: usize, nodes: &[&Node], rest: &mut dyn FnMut(usize) -> bool) -> bool {
+	let Some((node, tail)) = nodes.split_first() else {
+		return rest(at);
+	};
+	let mut next = |at| matches(chars, at, tail, rest);
+	match node {
+		Node::Char(c) => chars.get(at) == Some(c) && next(at + 1),
+		Node::Any => at < chars.len() && next(at + 1),
+		Node::Class { negated, ranges } => chars.get(at).is_some_and(|c| {
+			ranges.iter().any(|&(start, end)| (start..=end).contains(c)) != *negated
+		}) && next(at + 1),
+		Node::Start => at == 0 && next(at),
+		Node::End => at == chars.len() && next(at),
+		Node::Group(alternatives) => alternatives.iter().any(|alternative| {
+			let alternative: Vec<&Node> = alternative.iter().collect();
+			matches(chars, at, &alternative, &mut next)
+		}),
+		Node::Repeat { node, min, max } => repeat(chars, at, node, *min, *max, 0, &mut next),
+	}
+}
+
+fn repeat(
+	chars: &[char],
+	at: usize,
+	node: &Node,
+	min: usize,
+	max: Option<usize>,
+	count: usize,
+	rest: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+	// Greedy: try one more repetition first, without looping on empty matches.
+	if max.is_none_or(|max| count < max)
+		&& matches(chars, at, &[node], &mut |end| {
+			end != at && repeat(chars, end, node, min, max, count + 1, rest)
+		}) {
+		return true;
+	}
+	count >= min && rest(at)
+}
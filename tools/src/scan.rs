@@ -0,0 +1,273 @@
+//! Finding the VTables of classes in ELF and PE files.
+
+use std::fmt;
+
+use cppdvt::{ElfFile, ElfPointer, PeFile};
+
+use crate::emit::{SlotDecl, VTableDecl};
+use crate::names;
+
+/// Format of a scanned file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	/// An ELF file, with Itanium VTables. Addresses are virtual addresses.
+	Elf,
+	/// A PE image, with MSVC VTables. Addresses are RVAs.
+	Pe,
+}
+
+/// The VTables of a class found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassVTables {
+	/// Readable name of the class.
+	pub class: String,
+	/// The VTable symbol or RTTI name the class was found through.
+	pub symbol: String,
+	/// The VTables of the class, starting with the primary VTable.
+	pub vtables: Vec<ScannedVTable>,
+}
+
+/// A VTable found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedVTable {
+	/// Readable name of the base class this VTable is for,
+	/// or `None` for the primary VTable.
+	pub base: Option<String>,
+	/// Offset of the VTable pointer in the complete object.
+	pub offset: i64,
+	/// Address of the first slot.
+	pub address: u64,
+	/// The slots.
+	pub slots: Vec<ScannedSlot>,
+}
+
+/// A slot of a [`ScannedVTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedSlot {
+	/// Address of the function, if it is in the file.
+	pub target: Option<u64>,
+	/// Symbol of the function, if known.
+	pub symbol: Option<String>,
+	/// Hash of the body of the function, if it is in the file.
+	/// See [`body_hash`].
+	pub hash: Option<u64>,
+}
+
+impl ScannedSlot {
+	/// Return the readable name of the function, if known.
+	pub fn function_name(&self) -> Option<String> {
+		self.symbol.as_deref().map(names::function_name)
+	}
+}
+
+/// Hash the body of a function for comparing functions across builds.
+/// 
+/// The 32-bit operands of x86 `call` and `jmp` instructions are ignored,
+/// since they change whenever the code around them moves.
+/// This is a heuristic, as those opcodes may also appear within other instructions.
+/// 
+/// # Examples
+/// ```
+/// # use cppdvt_tools::scan::body_hash;
+/// let a = [0x55, 0xe8, 0x10, 0x00, 0x00, 0x00, 0xc3];
+/// let b = [0x55, 0xe8, 0x80, 0x20, 0x00, 0x00, 0xc3];
+/// assert_eq!(body_hash(&a), body_hash(&b));
+/// assert_ne!(body_hash(&a), body_hash(&[0x55, 0xc3]));
+/// ```
+pub fn body_hash(body: &[u8]) -> u64 {
+	// FNV-1a.
+	let mut hash = 0xcbf2_9ce4_8422_2325u64;
+	let mut skip = 0;
+	for &byte in body {
+		let byte = if skip > 0 {
+			skip -= 1;
+			0
+		} else {
+			if matches!(byte, 0xe8 | 0xe9) {
+				skip = 4;
+			}
+			byte
+		};
+		hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+	}
+	hash
+}
+
+/// Maximum number of bytes of a function to hash when its size is unknown.
+const MAX_BODY: usize = 256;
+
+/// Return the body of a function whose size is unknown,
+/// which is assumed to end at the first `ret` or `int3` padding.
+fn guess_body(bytes: &[u8]) -> &[u8] {
+	let bytes = &bytes[..bytes.len().min(MAX_BODY)];
+	match bytes.iter().position(|&byte| matches!(byte, 0xc3 | 0xcc)) {
+		Some(end) => &bytes[..=end],
+		None => bytes,
+	}
+}
+
+/// Error returned when a file cannot be scanned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Find the VTables of every class in an ELF or PE file.
+/// 
+/// # Errors
+/// Returns an error if the file is neither an ELF file nor a PE image,
+/// or cannot be parsed.
+pub fn scan(data: &[u8]) -> Result<(Format, Vec<ClassVTables>), Error> {
+	if data.starts_with(b"\x7fELF") {
+		let elf = ElfFile::parse(data).map_err(|error| Error(error.to_string()))?;
+		Ok((Format::Elf, scan_elf(&elf)))
+	} else if data.starts_with(b"MZ") {
+		let pe = PeFile::parse(data).map_err(|error| Error(error.to_string()))?;
+		Ok((Format::Pe, scan_pe(&pe)))
+	} else {
+		Err(Error("not an ELF file or a PE image".into()))
+	}
+}
+
+/// Find the VTables of every class in an ELF file.
+pub fn scan_elf(elf: &ElfFile<'_>) -> Vec<ClassVTables> {
+	let mut classes = Vec::new();
+	for vtable in elf.vtables() {
+		let typeinfo = vtable.primary().and_then(|primary| elf.typeinfo(primary.typeinfo));
+		let mut class = ClassVTables {
+			class: names::itanium_class_name(vtable.class_name()),
+			symbol: vtable.symbol.name.to_string(),
+			vtables: Vec::new(),
+		};
+		for (index, point) in vtable.address_points.iter().enumerate() {
+			let offset = -point.offset_to_top;
+			let base = (index != 0).then(|| {
+				typeinfo.as_ref()
+					.and_then(|typeinfo| typeinfo.bases.iter().find(|base| !base.is_virtual && base.offset == offset))
+					.and_then(|base| elf.typeinfo(base.typeinfo))
+					.and_then(|base| base.name)
+					.map_or_else(|| format!("<base at {offset}>"), names::itanium_class_name)
+			});
+			let slots = point.slots.iter().map(|slot| ScannedSlot {
+				target: match slot.target {
+					ElfPointer::Address(address) => Some(address),
+					_ => None,
+				},
+				symbol: slot.symbol.map(str::to_string),
+				hash: match slot.target {
+					ElfPointer::Address(address) => elf.bytes_at(address).map(|bytes| {
+						match elf.function_at(address).map(|symbol| symbol.size as usize) {
+							Some(size) if size > 0 => body_hash(&bytes[..size.min(bytes.len())]),
+							_ => body_hash(guess_body(bytes)),
+						}
+					}),
+					_ => None,
+				},
+			}).collect();
+			class.vtables.push(ScannedVTable { base, offset, address: point.address, slots });
+		}
+		classes.push(class);
+	}
+	classes
+}
+
+/// Find the VTables of every class in a PE image.
+pub fn scan_pe(pe: &PeFile<'_>) -> Vec<ClassVTables> {
+	let mut classes: Vec<ClassVTables> = Vec::new();
+	for vtable in pe.vtables() {
+		let locator = &vtable.locator;
+		let offset = locator.offset as i64;
+		let base = (offset != 0).then(|| {
+			locator.hierarchy.bases.iter().skip(1)
+				.find(|base| base.mdisp as i64 == offset)
+				.map_or_else(|| format!("<base at {offset}>"), |base| names::msvc_class_name(base.name))
+		});
+		let scanned = ScannedVTable {
+			base,
+			offset,
+			address: vtable.rva as u64,
+			slots: vtable.slots.iter().map(|&rva| ScannedSlot {
+				target: Some(rva as u64),
+				symbol: None,
+				hash: pe.bytes_at(rva).map(|bytes| body_hash(guess_body(bytes))),
+			}).collect(),
+		};
+		match classes.iter_mut().find(|class| class.symbol == locator.name) {
+			Some(class) => class.vtables.push(scanned),
+			None => classes.push(ClassVTables {
+				class: names::msvc_class_name(locator.name),
+				symbol: locator.name.to_string(),
+				vtables: vec![scanned],
+			}),
+		}
+	}
+	for class in &mut classes {
+		class.vtables.sort_by_key(|vtable| vtable.offset);
+	}
+	classes
+}
+
+impl ClassVTables {
+	/// Return the unqualified name of the class.
+	pub fn short_name(&self) -> &str {
+		let name = self.class.split('<').next().unwrap_or(&self.class);
+		name.rsplit("::").next().unwrap_or(name)
+	}
+
+	/// Generate skeleton `vtable!` declarations for the VTables of the class.
+	/// 
+	/// Slots are named after their function if its symbol is known,
+	/// and `unknown_N` otherwise, where `N` is the slot index.
+	/// Signatures are unknown, so every slot is declared without parameters.
+	pub fn vtable_decls(&self) -> Vec<VTableDecl> {
+		let short = crate::emit::rust_ident(self.short_name());
+		let short = short.trim_start_matches("r#");
+		let mut upper = String::new();
+		for part in short.split('_') {
+			let mut chars = part.chars();
+			if let Some(first) = chars.next() {
+				upper.extend(first.to_uppercase());
+				upper.push_str(chars.as_str());
+			}
+		}
+		self.vtables.iter().map(|vtable| {
+			let (name, doc) = match &vtable.base {
+				None => (format!("{upper}Vt"), format!("VTable for `{}`.", self.class)),
+				Some(base) => {
+					let base_short = base.split('<').next().unwrap_or(base);
+					let base_short = base_short.rsplit("::").next().unwrap_or(base_short);
+					let base_ident: String = base_short.chars().filter(char::is_ascii_alphanumeric).collect();
+					(
+						format!("{upper}As{base_ident}Vt"),
+						format!("VTable for `{base}` in `{}`, at offset {}.", self.class, vtable.offset),
+					)
+				}
+			};
+			let slots = vtable.slots.iter().enumerate().map(|(index, slot)| {
+				let mut docs = Vec::new();
+				if let Some(target) = slot.target {
+					docs.push(format!("`{target:#x}`"));
+				}
+				if let Some(name) = slot.function_name() {
+					docs.push(format!("`{name}`"));
+				}
+				SlotDecl {
+					name: slot.symbol.as_deref()
+						.and_then(names::slot_name)
+						.unwrap_or_else(|| format!("unknown_{index}")),
+					docs: vec![docs.join(": ")],
+					..SlotDecl::default()
+				}
+			}).collect();
+			let mut decl = VTableDecl { name, docs: vec![doc], slots };
+			decl.fix_names();
+			decl
+		}).collect()
+	}
+}
@@ -0,0 +1,154 @@
+//! Mapping of C++ types, as spelled by compilers, to Rust types.
+
+use std::collections::HashMap;
+
+/// Rust spelling of a mapped C++ type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapped {
+	/// The Rust type.
+	pub rust: String,
+	/// `true` if the C++ type was not understood
+	/// and was replaced with an opaque pointer.
+	pub opaque: bool,
+}
+
+/// Maps C++ types to Rust types.
+/// 
+/// Types that cannot be mapped fall back to `*mut c_void`,
+/// which is correct for pointers and references to unknown types
+/// and lets generated code compile for anything else.
+#[derive(Debug, Clone, Default)]
+pub struct TypeMap {
+	msvc: bool,
+	classes: HashMap<String, String>,
+	aliases: HashMap<String, String>,
+}
+
+impl TypeMap {
+	/// Create a map for the Itanium C++ ABI,
+	/// or for the MSVC C++ ABI if `msvc` is `true`.
+	pub fn new(msvc: bool) -> Self {
+		Self { msvc, ..Self::default() }
+	}
+
+	/// Map pointers to the C++ class `cpp` to pointers to
+	/// `VtObject<vtable>`.
+	pub fn add_class(&mut self, cpp: impl Into<String>, vtable: impl Into<String>) {
+		self.classes.insert(cpp.into(), vtable.into());
+	}
+
+	/// Map the C++ type `cpp` to the Rust type `rust`,
+	/// for example an enumeration to its underlying type.
+	pub fn add_alias(&mut self, cpp: impl Into<String>, rust: impl Into<String>) {
+		self.aliases.insert(cpp.into(), rust.into());
+	}
+
+	/// Map the C++ type `cpp`.
+	/// 
+	/// # Examples
+	/// ```
+	/// # use cppdvt_tools::types::TypeMap;
+	/// let mut map = TypeMap::new(false);
+	/// map.add_class("ns::Pet", "PetVt");
+	/// assert_eq!(map.map("unsigned int").rust, "u32");
+	/// assert_eq!(map.map("const char *").rust, "*const ::core::ffi::c_char");
+	/// assert_eq!(map.map("ns::Pet &").rust, "*mut ::cppdvt::VtObject<PetVt>");
+	/// 
+	/// let unknown = map.map("std::string");
+	/// assert_eq!(unknown.rust, "*mut ::core::ffi::c_void");
+	/// assert!(unknown.opaque);
+	/// ```
+	pub fn map(&self, cpp: &str) -> Mapped {
+		match self.map_value(cpp) {
+			Some(rust) => Mapped { rust, opaque: false },
+			None => Mapped { rust: "*mut ::core::ffi::c_void".into(), opaque: true },
+		}
+	}
+
+	/// Map a return type, where `void` maps to `None`.
+	pub fn map_return(&self, cpp: &str) -> Option<Mapped> {
+		if strip_cv(cpp) == "void" {
+			None
+		} else {
+			Some(self.map(cpp))
+		}
+	}
+
+	fn map_value(&self, cpp: &str) -> Option<String> {
+		let ty = strip_cv(cpp);
+		if let Some(pointee) = ty.strip_suffix("&&").or_else(|| ty.strip_suffix('&')).or_else(|| ty.strip_suffix('*')) {
+			return Some(self.map_pointer(pointee));
+		}
+		if ty.contains("(*)") || ty.contains("(&)") {
+			return Some("*mut ::core::ffi::c_void".into());
+		}
+		if let Some(rust) = self.aliases.get(ty) {
+			return Some(rust.clone());
+		}
+		let rust = match ty {
+			"bool" => "bool",
+			"char" => "::core::ffi::c_char",
+			"signed char" | "int8_t" | "std::int8_t" => "i8",
+			"unsigned char" | "uint8_t" | "std::uint8_t" => "u8",
+			"short" | "short int" | "signed short" | "int16_t" | "std::int16_t" => "i16",
+			"unsigned short" | "unsigned short int" | "uint16_t" | "std::uint16_t" | "char16_t" => "u16",
+			"int" | "signed" | "signed int" | "int32_t" | "std::int32_t" => "i32",
+			"unsigned" | "unsigned int" | "uint32_t" | "std::uint32_t" | "char32_t" => "u32",
+			"long" | "long int" | "signed long" => "::core::ffi::c_long",
+			"unsigned long" | "unsigned long int" => "::core::ffi::c_ulong",
+			"long long" | "long long int" | "int64_t" | "std::int64_t" | "__int64" => "i64",
+			"unsigned long long" | "unsigned long long int" | "uint64_t" | "std::uint64_t" | "unsigned __int64" => "u64",
+			"size_t" | "std::size_t" | "uintptr_t" | "std::uintptr_t" => "usize",
+			"ptrdiff_t" | "std::ptrdiff_t" | "ssize_t" | "intptr_t" | "std::intptr_t" => "isize",
+			"float" => "f32",
+			"double" => "f64",
+			"wchar_t" if self.msvc => "u16",
+			"wchar_t" => "i32",
+			_ => return None,
+		};
+		Some(rust.into())
+	}
+
+	fn map_pointer(&self, pointee: &str) -> String {
+		let pointee = pointee.trim();
+		let (is_const, pointee) = if let Some(pointee) = pointee.strip_prefix("const ") {
+			(true, pointee)
+		} else if let Some(pointee) = pointee.strip_suffix(" const") {
+			(true, pointee)
+		} else {
+			(false, pointee)
+		};
+		let mutability = if is_const { "*const" } else { "*mut" };
+		let pointee = strip_cv(pointee);
+		let target = if pointee == "void" {
+			"::core::ffi::c_void".into()
+		} else if let Some(vtable) = self.classes.get(pointee) {
+			format!("::cppdvt::VtObject<{vtable}>")
+		} else {
+			match self.map_value(pointee) {
+				Some(rust) => rust,
+				None => "::core::ffi::c_void".into(),
+			}
+		};
+		format!("{mutability} {target}")
+	}
+}
+
+/// Strip whitespace, elaborated type specifiers,
+/// and qualifiers that do not affect the ABI from a type.
+fn strip_cv(ty: &str) -> &str {
+	let mut ty = ty.trim();
+	loop {
+		let stripped = ["struct ", "class ", "enum ", "union ", "volatile "]
+			.iter()
+			.find_map(|prefix| ty.strip_prefix(prefix))
+			.or_else(|| ty.strip_suffix(" const").filter(|ty| !ty.ends_with('*') && !ty.ends_with('&')))
+			.or_else(|| ty.strip_suffix("const").filter(|ty| ty.ends_with('*')))
+			.or_else(|| ty.strip_suffix(" volatile"))
+			.or_else(|| ty.strip_prefix("const ").filter(|ty| !ty.ends_with('*') && !ty.ends_with('&')));
+		match stripped {
+			Some(stripped) => ty = stripped.trim(),
+			None => return ty,
+		}
+	}
+}